@@ -5,6 +5,7 @@ CREATE TABLE IF NOT EXISTS "artist" (
 	"id"	TEXT,
 	"name"	TEXT NOT NULL,
 	"name_context"	TEXT DEFAULT '',
+	"name_key"	TEXT, -- normalized name for kana/romaji-aware matching
 	"created_at"	INTEGER,
 	"updated_at"	INTEGER,
 	"status"	INTEGER NOT NULL DEFAULT 0, -- 0: normal, 1: deleted
@@ -17,6 +18,11 @@ CREATE TABLE IF NOT EXISTS "show" (
 	"name_romaji" TEXT,
 	"vintage" TEXT,
 	"s_type" TEXT,
+	"season" TEXT, -- parsed from vintage: Winter, Spring, Summer, Fall
+	"year" INTEGER, -- parsed from vintage
+	"vintage_key" INTEGER, -- year * 10 + season number (1-4), sorts chronologically
+	"name_key" TEXT, -- normalized name for kana/romaji-aware matching
+	"name_romaji_key" TEXT, -- normalized name_romaji
 	"created_at" INTEGER,
 	"updated_at" INTEGER,
 	"status" INTEGER NOT NULL DEFAULT 0 -- 0: normal, 1: deleted
@@ -27,6 +33,7 @@ CREATE TABLE IF NOT EXISTS "song" (
 	"name" TEXT NOT NULL,
 	"name_context" TEXT DEFAULT '',
 	"artist_id" TEXT NOT NULL,
+	"name_key" TEXT, -- normalized name for kana/romaji-aware matching
 	"created_at" INTEGER,
 	"updated_at" INTEGER,
 	"status" INTEGER NOT NULL DEFAULT 0 -- 0: normal, 1: deleted
@@ -38,7 +45,11 @@ CREATE TABLE IF NOT EXISTS "play_history" (
 	"song_id" TEXT NOT NULL,
 	"created_at" INTEGER NOT NULL,
 	"media_url" TEXT DEFAULT '',
-	"status" INTEGER DEFAULT 0 -- 0: normal, 1: deleted
+	"status" INTEGER DEFAULT 0, -- 0: normal, 1: deleted
+	"correct_guess" INTEGER, -- 1: guessed right, 0: not; NULL: outcome unknown
+	"wrong_guess" INTEGER, -- 1: wrong answer given, 0: not
+	"answer" TEXT DEFAULT '',
+	"session_id" TEXT -- game_session the play belongs to
 );
 
 CREATE TABLE IF NOT EXISTS "learning" (
@@ -57,13 +68,151 @@ CREATE TABLE IF NOT EXISTS "rel_show_song" (
 	"show_id" TEXT NOT NULL,
 	"song_id" TEXT NOT NULL,
 	"media_url" TEXT,
+	"song_type" TEXT, -- OP, ED or IN (opening, ending, insert)
+	"type_number" INTEGER, -- e.g. 2 for OP2; 0 for inserts
 	"created_at" INTEGER,
 	CONSTRAINT "unique_song_show_rel_show_song" UNIQUE("show_id", "song_id"),
 	FOREIGN KEY("song_id") REFERENCES "song"("id") ON DELETE CASCADE,
 	FOREIGN KEY("show_id") REFERENCES "show"("id") ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS "song_credit" (
+	"id" TEXT PRIMARY KEY,
+	"song_id" TEXT NOT NULL,
+	"artist_id" TEXT NOT NULL,
+	"role" TEXT NOT NULL, -- composer or arranger
+	"created_at" INTEGER,
+	CONSTRAINT "unique_song_artist_role_song_credit" UNIQUE("song_id", "artist_id", "role"),
+	FOREIGN KEY("song_id") REFERENCES "song"("id") ON DELETE CASCADE,
+	FOREIGN KEY("artist_id") REFERENCES "artist"("id") ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "artist_member" (
+	"id" TEXT PRIMARY KEY,
+	"group_id" TEXT NOT NULL, -- the unit or character-voice group
+	"member_id" TEXT NOT NULL,
+	"created_at" INTEGER,
+	CONSTRAINT "unique_group_member_artist_member" UNIQUE("group_id", "member_id"),
+	FOREIGN KEY("group_id") REFERENCES "artist"("id") ON DELETE CASCADE,
+	FOREIGN KEY("member_id") REFERENCES "artist"("id") ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "artist_alias" (
+	"id" TEXT PRIMARY KEY,
+	"artist_id" TEXT NOT NULL,
+	"name" TEXT NOT NULL, -- another romanization or stage name
+	"name_key" TEXT,
+	"created_at" INTEGER,
+	CONSTRAINT "unique_artist_name_artist_alias" UNIQUE("artist_id", "name"),
+	FOREIGN KEY("artist_id") REFERENCES "artist"("id") ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "show_alias" (
+	"id" TEXT PRIMARY KEY,
+	"show_id" TEXT NOT NULL,
+	"name" TEXT NOT NULL, -- e.g. an AMQ altAnimeNames entry
+	"name_key" TEXT,
+	"created_at" INTEGER,
+	CONSTRAINT "unique_show_name_show_alias" UNIQUE("show_id", "name"),
+	FOREIGN KEY("show_id") REFERENCES "show"("id") ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "game_session" (
+	"id" TEXT PRIMARY KEY,
+	"room_name" TEXT NOT NULL DEFAULT '', -- AMQ roomName
+	"started_at" INTEGER NOT NULL, -- AMQ startTime, unix timestamp
+	"created_at" INTEGER,
+	CONSTRAINT "unique_room_start_game_session" UNIQUE("room_name", "started_at")
+);
+
 -- Indexes
 CREATE INDEX IF NOT EXISTS idx_learning_song_id ON learning(song_id);
 CREATE INDEX IF NOT EXISTS idx_rel_show_song_song_id ON rel_show_song(song_id);
-CREATE INDEX IF NOT EXISTS idx_rel_show_song_show_id ON rel_show_song(show_id);
\ No newline at end of file
+CREATE INDEX IF NOT EXISTS idx_rel_show_song_show_id ON rel_show_song(show_id);
+CREATE INDEX IF NOT EXISTS idx_artist_name_key ON artist(name_key);
+CREATE INDEX IF NOT EXISTS idx_song_name_key ON song(name_key);
+CREATE INDEX IF NOT EXISTS idx_show_name_key ON show(name_key);
+CREATE INDEX IF NOT EXISTS idx_show_name_romaji_key ON show(name_romaji_key);
+CREATE INDEX IF NOT EXISTS idx_show_vintage_key ON show(vintage_key);
+CREATE INDEX IF NOT EXISTS idx_song_credit_song_id ON song_credit(song_id);
+CREATE INDEX IF NOT EXISTS idx_song_credit_artist_id ON song_credit(artist_id);
+CREATE INDEX IF NOT EXISTS idx_artist_member_member_id ON artist_member(member_id);
+CREATE INDEX IF NOT EXISTS idx_artist_alias_name_key ON artist_alias(name_key);
+CREATE INDEX IF NOT EXISTS idx_show_alias_name_key ON show_alias(name_key);
+CREATE INDEX IF NOT EXISTS idx_play_history_session_id ON play_history(session_id);
+
+-- Full-text name index (used by `find`)
+-- name_fts holds one row per indexed name field; name_fts_row maps its rowid
+-- back to the entity. Triggers keep both in sync with artist, song and show.
+CREATE TABLE IF NOT EXISTS "name_fts_row" (
+	"id" INTEGER PRIMARY KEY,
+	"entity" TEXT NOT NULL, -- artist, song or show
+	"entity_id" TEXT NOT NULL,
+	"field" TEXT NOT NULL, -- name, name_context or name_romaji
+	CONSTRAINT "unique_entity_field_name_fts_row" UNIQUE("entity", "entity_id", "field")
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS "name_fts" USING fts5(
+	"text",
+	tokenize = 'unicode61 remove_diacritics 2',
+	prefix = '2 3'
+);
+
+CREATE TRIGGER IF NOT EXISTS "artist_name_fts_insert" AFTER INSERT ON "artist" BEGIN
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('artist', new.id, 'name');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS "artist_name_fts_delete" AFTER DELETE ON "artist" BEGIN
+	DELETE FROM name_fts WHERE rowid IN (SELECT id FROM name_fts_row WHERE entity = 'artist' AND entity_id = old.id);
+	DELETE FROM name_fts_row WHERE entity = 'artist' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS "artist_name_fts_update" AFTER UPDATE OF id, name ON "artist" BEGIN
+	DELETE FROM name_fts WHERE rowid IN (SELECT id FROM name_fts_row WHERE entity = 'artist' AND entity_id = old.id);
+	DELETE FROM name_fts_row WHERE entity = 'artist' AND entity_id = old.id;
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('artist', new.id, 'name');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS "song_name_fts_insert" AFTER INSERT ON "song" BEGIN
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('song', new.id, 'name');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name);
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('song', new.id, 'name_context');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name_context);
+END;
+
+CREATE TRIGGER IF NOT EXISTS "song_name_fts_delete" AFTER DELETE ON "song" BEGIN
+	DELETE FROM name_fts WHERE rowid IN (SELECT id FROM name_fts_row WHERE entity = 'song' AND entity_id = old.id);
+	DELETE FROM name_fts_row WHERE entity = 'song' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS "song_name_fts_update" AFTER UPDATE OF id, name, name_context ON "song" BEGIN
+	DELETE FROM name_fts WHERE rowid IN (SELECT id FROM name_fts_row WHERE entity = 'song' AND entity_id = old.id);
+	DELETE FROM name_fts_row WHERE entity = 'song' AND entity_id = old.id;
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('song', new.id, 'name');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name);
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('song', new.id, 'name_context');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name_context);
+END;
+
+CREATE TRIGGER IF NOT EXISTS "show_name_fts_insert" AFTER INSERT ON "show" BEGIN
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('show', new.id, 'name');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name);
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('show', new.id, 'name_romaji');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name_romaji);
+END;
+
+CREATE TRIGGER IF NOT EXISTS "show_name_fts_delete" AFTER DELETE ON "show" BEGIN
+	DELETE FROM name_fts WHERE rowid IN (SELECT id FROM name_fts_row WHERE entity = 'show' AND entity_id = old.id);
+	DELETE FROM name_fts_row WHERE entity = 'show' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS "show_name_fts_update" AFTER UPDATE OF id, name, name_romaji ON "show" BEGIN
+	DELETE FROM name_fts WHERE rowid IN (SELECT id FROM name_fts_row WHERE entity = 'show' AND entity_id = old.id);
+	DELETE FROM name_fts_row WHERE entity = 'show' AND entity_id = old.id;
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('show', new.id, 'name');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name);
+	INSERT INTO name_fts_row (entity, entity_id, field) VALUES ('show', new.id, 'name_romaji');
+	INSERT INTO name_fts (rowid, text) VALUES (last_insert_rowid(), new.name_romaji);
+END;
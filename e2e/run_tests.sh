@@ -170,6 +170,29 @@ assert_exit_code "search exits 0" 0 "$ec"
 assert_json_field "search finds 1 result" "$out" '.results | length' "1"
 assert_json_field "search result name" "$out" '.results[0].name' "FindMe"
 
+out=$(jankenoboe search artist --term '{"name":{"value":"Me","match":"ends-with"}}' --fields name --order-by name:desc --limit 1)
+assert_json_field "search paged total" "$out" '.total' "2"
+assert_json_field "search paged first row" "$out" '.results[0].name' "NotMe"
+assert_json_field "search paged next_offset" "$out" '.next_offset' "1"
+
+out=$(jankenoboe search artist --term '{"any":[{"name":{"value":"FindMe"}},{"not":{"name":{"value":"me","match":"contains"}}}]}' --fields name)
+assert_json_field "search any/not group" "$out" '.results[0].name' "FindMe"
+assert_json_field "search any/not group count" "$out" '.total' "1"
+
+artist_id=$(jankenoboe search artist --term '{"name":{"value":"FindMe"}}' --fields id | jq -r '.results[0].id')
+jankenoboe create song --data "{\"name\":\"JoinSong\",\"artist_id\":\"$artist_id\"}" > /dev/null
+out=$(jankenoboe search song --term '{"artist.name":{"value":"findme","match":"exact-i"},"learning.song_id":{"match":"is-null"}}' --fields name,artist.name)
+assert_json_field "joined search finds song" "$out" '.results[0].name' "JoinSong"
+assert_json_field "joined search artist field" "$out" '.results[0]["artist.name"]' "FindMe"
+
+out=$(jankenoboe find "find")
+assert_json_field "find ranks artist by name prefix" "$out" '.results[0].name' "FindMe"
+assert_json_field "find result type" "$out" '.results[0].type' "artist"
+
+jankenoboe create show --data '{"name":"K-On!","name_romaji":"Keion!","vintage":"Spring 2009"}' > /dev/null
+out=$(jankenoboe search show --term '{"name_romaji":{"value":"けいおん","match":"normalized"}}' --fields name)
+assert_json_field "normalized search matches kana with romaji" "$out" '.results[0].name' "K-On!"
+
 echo ""
 
 # ---- 5. Duplicates ----
@@ -322,6 +345,14 @@ assert_json_field "search rel_show_song finds link" "$out" '.results | length' "
 assert_json_field "search rel_show_song show_id" "$out" '.results[0].show_id' "$SH_ID"
 assert_json_field "search rel_show_song song_id" "$out" '.results[0].song_id' "$S_ID"
 
+# Update rel_show_song media_url by composite key
+out=$(jankenoboe update rel_show_song "$SH_ID:$S_ID" --data '{"media_url":"https://example.com/video2.mp4"}')
+ec=$?
+assert_exit_code "update rel_show_song exits 0" 0 "$ec"
+assert_json_field "update rel_show_song returns key" "$out" '.id' "$SH_ID:$S_ID"
+out=$(jankenoboe search rel_show_song --fields media_url --term "{\"show_id\":{\"value\":\"$SH_ID\"}}")
+assert_json_field "rel_show_song media_url updated" "$out" '.results[0].media_url' "https://example.com/video2.mp4"
+
 # Create play_history
 out=$(jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S_ID\",\"media_url\":\"https://example.com/video1.mp4\"}")
 ec=$?
@@ -366,6 +397,18 @@ ec=$?
 assert_exit_code "delete song exits 0" 0 "$ec"
 assert_output_contains "delete song response" "$out" "deleted"
 
+# Delete artist with a song: refused without --cascade, previewed, then cascaded
+s2_out=$(jankenoboe create song --data "{\"name\":\"CascadeSong\",\"artist_id\":\"$A1_ID\"}")
+S2_ID=$(echo "$s2_out" | jq -r '.id')
+jankenoboe delete artist "$A1_ID" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "delete artist with songs exits 1" 1 "$ec"
+out=$(jankenoboe delete artist "$A1_ID" --preview)
+assert_json_field "delete preview lists song" "$out" '.dependents.song[0]' "$S2_ID"
+out=$(jankenoboe delete artist "$A1_ID" --cascade)
+ec=$?
+assert_exit_code "delete artist --cascade exits 0" 0 "$ec"
+assert_json_field "delete cascade removed song" "$out" '.dependents_deleted.song' "1"
+
 echo ""
 
 # ---- 12. Search match modes ----
@@ -436,25 +479,36 @@ assert_json_field "search song multi-field artist_id" "$out" '.results[0].artist
 
 echo ""
 
-# ---- 14. Soft-delete (status update) ----
+# ---- 14. Soft-delete / restore ----
 printf "${YELLOW}--- Soft Delete ---${NC}\n"
 reset_db
 
 a_out=$(jankenoboe create artist --data '{"name":"SoftDelArtist"}')
 A_ID=$(echo "$a_out" | jq -r '.id')
 
-# Soft-delete via status=1
-out=$(jankenoboe update artist "$A_ID" --data '{"status": 1}')
+out=$(jankenoboe soft-delete artist "$A_ID")
 ec=$?
 assert_exit_code "soft-delete artist exits 0" 0 "$ec"
-assert_output_contains "soft-delete response" "$out" "updated"
+assert_json_field "soft-delete response" "$out" '.soft_deleted' "true"
 
-# Verify status updated
+# Hidden from reads by default
 out=$(jankenoboe get artist "$A_ID" --fields id,name,status)
 ec=$?
 assert_exit_code "get soft-deleted artist exits 0" 0 "$ec"
+assert_json_field "soft-deleted artist hidden from get" "$out" '.results | length' "0"
+
+out=$(jankenoboe get artist "$A_ID" --fields id,name,status --include-deleted)
 assert_json_field "soft-deleted status is 1" "$out" '.results[0].status' "1"
 
+out=$(jankenoboe search artist --fields id --term '{"name":{"value":"SoftDelArtist"}}')
+assert_json_field "soft-deleted artist hidden from search" "$out" '.results | length' "0"
+
+out=$(jankenoboe restore artist "$A_ID")
+ec=$?
+assert_exit_code "restore artist exits 0" 0 "$ec"
+out=$(jankenoboe get artist "$A_ID" --fields status)
+assert_json_field "restored status is 0" "$out" '.results[0].status' "0"
+
 echo ""
 
 # ---- 15. Learning lifecycle (level up, level down, graduate) ----
@@ -580,6 +634,8 @@ S_ID=$(echo "$s_out" | jq -r '.id')
 sh_out=$(jankenoboe create show --data '{"name":"ReviewShow","vintage":"Winter 2024","s_type":"TV"}')
 SH_ID=$(echo "$sh_out" | jq -r '.id')
 jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S_ID\",\"media_url\":\"https://example.com/review.mp4\"}" > /dev/null
+# AMQ type 1 (Opening), typeNumber 2
+jankenoboe create rel_show_song --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S_ID\",\"song_type\":1,\"type_number\":2}" > /dev/null
 
 # Add to learning (level 0 is immediately due with 5-min warm-up after creation)
 jankenoboe learning-batch --song-ids "$S_ID" > /dev/null
@@ -595,6 +651,14 @@ ec=$?
 assert_exit_code "learning-due with offset exits 0" 0 "$ec"
 assert_json_field "learning-due with offset count 1" "$out" '.count' "1"
 
+# --song-type keeps only songs linked with that type
+out=$(jankenoboe learning-due --limit 10 --offset 400 --song-type OP)
+assert_json_field "learning-due --song-type OP count 1" "$out" '.count' "1"
+out=$(jankenoboe learning-due --limit 10 --offset 400 --song-type ED)
+assert_json_field "learning-due --song-type ED count 0" "$out" '.count' "0"
+jankenoboe learning-due --song-type OST 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "learning-due unknown song type exits 1" 1 "$ec"
+
 # Wait briefly, then generate review
 # Note: Level 0 songs have a 300-second warm-up, so we manually set last_level_up_at in the past
 # by using learning-due first, and then generating the review
@@ -613,6 +677,8 @@ else
   FAIL=$((FAIL + 1))
   printf "${RED}  ✗ HTML report file not found${NC}\n"
 fi
+jankenoboe learning-song-review --output /tmp/e2e_review_song_type.html --offset 400 > /dev/null
+assert_output_contains "HTML report shows song type label" "$(cat /tmp/e2e_review_song_type.html)" '"songType":"OP2"'
 
 echo ""
 
@@ -890,6 +956,24 @@ assert_output_contains "learning-batch empty shows error" "$stderr" "error"
 jankenoboe bulk-reassign 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
 assert_exit_code "bulk-reassign no args exits non-zero" 1 "$ec"
 
+# Create song referencing a nonexistent artist
+jankenoboe create song --data '{"name":"x","artist_id":"nonexistent-id"}' 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+stderr=$(cat /tmp/e2e_stderr)
+assert_exit_code "create song with missing artist exits 1" 1 "$ec"
+assert_output_contains "create song with missing artist names field" "$stderr" "artist_id not found"
+
+# fsck on a clean database
+out=$(jankenoboe fsck)
+ec=$?
+assert_exit_code "fsck exits 0" 0 "$ec"
+assert_json_field "fsck finds no orphans" "$out" '.orphan_count' "0"
+
+# doctor on a clean database
+out=$(jankenoboe doctor)
+ec=$?
+assert_exit_code "doctor exits 0" 0 "$ec"
+assert_json_field "doctor reports ok" "$out" '.ok' "true"
+
 echo ""
 
 # ---- 25. URL Percent-Encoding ----
@@ -997,6 +1081,24 @@ assert_json_field "shows-by-artist-ids nonexistent count" "$out" '.count' "0"
 jankenoboe shows-by-artist-ids --artist-ids "" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
 assert_exit_code "shows-by-artist-ids empty exits 1" 1 "$ec"
 
+# Inverse: songs of a show, and shows of a season
+out=$(jankenoboe songs-by-show-ids --show-ids "$SH1_ID")
+ec=$?
+assert_exit_code "songs-by-show-ids exits 0" 0 "$ec"
+assert_json_field "songs-by-show-ids count" "$out" '.count' "2"
+assert_json_field "songs-by-show-ids learning_status" "$out" '.results[0].learning_status' "none"
+
+out=$(jankenoboe shows-by-vintage --year-range 2024)
+assert_json_field "shows-by-vintage year is chronological" "$out" '[.results[].show_name] | join(",")' "Show Alpha,Show Beta"
+out=$(jankenoboe shows-by-vintage --season "Fall 2024")
+assert_json_field "shows-by-vintage season" "$out" '.results[0].show_name' "Show Beta"
+jankenoboe shows-by-vintage --season "Fall" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "shows-by-vintage invalid season exits 1" 1 "$ec"
+out=$(jankenoboe search show --term '{"year":{"value":2024,"match":"gte"}}' --fields name,season --order-by vintage_key:desc)
+assert_json_field "search shows by stored vintage_key" "$out" '.results[0].season' "Fall"
+jankenoboe create show --data '{"name":"Undated","vintage":"sometime"}' 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "create show with unparseable vintage exits 1" 1 "$ec"
+
 echo ""
 
 # ---- 27. Songs by artist IDs ----
@@ -1040,6 +1142,53 @@ assert_json_field "songs-by-artist-ids nonexistent count" "$out" '.count' "0"
 jankenoboe songs-by-artist-ids --artist-ids "" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
 assert_exit_code "songs-by-artist-ids empty exits 1" 1 "$ec"
 
+# Composer credits: SongArtistBeta composed AlphaSong1 and arranged BetaSong1
+ALPHA1_ID=$(jankenoboe search song --term '{"name":{"value":"AlphaSong1"}}' --fields id | jq -r '.results[0].id')
+BETA1_ID=$(jankenoboe search song --term '{"name":{"value":"BetaSong1"}}' --fields id | jq -r '.results[0].id')
+out=$(jankenoboe create song_credit --data "{\"song_id\":\"$ALPHA1_ID\",\"artist_id\":\"$SA2_ID\",\"role\":\"Composer\"}")
+ec=$?
+assert_exit_code "create song_credit exits 0" 0 "$ec"
+jankenoboe create song_credit --data "{\"song_id\":\"$BETA1_ID\",\"artist_id\":\"$SA2_ID\",\"role\":\"arranger\"}" > /dev/null
+out=$(jankenoboe songs-by-composer-ids --composer-ids "$SA2_ID")
+assert_json_field "songs-by-composer-ids composer only" "$out" '[.results[] | "\(.role):\(.song_name):\(.artist_name)"] | join(",")' "composer:AlphaSong1:SongArtistAlpha"
+out=$(jankenoboe songs-by-composer-ids --composer-ids "$SA2_ID" --include-arrangers)
+assert_json_field "songs-by-composer-ids with arrangers" "$out" '.count' "2"
+jankenoboe create song_credit --data "{\"song_id\":\"$ALPHA1_ID\",\"artist_id\":\"$SA2_ID\",\"role\":\"lyricist\"}" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "create song_credit invalid role exits 1" 1 "$ec"
+
+# Group membership: SongArtistAlpha is a member of a group that has its own song
+GRP_ID=$(jankenoboe create artist --data '{"name":"SongArtistUnit"}' | jq -r '.id')
+jankenoboe create song --data "{\"name\":\"UnitSong\",\"artist_id\":\"$GRP_ID\"}" > /dev/null
+out=$(jankenoboe add-member --group-id "$GRP_ID" --member-ids "$SA1_ID")
+ec=$?
+assert_exit_code "add-member exits 0" 0 "$ec"
+assert_json_field "add-member added" "$out" '.added | length' "1"
+out=$(jankenoboe songs-by-artist-ids --artist-ids "$SA1_ID" --include-groups)
+assert_json_field "songs-by-artist-ids --include-groups count" "$out" '.count' "3"
+assert_json_field "songs-by-artist-ids group song via member" "$out" '[.results[] | select(.song_name == "UnitSong") | .via_artist_id] | .[0]' "$SA1_ID"
+jankenoboe add-member --group-id "$GRP_ID" --member-ids "$GRP_ID" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "add-member self exits 1" 1 "$ec"
+out=$(jankenoboe remove-member --group-id "$GRP_ID" --member-ids "$SA1_ID")
+assert_json_field "remove-member removed" "$out" '.removed | length' "1"
+out=$(jankenoboe songs-by-artist-ids --artist-ids "$SA1_ID" --include-groups)
+assert_json_field "songs-by-artist-ids after remove-member" "$out" '.count' "2"
+
+# Aliases: an alias matching another artist's name groups them in duplicates
+out=$(jankenoboe create artist_alias --data "{\"artist_id\":\"$SA1_ID\",\"name\":\"songartistunit\"}")
+ec=$?
+assert_exit_code "create artist_alias exits 0" 0 "$ec"
+ALIAS_ID=$(echo "$out" | jq -r '.id')
+out=$(jankenoboe duplicates artist)
+assert_json_field "duplicates artist via alias" "$out" '[.duplicates[].records[] | select(.alias != null) | .alias] | .[0]' "songartistunit"
+out=$(jankenoboe promote-alias artist_alias "$ALIAS_ID")
+ec=$?
+assert_exit_code "promote-alias exits 0" 0 "$ec"
+assert_json_field "promote-alias previous name" "$out" '.previous_name' "SongArtistAlpha"
+out=$(jankenoboe get artist "$SA1_ID" --fields name)
+assert_json_field "promote-alias renamed artist" "$out" '.results[0].name' "songartistunit"
+jankenoboe create artist_alias --data "{\"artist_id\":\"$SA1_ID\",\"name\":\" \"}" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "create artist_alias blank name exits 1" 1 "$ec"
+
 echo ""
 
 # ---- 28. Learning song stats ----
@@ -1076,6 +1225,91 @@ assert_json_field "learning-song-stats second days_spent" "$out" '.results[1].da
 jankenoboe learning-song-stats --song-ids "" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
 assert_exit_code "learning-song-stats empty exits 1" 1 "$ec"
 
+# Play outcomes and accuracy analytics
+SH_ID=$(jankenoboe create show --data '{"name":"StatsShow","vintage":"Winter 2024"}' | jq -r '.id')
+jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S1_ID\",\"correct_guess\":true,\"wrong_guess\":false,\"answer\":\"StatsShow\"}" > /dev/null
+jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S2_ID\",\"correct_guess\":false,\"wrong_guess\":true}" > /dev/null
+out=$(jankenoboe play-stats)
+ec=$?
+assert_exit_code "play-stats exits 0" 0 "$ec"
+assert_json_field "play-stats summary hit_rate" "$out" '.summary.hit_rate' "0.5"
+assert_json_field "play-stats most-missed first" "$out" '.results[0].song_name' "StatsSong2"
+out=$(jankenoboe play-stats --by month)
+assert_json_field "play-stats by month" "$out" '.count' "1"
+jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S1_ID\",\"correct_guess\":true,\"wrong_guess\":true}" 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "play_history both outcomes exits 1" 1 "$ec"
+jankenoboe play-stats --by year 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "play-stats invalid grouping exits 1" 1 "$ec"
+
+# Suggestions: a played song outside learning, ready for learning-batch
+S3_ID=$(jankenoboe create song --data "{\"name\":\"StatsSong3\",\"artist_id\":\"$A_ID\"}" | jq -r '.id')
+jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S3_ID\",\"correct_guess\":false,\"wrong_guess\":false}" > /dev/null
+out=$(jankenoboe learning-suggest --limit 5)
+ec=$?
+assert_exit_code "learning-suggest exits 0" 0 "$ec"
+assert_json_field "learning-suggest skips active learning" "$out" '.song_ids' "$S3_ID"
+out=$(jankenoboe learning-batch --song-ids "$(echo "$out" | jq -r '.song_ids')")
+assert_json_field "learning-suggest ids feed learning-batch" "$out" '.created_ids | length' "1"
+jankenoboe learning-suggest --limit 0 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "learning-suggest limit 0 exits 1" 1 "$ec"
+
+# Game sessions: plays grouped per game, listed, then deleted with their plays
+GS_ID=$(jankenoboe create game_session --data '{"room_name":"StatsRoom","started_at":1700000000}' | jq -r '.id')
+jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S1_ID\",\"correct_guess\":true,\"wrong_guess\":false,\"session_id\":\"$GS_ID\"}" > /dev/null
+jankenoboe create play_history --data "{\"show_id\":\"$SH_ID\",\"song_id\":\"$S2_ID\",\"correct_guess\":false,\"wrong_guess\":true,\"session_id\":\"$GS_ID\"}" > /dev/null
+out=$(jankenoboe game-sessions)
+ec=$?
+assert_exit_code "game-sessions exits 0" 0 "$ec"
+assert_json_field "game-sessions hit_rate" "$out" '.results[0].hit_rate' "0.5"
+out=$(jankenoboe songs-by-session-ids --session-ids "$GS_ID")
+assert_json_field "songs-by-session-ids lists the session's plays" "$out" '[.results[].song_name] | sort | join(",")' "StatsSong1,StatsSong2"
+jankenoboe create game_session --data '{"room_name":"StatsRoom","started_at":1700000000}' 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "duplicate game_session exits 1" 1 "$ec"
+out=$(jankenoboe delete game_session "$GS_ID" --cascade)
+assert_json_field "delete game_session removes its plays" "$out" '.dependents_deleted.play_history' "2"
+out=$(jankenoboe play-stats)
+assert_json_field "plays outside the session remain" "$out" '.summary.plays' "3"
+
+# Export
+EXPORT_DIR=$(mktemp -d)
+out=$(jankenoboe export --output "$EXPORT_DIR")
+ec=$?
+assert_exit_code "export exits 0" 0 "$ec"
+assert_json_field "export writes the play history file" "$out" '.tables.play_history.rows' "3"
+out=$(jq -s 'length' "$EXPORT_DIR/play_history.jsonl")
+assert_json_field "export play_history.jsonl has one line per row" "$out" '.' "3"
+out=$(jankenoboe export --tables song --format csv --output "$EXPORT_DIR" --term "{\"artist_id\":{\"value\":\"$A_ID\"}}")
+assert_json_field "export csv with term" "$out" '.tables.song.file' "$EXPORT_DIR/song.csv"
+assert_output_contains "export csv has a header row" "$(head -1 "$EXPORT_DIR/song.csv")" "id,name,name_context,artist_id"
+
+# Import the export into a fresh database
+IMPORT_DB=$(mktemp)
+sqlite3 "$IMPORT_DB" < "$INIT_SQL"
+out=$(JANKENOBOE_DB="$IMPORT_DB" jankenoboe import-dump --input "$EXPORT_DIR" --tables artist,song,show,play_history)
+ec=$?
+assert_exit_code "import-dump exits 0" 0 "$ec"
+assert_json_field "import-dump inserts the play history" "$out" '.tables.play_history.inserted' "3"
+out=$(JANKENOBOE_DB="$IMPORT_DB" jankenoboe import-dump --input "$EXPORT_DIR" --tables artist --on-conflict newest)
+assert_json_field "import-dump newest skips unchanged rows" "$out" '.updated' "0"
+out=$(JANKENOBOE_DB="$IMPORT_DB" jankenoboe get song "$S1_ID" --fields name)
+assert_json_field "imported song keeps its id" "$out" '.results[0].name' "StatsSong1"
+
+# Sync with the imported copy after changing both sides
+JANKENOBOE_DB="$IMPORT_DB" jankenoboe create artist --data '{"name":"SyncOnlyOther"}' > /dev/null
+jankenoboe create artist --data '{"name":"SyncOnlyMain"}' > /dev/null
+out=$(jankenoboe sync --other "$IMPORT_DB")
+ec=$?
+assert_exit_code "sync exits 0" 0 "$ec"
+assert_json_field "sync copies the other artist to main" "$out" '.tables.artist.copied_to_main' "1"
+assert_json_field "sync copies the main artist to other" "$out" '.tables.artist.copied_to_other' "1"
+assert_json_field "sync reports no conflicts" "$out" '.conflict_count' "0"
+out=$(jankenoboe search artist --term '{"name":{"value":"SyncOnlyOther"}}' --fields id)
+assert_json_field "synced artist is searchable" "$out" '.count' "1"
+jankenoboe sync --other /nonexistent/other.db 2>/tmp/e2e_stderr 1>/dev/null; ec=$?
+assert_exit_code "sync with a missing file exits 1" 1 "$ec"
+rm -f "$IMPORT_DB"
+rm -rf "$EXPORT_DIR"
+
 echo ""
 
 # ---- 29. Uninstall verification ----
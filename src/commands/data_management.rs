@@ -6,7 +6,10 @@ use crate::easing::{MAX_LEVEL, generate_level_up_path_json};
 use crate::encoding::url_decode;
 use crate::error::AppError;
 use crate::models;
+use crate::normalize;
+use crate::song_type;
 use crate::table_config;
+use crate::vintage;
 
 // ---------------------------------------------------------------------------
 // create <table> --data
@@ -28,6 +31,19 @@ pub fn cmd_create(conn: &mut Connection, table: &str, data_json: &str) -> Result
             )));
         }
     }
+    if table == "song_credit" {
+        validate_song_credit(&mut data)?;
+    }
+    if models::ALIAS_TABLES.contains(&table) {
+        validate_alias(table, &data, true)?;
+    }
+    if table == "play_history" {
+        validate_play_outcome(&mut data)?;
+    }
+    if table == "game_session" {
+        validate_game_session(&data, true)?;
+    }
+    validate_references(conn, table, &data)?;
 
     let now = models::now_unix();
 
@@ -57,6 +73,10 @@ pub fn cmd_create(conn: &mut Connection, table: &str, data_json: &str) -> Result
             columns.push(format!("\"{key}\""));
             placeholders.push(format!("@{param_key}"));
             let (arg_def, param_val) = json_value_to_param(val);
+            for (col, value) in derived_columns(table, key, &param_val)? {
+                columns.push(format!("\"{col}\""));
+                placeholders.push(bind_derived(col, value, &mut args, &mut param_values));
+            }
             args.insert(param_key.clone(), arg_def);
             param_values.insert(param_key, param_val);
         }
@@ -168,34 +188,54 @@ fn create_rel_show_song(
         .ok_or_else(|| AppError::InvalidParameter("song_id is required".into()))?;
     let media_url = data.get("media_url").and_then(|v| v.as_str()).unwrap_or("");
 
+    let mut args = Map::new();
+    let mut params = Map::new();
+    for (col, val) in [
+        ("show_id", show_id),
+        ("song_id", song_id),
+        ("media_url", media_url),
+    ] {
+        args.insert(col.to_string(), json!({}));
+        params.insert(col.to_string(), json!(val));
+    }
+    args.insert("now".to_string(), json!({"type": "integer"}));
+    params.insert("now".to_string(), json!(now));
+    let mut type_columns = String::new();
+    let mut type_values = String::new();
+    for (col, value) in rel_type_fields(data)? {
+        let placeholder = bind_derived(col, value, &mut args, &mut params);
+        type_columns.push_str(&format!(", {col}"));
+        type_values.push_str(&format!(", {placeholder}"));
+    }
+
     let query_json = json!({
         "create_rel": {
-            "query": "INSERT INTO rel_show_song (show_id, song_id, media_url, created_at) VALUES (@show_id, @song_id, @media_url, @now)",
-            "args": {
-                "show_id": {},
-                "song_id": {},
-                "media_url": {},
-                "now": {"type": "integer"}
-            }
+            "query": format!(
+                "INSERT INTO rel_show_song (show_id, song_id, media_url{type_columns}, created_at) \
+                 VALUES (@show_id, @song_id, @media_url{type_values}, @now)"
+            ),
+            "args": args
         }
     });
 
     let queries = QueryDefinitions::from_json(query_json)
         .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
 
-    let params = json!({
-        "show_id": show_id,
-        "song_id": song_id,
-        "media_url": media_url,
-        "now": now
-    });
-
-    jankensqlhub::query_run_sqlite(conn, &queries, "create_rel", &params)
+    jankensqlhub::query_run_sqlite(conn, &queries, "create_rel", &json!(params))
         .map_err(AppError::from)?;
 
     Ok(json!({"id": format!("{show_id}:{song_id}")}))
 }
 
+/// Split a `show_id:song_id` rel_show_song key.
+fn parse_rel_key(id: &str) -> Result<(&str, &str), AppError> {
+    id.split_once(':').ok_or_else(|| {
+        AppError::InvalidParameter(format!(
+            "rel_show_song id must be show_id:song_id, got: {id}"
+        ))
+    })
+}
+
 // ---------------------------------------------------------------------------
 // update <table> <id> --data
 // ---------------------------------------------------------------------------
@@ -223,6 +263,21 @@ pub fn cmd_update(
             )));
         }
     }
+    if models::ALIAS_TABLES.contains(&table) {
+        validate_alias(table, &data, false)?;
+    }
+    if table == "play_history" {
+        validate_play_outcome(&mut data)?;
+    }
+    if table == "game_session" {
+        validate_game_session(&data, false)?;
+    }
+    validate_references(conn, table, &data)?;
+
+    // rel_show_song has no id column - addressed by its composite key
+    if table == "rel_show_song" {
+        return update_rel_show_song(conn, id, &data);
+    }
 
     let now = models::now_unix();
     let level_changed = table == "learning" && data.contains_key("level");
@@ -237,6 +292,10 @@ pub fn cmd_update(
             let param_key = format!("p_{key}");
             set_parts.push(format!("\"{key}\"=@{param_key}"));
             let (arg_def, param_val) = json_value_to_param(val);
+            for (col, value) in derived_columns(table, key, &param_val)? {
+                let placeholder = bind_derived(col, value, &mut args, &mut param_values);
+                set_parts.push(format!("\"{col}\"={placeholder}"));
+            }
             args.insert(param_key.clone(), arg_def);
             param_values.insert(param_key, param_val);
         }
@@ -305,11 +364,125 @@ pub fn cmd_update(
     Ok(json!({"updated": true}))
 }
 
+/// Update a rel_show_song link addressed by its `show_id:song_id` key.
+/// Changing `show_id` or `song_id` re-keys the link, so the new key must be free.
+fn update_rel_show_song(
+    conn: &mut Connection,
+    key: &str,
+    data: &Map<String, Value>,
+) -> Result<Value, AppError> {
+    let (show_id, song_id) = parse_rel_key(key)?;
+
+    let mut set_parts: Vec<String> = Vec::new();
+    let mut args = serde_json::Map::new();
+    let mut params = serde_json::Map::new();
+    params.insert("show_id".to_string(), json!(show_id));
+    params.insert("song_id".to_string(), json!(song_id));
+    for field in ["show_id", "song_id", "media_url"] {
+        let Some(val) = data.get(field) else {
+            continue;
+        };
+        let val = val.as_str().ok_or_else(|| {
+            AppError::InvalidParameter(format!("{field} must be a string for rel_show_song"))
+        })?;
+        let param_key = format!("p_{field}");
+        set_parts.push(format!("\"{field}\"=@{param_key}"));
+        args.insert(param_key.clone(), json!({}));
+        params.insert(param_key, json!(val));
+    }
+    for (col, value) in rel_type_fields(data)? {
+        let placeholder = bind_derived(col, value, &mut args, &mut params);
+        set_parts.push(format!("\"{col}\"={placeholder}"));
+    }
+    args.insert("show_id".to_string(), json!({}));
+    args.insert("song_id".to_string(), json!({}));
+
+    let new_show_id = params
+        .get("p_show_id")
+        .and_then(Value::as_str)
+        .unwrap_or(show_id);
+    let new_song_id = params
+        .get("p_song_id")
+        .and_then(Value::as_str)
+        .unwrap_or(song_id);
+    let new_key = format!("{new_show_id}:{new_song_id}");
+
+    let query_json = json!({
+        "check_rel_exists": {
+            "query": "SELECT show_id FROM rel_show_song WHERE show_id=@show_id AND song_id=@song_id",
+            "returns": ["show_id"],
+            "args": {"show_id": {}, "song_id": {}}
+        },
+        "update_rel": {
+            "query": format!(
+                "UPDATE rel_show_song SET {} WHERE show_id=@show_id AND song_id=@song_id",
+                set_parts.join(", ")
+            ),
+            "args": args
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let check = jankensqlhub::query_run_sqlite(
+        conn,
+        &queries,
+        "check_rel_exists",
+        &json!({"show_id": show_id, "song_id": song_id}),
+    )
+    .map_err(AppError::from)?;
+    if check.data.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Record not found: rel_show_song/{key}"
+        )));
+    }
+
+    if new_key != key {
+        let taken = jankensqlhub::query_run_sqlite(
+            conn,
+            &queries,
+            "check_rel_exists",
+            &json!({"show_id": new_show_id, "song_id": new_song_id}),
+        )
+        .map_err(AppError::from)?;
+        if !taken.data.is_empty() {
+            return Err(AppError::InvalidParameter(format!(
+                "rel_show_song link already exists: {new_key}"
+            )));
+        }
+    }
+
+    jankensqlhub::query_run_sqlite(conn, &queries, "update_rel", &json!(params))
+        .map_err(AppError::from)?;
+
+    Ok(json!({"updated": true, "id": new_key}))
+}
+
 // ---------------------------------------------------------------------------
-// delete <table> <id>
+// delete <table> <id> [--cascade] [--preview]
 // ---------------------------------------------------------------------------
 
-pub fn cmd_delete(conn: &mut Connection, table: &str, id: &str) -> Result<Value, AppError> {
+/// Dependent tables in the order they are reported, and the reverse of the
+/// order they are deleted in.
+const DEPENDENT_TABLES: &[&str] = &[
+    "song",
+    "rel_show_song",
+    "play_history",
+    "learning",
+    "song_credit",
+    "artist_member",
+    "artist_alias",
+    "show_alias",
+];
+
+pub fn cmd_delete(
+    conn: &mut Connection,
+    table: &str,
+    id: &str,
+    cascade: bool,
+    preview: bool,
+) -> Result<Value, AppError> {
     models::validate_table(table, models::DELETE_TABLES)?;
 
     let query_json = json!({
@@ -317,26 +490,355 @@ pub fn cmd_delete(conn: &mut Connection, table: &str, id: &str) -> Result<Value,
             "query": "SELECT id FROM #[table] WHERE id=@id",
             "returns": ["id"],
             "args": {
-                "table": {"enum": table_config::build_table_enum(models::DELETE_TABLES)},
+                "table": {"enum": ["artist", "show", "song", "play_history", "song_credit", "artist_alias", "show_alias", "game_session"]},
                 "id": {}
             }
         },
+        "check_rel_exists": {
+            "query": "SELECT show_id FROM rel_show_song WHERE show_id=@show_id AND song_id=@song_id",
+            "returns": ["show_id"],
+            "args": {"show_id": {}, "song_id": {}}
+        },
         "delete_by_id": {
             "query": "DELETE FROM #[table] WHERE id=@id",
             "args": {
-                "table": {"enum": table_config::build_table_enum(models::DELETE_TABLES)},
+                "table": {"enum": ["artist", "show", "song", "play_history", "song_credit", "artist_alias", "show_alias", "game_session"]},
                 "id": {}
             }
+        },
+        "delete_rel": {
+            "query": "DELETE FROM rel_show_song WHERE show_id=@show_id AND song_id=@song_id",
+            "args": {"show_id": {}, "song_id": {}}
         }
     });
 
     let queries = QueryDefinitions::from_json(query_json)
         .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
 
-    let params = json!({ "table": table, "id": id });
+    // rel_show_song has no id column - addressed by its composite key
+    let rel_key = if table == "rel_show_song" {
+        let (show_id, song_id) = parse_rel_key(id)?;
+        Some(json!({"show_id": show_id, "song_id": song_id}))
+    } else {
+        None
+    };
 
-    let check = jankensqlhub::query_run_sqlite(conn, &queries, "check_exists", &params)
+    let tx = conn.transaction()?;
+
+    let check = match &rel_key {
+        Some(key) => {
+            jankensqlhub::query_run_sqlite_with_transaction(&tx, &queries, "check_rel_exists", key)
+        }
+        None => jankensqlhub::query_run_sqlite_with_transaction(
+            &tx,
+            &queries,
+            "check_exists",
+            &json!({ "table": table, "id": id }),
+        ),
+    }
+    .map_err(AppError::from)?;
+
+    if check.data.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Record not found: {table}/{id}"
+        )));
+    }
+
+    let dependents = collect_dependents(&tx, table, id)?;
+    let dependent_count: usize = dependents
+        .values()
+        .map(|ids| ids.as_array().map_or(0, Vec::len))
+        .sum();
+
+    if preview {
+        return Ok(json!({
+            "preview": true,
+            "table": table,
+            "id": id,
+            "dependent_count": dependent_count,
+            "dependents": dependents
+        }));
+    }
+
+    if dependent_count > 0 && !cascade {
+        return Err(AppError::InvalidParameter(format!(
+            "{table}/{id} has {dependent_count} dependent row(s); \
+             use --cascade to delete them too (--preview lists them)"
+        )));
+    }
+
+    for dep_table in DEPENDENT_TABLES.iter().rev() {
+        delete_dependents(&tx, dep_table, &dependents[*dep_table])?;
+    }
+
+    match &rel_key {
+        Some(key) => {
+            jankensqlhub::query_run_sqlite_with_transaction(&tx, &queries, "delete_rel", key)
+        }
+        None => jankensqlhub::query_run_sqlite_with_transaction(
+            &tx,
+            &queries,
+            "delete_by_id",
+            &json!({ "table": table, "id": id }),
+        ),
+    }
+    .map_err(AppError::from)?;
+
+    tx.commit()?;
+
+    let deleted_counts: Map<String, Value> = dependents
+        .iter()
+        .map(|(dep_table, ids)| (dep_table.clone(), json!(ids.as_array().map_or(0, Vec::len))))
+        .collect();
+
+    Ok(json!({"deleted": true, "dependents_deleted": deleted_counts}))
+}
+
+/// List every row that depends on a record, keyed by table (all
+/// `DEPENDENT_TABLES` keys are present). Deleting an artist reaches through its
+/// songs to their links, plays, learning and credits, plus the credits naming
+/// the artist as composer or arranger, its group memberships (either side) and
+/// its aliases. A game session's dependents are its plays. `rel_show_song`
+/// rows are reported as `show_id:song_id`.
+fn collect_dependents(
+    tx: &rusqlite::Transaction,
+    table: &str,
+    id: &str,
+) -> Result<Map<String, Value>, AppError> {
+    let query_json = json!({
+        "songs_by_artist": {
+            "query": "SELECT id FROM song WHERE artist_id=@id ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "rels_by_songs": {
+            "query": "SELECT show_id || ':' || song_id as id FROM rel_show_song \
+                      WHERE song_id IN :[ids] ORDER BY 1",
+            "returns": ["id"],
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "plays_by_songs": {
+            "query": "SELECT id FROM play_history WHERE song_id IN :[ids] ORDER BY id",
+            "returns": ["id"],
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "learning_by_songs": {
+            "query": "SELECT id FROM learning WHERE song_id IN :[ids] ORDER BY id",
+            "returns": ["id"],
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "credits_by_songs": {
+            "query": "SELECT id FROM song_credit WHERE song_id IN :[ids] ORDER BY id",
+            "returns": ["id"],
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "credits_by_artist": {
+            "query": "SELECT id FROM song_credit WHERE artist_id=@id \
+                      OR song_id IN (SELECT id FROM song WHERE artist_id=@id) ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "members_by_artist": {
+            "query": "SELECT id FROM artist_member WHERE group_id=@id OR member_id=@id ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "aliases_by_artist": {
+            "query": "SELECT id FROM artist_alias WHERE artist_id=@id ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "aliases_by_show": {
+            "query": "SELECT id FROM show_alias WHERE show_id=@id ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "rels_by_show": {
+            "query": "SELECT show_id || ':' || song_id as id FROM rel_show_song \
+                      WHERE show_id=@id ORDER BY 1",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "plays_by_show": {
+            "query": "SELECT id FROM play_history WHERE show_id=@id ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        },
+        "plays_by_session": {
+            "query": "SELECT id FROM play_history WHERE session_id=@id ORDER BY id",
+            "returns": ["id"],
+            "args": {"id": {}}
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let ids_of = |name: &str, params: &Value| -> Result<Vec<Value>, AppError> {
+        let result = jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, name, params)
+            .map_err(AppError::from)?;
+        Ok(result
+            .data
+            .into_iter()
+            .map(|row| row["id"].clone())
+            .collect())
+    };
+
+    let mut songs: Vec<Value> = Vec::new();
+    let mut rels: Vec<Value> = Vec::new();
+    let mut plays: Vec<Value> = Vec::new();
+    let mut learning: Vec<Value> = Vec::new();
+    let mut credits: Vec<Value> = Vec::new();
+    let mut members: Vec<Value> = Vec::new();
+    let mut artist_aliases: Vec<Value> = Vec::new();
+    let mut show_aliases: Vec<Value> = Vec::new();
+
+    let song_ids: Vec<Value> = match table {
+        "artist" => {
+            songs = ids_of("songs_by_artist", &json!({"id": id}))?;
+            songs.clone()
+        }
+        "song" => vec![json!(id)],
+        _ => Vec::new(),
+    };
+
+    if !song_ids.is_empty() {
+        let params = json!({"ids": song_ids});
+        rels = ids_of("rels_by_songs", &params)?;
+        plays = ids_of("plays_by_songs", &params)?;
+        learning = ids_of("learning_by_songs", &params)?;
+        credits = ids_of("credits_by_songs", &params)?;
+    }
+    if table == "artist" {
+        credits = ids_of("credits_by_artist", &json!({"id": id}))?;
+        members = ids_of("members_by_artist", &json!({"id": id}))?;
+        artist_aliases = ids_of("aliases_by_artist", &json!({"id": id}))?;
+    }
+    if table == "show" {
+        rels = ids_of("rels_by_show", &json!({"id": id}))?;
+        plays = ids_of("plays_by_show", &json!({"id": id}))?;
+        show_aliases = ids_of("aliases_by_show", &json!({"id": id}))?;
+    }
+    if table == "game_session" {
+        plays = ids_of("plays_by_session", &json!({"id": id}))?;
+    }
+
+    let mut dependents = Map::new();
+    dependents.insert("song".into(), json!(songs));
+    dependents.insert("rel_show_song".into(), json!(rels));
+    dependents.insert("play_history".into(), json!(plays));
+    dependents.insert("learning".into(), json!(learning));
+    dependents.insert("song_credit".into(), json!(credits));
+    dependents.insert("artist_member".into(), json!(members));
+    dependents.insert("artist_alias".into(), json!(artist_aliases));
+    dependents.insert("show_alias".into(), json!(show_aliases));
+    Ok(dependents)
+}
+
+/// Hard-delete the dependent rows listed by `collect_dependents` for one table.
+fn delete_dependents(tx: &rusqlite::Transaction, table: &str, ids: &Value) -> Result<(), AppError> {
+    let ids = ids.as_array().map(Vec::as_slice).unwrap_or_default();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let query_json = json!({
+        "delete_by_ids": {
+            "query": "DELETE FROM #[table] WHERE id IN :[ids]",
+            "args": {
+                "table": {"enum": ["song", "play_history", "learning", "song_credit", "artist_member", "artist_alias", "show_alias"]},
+                "ids": {"itemtype": "string"}
+            }
+        },
+        "delete_rel": {
+            "query": "DELETE FROM rel_show_song WHERE show_id=@show_id AND song_id=@song_id",
+            "args": {"show_id": {}, "song_id": {}}
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    if table == "rel_show_song" {
+        for key in ids.iter().filter_map(Value::as_str) {
+            if let Some((show_id, song_id)) = key.split_once(':') {
+                jankensqlhub::query_run_sqlite_with_transaction(
+                    tx,
+                    &queries,
+                    "delete_rel",
+                    &json!({"show_id": show_id, "song_id": song_id}),
+                )
+                .map_err(AppError::from)?;
+            }
+        }
+    } else {
+        jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "delete_by_ids",
+            &json!({"table": table, "ids": ids}),
+        )
         .map_err(AppError::from)?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// soft-delete <table> <id> / restore <table> <id>
+// ---------------------------------------------------------------------------
+
+pub fn cmd_soft_delete(conn: &mut Connection, table: &str, id: &str) -> Result<Value, AppError> {
+    set_status(conn, table, id, 1)?;
+    Ok(json!({"soft_deleted": true}))
+}
+
+pub fn cmd_restore(conn: &mut Connection, table: &str, id: &str) -> Result<Value, AppError> {
+    set_status(conn, table, id, 0)?;
+    Ok(json!({"restored": true}))
+}
+
+/// Set `status` on a record, bumping `updated_at` where the table has one.
+fn set_status(conn: &mut Connection, table: &str, id: &str, status: i64) -> Result<(), AppError> {
+    models::validate_table(table, models::SOFT_DELETE_TABLES)?;
+
+    // play_history has no updated_at column
+    let has_updated_at = table != "play_history";
+    let set_sql = if has_updated_at {
+        "status=@status, updated_at=@now"
+    } else {
+        "status=@status"
+    };
+
+    let query_json = json!({
+        "check_exists": {
+            "query": "SELECT id FROM #[table] WHERE id=@id",
+            "returns": ["id"],
+            "args": {
+                "table": {"enum": table_config::build_table_enum(models::SOFT_DELETE_TABLES)},
+                "id": {}
+            }
+        },
+        "set_status": {
+            "query": format!("UPDATE #[table] SET {set_sql} WHERE id=@id"),
+            "args": {
+                "table": {"enum": table_config::build_table_enum(models::SOFT_DELETE_TABLES)},
+                "id": {},
+                "status": {"type": "integer"},
+                "now": {"type": "integer"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let check = jankensqlhub::query_run_sqlite(
+        conn,
+        &queries,
+        "check_exists",
+        &json!({ "table": table, "id": id }),
+    )
+    .map_err(AppError::from)?;
 
     if check.data.is_empty() {
         return Err(AppError::NotFound(format!(
@@ -344,10 +846,14 @@ pub fn cmd_delete(conn: &mut Connection, table: &str, id: &str) -> Result<Value,
         )));
     }
 
-    jankensqlhub::query_run_sqlite(conn, &queries, "delete_by_id", &params)
+    let mut params = json!({ "table": table, "id": id, "status": status });
+    if has_updated_at {
+        params["now"] = json!(models::now_unix());
+    }
+    jankensqlhub::query_run_sqlite(conn, &queries, "set_status", &params)
         .map_err(AppError::from)?;
 
-    Ok(json!({"deleted": true}))
+    Ok(())
 }
 
 // ---------------------------------------------------------------------------
@@ -464,10 +970,1037 @@ pub fn cmd_bulk_reassign(
     }
 }
 
+// ---------------------------------------------------------------------------
+// add-member / remove-member --group-id --member-ids
+// ---------------------------------------------------------------------------
+
+/// Record artists as members of a group artist (a unit or character-voice
+/// group). Existing memberships are reported, not duplicated.
+pub fn cmd_add_member(
+    conn: &mut Connection,
+    group_id: &str,
+    member_ids_str: &str,
+) -> Result<Value, AppError> {
+    let member_ids = parse_member_ids(member_ids_str)?;
+    if member_ids.contains(&group_id) {
+        return Err(AppError::InvalidParameter(format!(
+            "artist cannot be a member of itself: {group_id}"
+        )));
+    }
+
+    let query_json = json!({
+        "existing_artists": {
+            "query": "SELECT id FROM artist WHERE id IN :[ids]",
+            "returns": ["id"],
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "current_members": {
+            "query": "SELECT member_id FROM artist_member WHERE group_id=@group_id AND member_id IN :[ids]",
+            "returns": ["member_id"],
+            "args": {"group_id": {}, "ids": {"itemtype": "string"}}
+        },
+        "insert_member": {
+            "query": "INSERT INTO artist_member (id, group_id, member_id, created_at) \
+                      VALUES (@id, @group_id, @member_id, @now)",
+            "args": {"id": {}, "group_id": {}, "member_id": {}, "now": {"type": "integer"}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut all_ids: Vec<&str> = vec![group_id];
+    all_ids.extend(&member_ids);
+    let existing = jankensqlhub::query_run_sqlite(
+        conn,
+        &queries,
+        "existing_artists",
+        &json!({"ids": all_ids}),
+    )
+    .map_err(AppError::from)?;
+    let existing_ids: Vec<&str> = existing
+        .data
+        .iter()
+        .filter_map(|row| row["id"].as_str())
+        .collect();
+    let missing: Vec<&str> = all_ids
+        .iter()
+        .filter(|id| !existing_ids.contains(id))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Record(s) not found: artist/{}",
+            missing.join(", ")
+        )));
+    }
+
+    let tx = conn.transaction()?;
+    let current = current_members(&tx, &queries, group_id, &member_ids)?;
+    let now = models::now_unix();
+    let mut added: Vec<&str> = Vec::new();
+    for member_id in &member_ids {
+        if current.iter().any(|c| c == member_id) {
+            continue;
+        }
+        let params = json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "group_id": group_id,
+            "member_id": member_id,
+            "now": now
+        });
+        jankensqlhub::query_run_sqlite_with_transaction(&tx, &queries, "insert_member", &params)
+            .map_err(AppError::from)?;
+        added.push(member_id);
+    }
+    tx.commit()?;
+
+    Ok(json!({
+        "group_id": group_id,
+        "added": added,
+        "already_members": current
+    }))
+}
+
+/// Remove artists from a group. IDs that are not members are reported.
+pub fn cmd_remove_member(
+    conn: &mut Connection,
+    group_id: &str,
+    member_ids_str: &str,
+) -> Result<Value, AppError> {
+    let member_ids = parse_member_ids(member_ids_str)?;
+
+    let query_json = json!({
+        "check_group": {
+            "query": "SELECT id FROM artist WHERE id=@group_id",
+            "returns": ["id"],
+            "args": {"group_id": {}}
+        },
+        "current_members": {
+            "query": "SELECT member_id FROM artist_member WHERE group_id=@group_id AND member_id IN :[ids]",
+            "returns": ["member_id"],
+            "args": {"group_id": {}, "ids": {"itemtype": "string"}}
+        },
+        "delete_members": {
+            "query": "DELETE FROM artist_member WHERE group_id=@group_id AND member_id IN :[ids]",
+            "args": {"group_id": {}, "ids": {"itemtype": "string"}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let tx = conn.transaction()?;
+    let group = jankensqlhub::query_run_sqlite_with_transaction(
+        &tx,
+        &queries,
+        "check_group",
+        &json!({"group_id": group_id}),
+    )
+    .map_err(AppError::from)?;
+    if group.data.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Record not found: artist/{group_id}"
+        )));
+    }
+
+    let removed = current_members(&tx, &queries, group_id, &member_ids)?;
+    if !removed.is_empty() {
+        jankensqlhub::query_run_sqlite_with_transaction(
+            &tx,
+            &queries,
+            "delete_members",
+            &json!({"group_id": group_id, "ids": removed}),
+        )
+        .map_err(AppError::from)?;
+    }
+    tx.commit()?;
+
+    let not_members: Vec<&str> = member_ids
+        .iter()
+        .filter(|id| !removed.iter().any(|r| r == *id))
+        .copied()
+        .collect();
+    Ok(json!({
+        "group_id": group_id,
+        "removed": removed,
+        "not_members": not_members
+    }))
+}
+
+/// Split `--member-ids`, dropping blanks and repeats.
+fn parse_member_ids(member_ids_str: &str) -> Result<Vec<&str>, AppError> {
+    let mut ids: Vec<&str> = Vec::new();
+    for id in member_ids_str.split(',').map(str::trim) {
+        if !id.is_empty() && !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    if ids.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "member_ids cannot be empty".into(),
+        ));
+    }
+    Ok(ids)
+}
+
+/// Which of `member_ids` already belong to the group, in `member_ids` order.
+fn current_members(
+    tx: &rusqlite::Transaction,
+    queries: &QueryDefinitions,
+    group_id: &str,
+    member_ids: &[&str],
+) -> Result<Vec<String>, AppError> {
+    let result = jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        queries,
+        "current_members",
+        &json!({"group_id": group_id, "ids": member_ids}),
+    )
+    .map_err(AppError::from)?;
+    let found: Vec<&str> = result
+        .data
+        .iter()
+        .filter_map(|row| row["member_id"].as_str())
+        .collect();
+    Ok(member_ids
+        .iter()
+        .filter(|id| found.contains(id))
+        .map(|id| (*id).to_string())
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// promote-alias <table> <id>
+// ---------------------------------------------------------------------------
+
+/// Make an alias the primary name of its artist or show. The previous name
+/// takes the alias's place, so nothing is lost; it is dropped only when empty
+/// or already another alias of the record.
+pub fn cmd_promote_alias(conn: &mut Connection, table: &str, id: &str) -> Result<Value, AppError> {
+    models::validate_table(table, models::ALIAS_TABLES)?;
+    let (owner, ref_col) = if table == "artist_alias" {
+        ("artist", "artist_id")
+    } else {
+        ("show", "show_id")
+    };
+
+    // Table and column names come from the fixed alias tables above
+    let query_json = json!({
+        "get_alias": {
+            "query": format!("SELECT {ref_col} as owner_id, name FROM {table} WHERE id=@id"),
+            "returns": ["owner_id", "name"]
+        },
+        "get_owner": {
+            "query": format!("SELECT IFNULL(name, '') as name FROM {owner} WHERE id=@owner_id"),
+            "returns": ["name"]
+        },
+        "other_alias": {
+            "query": format!(
+                "SELECT id FROM {table} WHERE {ref_col}=@owner_id AND name=@name AND id != @id"
+            ),
+            "returns": ["id"]
+        },
+        "rename_owner": {
+            "query": format!(
+                "UPDATE {owner} SET name=@name, name_key=@name_key, updated_at=@now WHERE id=@owner_id"
+            ),
+            "args": {"now": {"type": "integer"}}
+        },
+        "rename_alias": {
+            "query": format!("UPDATE {table} SET name=@name, name_key=@name_key WHERE id=@id")
+        },
+        "drop_alias": {
+            "query": format!("DELETE FROM {table} WHERE id=@id")
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let tx = conn.transaction()?;
+    let alias = jankensqlhub::query_run_sqlite_with_transaction(
+        &tx,
+        &queries,
+        "get_alias",
+        &json!({"id": id}),
+    )
+    .map_err(AppError::from)?;
+    let Some(alias) = alias.data.first() else {
+        return Err(AppError::NotFound(format!(
+            "Record not found: {table}/{id}"
+        )));
+    };
+    let owner_id = alias["owner_id"].as_str().unwrap_or_default().to_string();
+    let alias_name = alias["name"].as_str().unwrap_or_default().to_string();
+
+    let current = jankensqlhub::query_run_sqlite_with_transaction(
+        &tx,
+        &queries,
+        "get_owner",
+        &json!({"owner_id": owner_id}),
+    )
+    .map_err(AppError::from)?;
+    let Some(current) = current.data.first() else {
+        return Err(AppError::NotFound(format!(
+            "Record not found: {owner}/{owner_id}"
+        )));
+    };
+    let previous_name = current["name"].as_str().unwrap_or_default().to_string();
+
+    jankensqlhub::query_run_sqlite_with_transaction(
+        &tx,
+        &queries,
+        "rename_owner",
+        &json!({
+            "owner_id": owner_id,
+            "name": alias_name,
+            "name_key": normalize::name_key(&alias_name),
+            "now": models::now_unix()
+        }),
+    )
+    .map_err(AppError::from)?;
+
+    let duplicate = jankensqlhub::query_run_sqlite_with_transaction(
+        &tx,
+        &queries,
+        "other_alias",
+        &json!({"owner_id": owner_id, "name": previous_name, "id": id}),
+    )
+    .map_err(AppError::from)?;
+    let kept_alias = if previous_name.trim().is_empty() || !duplicate.data.is_empty() {
+        jankensqlhub::query_run_sqlite_with_transaction(
+            &tx,
+            &queries,
+            "drop_alias",
+            &json!({"id": id}),
+        )
+        .map_err(AppError::from)?;
+        Value::Null
+    } else {
+        jankensqlhub::query_run_sqlite_with_transaction(
+            &tx,
+            &queries,
+            "rename_alias",
+            &json!({
+                "id": id,
+                "name": previous_name,
+                "name_key": normalize::name_key(&previous_name)
+            }),
+        )
+        .map_err(AppError::from)?;
+        json!({"id": id, "name": previous_name})
+    };
+    tx.commit()?;
+
+    Ok(json!({
+        "promoted": true,
+        "table": owner,
+        "id": owner_id,
+        "name": alias_name,
+        "previous_name": previous_name,
+        "alias": kept_alias
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// merge <table> --keep --remove
+// ---------------------------------------------------------------------------
+
+pub fn cmd_merge(
+    conn: &mut Connection,
+    table: &str,
+    keep_id: &str,
+    remove_ids_str: &str,
+) -> Result<Value, AppError> {
+    models::validate_table(table, models::MERGE_TABLES)?;
+
+    let remove_ids: Vec<&str> = remove_ids_str
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if remove_ids.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "remove ids cannot be empty".into(),
+        ));
+    }
+    if remove_ids.contains(&keep_id) {
+        return Err(AppError::InvalidParameter(format!(
+            "keep id cannot also be removed: {keep_id}"
+        )));
+    }
+
+    let table_enum = table_config::build_table_enum(models::MERGE_TABLES);
+    let query_json = json!({
+        "existing_ids": {
+            "query": "SELECT id FROM #[table] WHERE id IN :[ids]",
+            "returns": ["id"],
+            "args": {
+                "table": {"enum": table_enum},
+                "ids": {"itemtype": "string"}
+            }
+        },
+        "soft_delete": {
+            "query": "UPDATE #[table] SET status=1, updated_at=@now WHERE id IN :[ids]",
+            "args": {
+                "table": {"enum": table_enum},
+                "ids": {"itemtype": "string"},
+                "now": {"type": "integer"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut all_ids: Vec<&str> = vec![keep_id];
+    all_ids.extend(&remove_ids);
+    let all_ids_json: Vec<Value> = all_ids.iter().map(|s| json!(s)).collect();
+    let existing = jankensqlhub::query_run_sqlite(
+        conn,
+        &queries,
+        "existing_ids",
+        &json!({"table": table, "ids": all_ids_json}),
+    )
+    .map_err(AppError::from)?;
+    let existing_ids: Vec<&str> = existing
+        .data
+        .iter()
+        .filter_map(|row| row["id"].as_str())
+        .collect();
+    let missing: Vec<&str> = all_ids
+        .iter()
+        .filter(|id| !existing_ids.contains(id))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "Record(s) not found: {table}/{}",
+            missing.join(", ")
+        )));
+    }
+
+    let now = models::now_unix();
+    let remove_ids_json: Vec<Value> = remove_ids.iter().map(|s| json!(s)).collect();
+    let mut moved = Map::new();
+    let mut report = Map::new();
+
+    let tx = conn.transaction()?;
+
+    match table {
+        "artist" => {
+            let count =
+                repoint_references(&tx, "song", "artist_id", keep_id, &remove_ids_json, now)?;
+            moved.insert("song".into(), json!(count));
+
+            let (credit_count, collisions) =
+                merge_song_credits(&tx, "artist_id", keep_id, &remove_ids)?;
+            moved.insert("song_credit".into(), json!(credit_count));
+            report.insert("song_credit_collisions".into(), json!(collisions));
+
+            let (member_count, collisions) = merge_artist_members(&tx, keep_id, &remove_ids)?;
+            moved.insert("artist_member".into(), json!(member_count));
+            report.insert("artist_member_collisions".into(), json!(collisions));
+
+            let (alias_count, added) = merge_aliases(&tx, "artist", keep_id, &remove_ids, now)?;
+            moved.insert("artist_alias".into(), json!(alias_count));
+            report.insert("aliases_added".into(), json!(added));
+        }
+        "show" | "song" => {
+            let ref_col = if table == "show" {
+                "show_id"
+            } else {
+                "song_id"
+            };
+            let count =
+                repoint_references(&tx, "play_history", ref_col, keep_id, &remove_ids_json, now)?;
+            moved.insert("play_history".into(), json!(count));
+
+            let (rel_count, collisions) = merge_rel_show_song(&tx, ref_col, keep_id, &remove_ids)?;
+            moved.insert("rel_show_song".into(), json!(rel_count));
+            report.insert("rel_show_song_collisions".into(), json!(collisions));
+
+            if table == "show" {
+                let (alias_count, added) = merge_aliases(&tx, "show", keep_id, &remove_ids, now)?;
+                moved.insert("show_alias".into(), json!(alias_count));
+                report.insert("aliases_added".into(), json!(added));
+            }
+
+            if table == "song" {
+                report.insert(
+                    "learning".into(),
+                    merge_learning(&tx, keep_id, &all_ids_json, now)?,
+                );
+
+                let (credit_count, collisions) =
+                    merge_song_credits(&tx, "song_id", keep_id, &remove_ids)?;
+                moved.insert("song_credit".into(), json!(credit_count));
+                report.insert("song_credit_collisions".into(), json!(collisions));
+            }
+        }
+        _ => unreachable!(),
+    }
+
+    jankensqlhub::query_run_sqlite_with_transaction(
+        &tx,
+        &queries,
+        "soft_delete",
+        &json!({"table": table, "ids": remove_ids_json, "now": now}),
+    )
+    .map_err(AppError::from)?;
+
+    tx.commit()?;
+
+    report.insert("kept_id".into(), json!(keep_id));
+    report.insert("removed_ids".into(), json!(remove_ids));
+    report.insert("moved".into(), json!(moved));
+    report.insert("soft_deleted_count".into(), json!(remove_ids.len()));
+    Ok(json!(report))
+}
+
+/// Point every `ref_table.ref_col` that references one of `remove_ids` at `keep_id`.
+/// Returns the number of rows repointed.
+///
+/// `ref_table` and `ref_col` are fixed by the caller, never user input.
+fn repoint_references(
+    tx: &rusqlite::Transaction,
+    ref_table: &str,
+    ref_col: &str,
+    keep_id: &str,
+    remove_ids_json: &[Value],
+    now: i64,
+) -> Result<i64, AppError> {
+    let touch_updated_at = if ref_table == "song" {
+        ", updated_at=@now"
+    } else {
+        ""
+    };
+    let mut update_args = json!({"ids": {"itemtype": "string"}});
+    if ref_table == "song" {
+        update_args["now"] = json!({"type": "integer"});
+    }
+    let query_json = json!({
+        "count_refs": {
+            "query": format!("SELECT COUNT(*) as cnt FROM {ref_table} WHERE {ref_col} IN :[ids]"),
+            "returns": ["cnt"],
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "repoint_refs": {
+            "query": format!(
+                "UPDATE {ref_table} SET {ref_col}=@keep_id{touch_updated_at} WHERE {ref_col} IN :[ids]"
+            ),
+            "args": update_args
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let count_result = jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        &queries,
+        "count_refs",
+        &json!({"ids": remove_ids_json}),
+    )
+    .map_err(AppError::from)?;
+    let count = count_result.data[0]["cnt"].as_i64().unwrap_or(0);
+
+    let mut params = json!({"keep_id": keep_id, "ids": remove_ids_json});
+    if ref_table == "song" {
+        params["now"] = json!(now);
+    }
+    jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "repoint_refs", &params)
+        .map_err(AppError::from)?;
+
+    Ok(count)
+}
+
+/// Repoint `rel_show_song` links from each removed record to the kept one.
+///
+/// A link that the kept record already has would violate the `(show_id, song_id)`
+/// unique constraint, so it is dropped instead; its `media_url` backfills the kept
+/// link when that one is empty. Returns the repointed count and the dropped links.
+fn merge_rel_show_song(
+    tx: &rusqlite::Transaction,
+    ref_col: &str,
+    keep_id: &str,
+    remove_ids: &[&str],
+) -> Result<(i64, Vec<Value>), AppError> {
+    let other_col = if ref_col == "show_id" {
+        "song_id"
+    } else {
+        "show_id"
+    };
+    let query_json = json!({
+        "find_collisions": {
+            "query": format!(
+                "SELECT r.show_id, r.song_id FROM rel_show_song r \
+                 WHERE r.{ref_col}=@remove_id AND EXISTS ( \
+                   SELECT 1 FROM rel_show_song k \
+                   WHERE k.{ref_col}=@keep_id AND k.{other_col}=r.{other_col})"
+            ),
+            "returns": ["show_id", "song_id"]
+        },
+        "backfill_media_url": {
+            "query": format!(
+                "UPDATE rel_show_song SET media_url = ( \
+                   SELECT r.media_url FROM rel_show_song r \
+                   WHERE r.{ref_col}=@remove_id AND r.{other_col}=rel_show_song.{other_col}) \
+                 WHERE {ref_col}=@keep_id AND COALESCE(media_url, '')='' \
+                 AND {other_col} IN (SELECT {other_col} FROM rel_show_song WHERE {ref_col}=@remove_id)"
+            )
+        },
+        "drop_collisions": {
+            "query": format!(
+                "DELETE FROM rel_show_song WHERE {ref_col}=@remove_id \
+                 AND {other_col} IN (SELECT {other_col} FROM rel_show_song WHERE {ref_col}=@keep_id)"
+            )
+        },
+        "count_links": {
+            "query": format!("SELECT COUNT(*) as cnt FROM rel_show_song WHERE {ref_col}=@remove_id"),
+            "returns": ["cnt"]
+        },
+        "repoint_links": {
+            "query": format!("UPDATE rel_show_song SET {ref_col}=@keep_id WHERE {ref_col}=@remove_id")
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut repointed: i64 = 0;
+    let mut collisions: Vec<Value> = Vec::new();
+
+    // One removed record at a time, so two removed records sharing a link
+    // collide with each other once the first has been repointed.
+    for remove_id in remove_ids {
+        let params = json!({"keep_id": keep_id, "remove_id": remove_id});
+        let found = jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "find_collisions",
+            &params,
+        )
+        .map_err(AppError::from)?;
+        collisions.extend(found.data);
+
+        for name in ["backfill_media_url", "drop_collisions"] {
+            jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, name, &params)
+                .map_err(AppError::from)?;
+        }
+
+        let count =
+            jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "count_links", &params)
+                .map_err(AppError::from)?;
+        repointed += count.data[0]["cnt"].as_i64().unwrap_or(0);
+
+        jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "repoint_links", &params)
+            .map_err(AppError::from)?;
+    }
+
+    Ok((repointed, collisions))
+}
+
+/// Repoint `song_credit` rows from each removed artist or song to the kept one.
+///
+/// A credit the kept record already has (same song or artist, same role) would
+/// violate the unique constraint, so it is dropped instead. Returns the
+/// repointed count and the dropped credits.
+fn merge_song_credits(
+    tx: &rusqlite::Transaction,
+    ref_col: &str,
+    keep_id: &str,
+    remove_ids: &[&str],
+) -> Result<(i64, Vec<Value>), AppError> {
+    let other_col = if ref_col == "artist_id" {
+        "song_id"
+    } else {
+        "artist_id"
+    };
+    let query_json = json!({
+        "find_collisions": {
+            "query": format!(
+                "SELECT c.id, c.song_id, c.artist_id, c.role FROM song_credit c \
+                 WHERE c.{ref_col}=@remove_id AND EXISTS ( \
+                   SELECT 1 FROM song_credit k \
+                   WHERE k.{ref_col}=@keep_id AND k.{other_col}=c.{other_col} AND k.role=c.role) \
+                 ORDER BY c.id"
+            ),
+            "returns": ["id", "song_id", "artist_id", "role"]
+        },
+        "drop_credits": {
+            "query": "DELETE FROM song_credit WHERE id IN :[ids]",
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "count_credits": {
+            "query": format!("SELECT COUNT(*) as cnt FROM song_credit WHERE {ref_col}=@remove_id"),
+            "returns": ["cnt"]
+        },
+        "repoint_credits": {
+            "query": format!("UPDATE song_credit SET {ref_col}=@keep_id WHERE {ref_col}=@remove_id")
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut repointed: i64 = 0;
+    let mut collisions: Vec<Value> = Vec::new();
+
+    // One removed record at a time, as in merge_rel_show_song
+    for remove_id in remove_ids {
+        let params = json!({"keep_id": keep_id, "remove_id": remove_id});
+        let found = jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "find_collisions",
+            &params,
+        )
+        .map_err(AppError::from)?;
+        let ids: Vec<Value> = found.data.iter().map(|row| row["id"].clone()).collect();
+        if !ids.is_empty() {
+            jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                "drop_credits",
+                &json!({"ids": ids}),
+            )
+            .map_err(AppError::from)?;
+        }
+        collisions.extend(found.data);
+
+        let count =
+            jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "count_credits", &params)
+                .map_err(AppError::from)?;
+        repointed += count.data[0]["cnt"].as_i64().unwrap_or(0);
+
+        jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "repoint_credits", &params)
+            .map_err(AppError::from)?;
+    }
+
+    Ok((repointed, collisions))
+}
+
+/// Repoint `artist_member` rows from each removed artist to the kept one, on
+/// both the group and the member side.
+///
+/// A membership the kept artist already has, or one that would make the kept
+/// artist a member of itself, is dropped instead. Returns the repointed count
+/// and the dropped memberships.
+fn merge_artist_members(
+    tx: &rusqlite::Transaction,
+    keep_id: &str,
+    remove_ids: &[&str],
+) -> Result<(i64, Vec<Value>), AppError> {
+    let mut query_map = Map::new();
+    for (ref_col, other_col) in [("group_id", "member_id"), ("member_id", "group_id")] {
+        query_map.insert(
+            format!("find_collisions_{ref_col}"),
+            json!({
+                "query": format!(
+                    "SELECT m.id, m.group_id, m.member_id FROM artist_member m \
+                     WHERE m.{ref_col}=@remove_id AND (m.{other_col}=@keep_id OR EXISTS ( \
+                       SELECT 1 FROM artist_member k \
+                       WHERE k.{ref_col}=@keep_id AND k.{other_col}=m.{other_col})) \
+                     ORDER BY m.id"
+                ),
+                "returns": ["id", "group_id", "member_id"]
+            }),
+        );
+        query_map.insert(
+            format!("count_members_{ref_col}"),
+            json!({
+                "query": format!("SELECT COUNT(*) as cnt FROM artist_member WHERE {ref_col}=@remove_id"),
+                "returns": ["cnt"]
+            }),
+        );
+        query_map.insert(
+            format!("repoint_members_{ref_col}"),
+            json!({
+                "query": format!(
+                    "UPDATE artist_member SET {ref_col}=@keep_id WHERE {ref_col}=@remove_id"
+                )
+            }),
+        );
+    }
+    query_map.insert(
+        "drop_members".into(),
+        json!({
+            "query": "DELETE FROM artist_member WHERE id IN :[ids]",
+            "args": {"ids": {"itemtype": "string"}}
+        }),
+    );
+
+    let queries = QueryDefinitions::from_json(Value::Object(query_map))
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut repointed: i64 = 0;
+    let mut collisions: Vec<Value> = Vec::new();
+
+    // One removed artist and side at a time, so a membership between two
+    // removed artists is caught as a self-membership on its second side
+    for remove_id in remove_ids {
+        let params = json!({"keep_id": keep_id, "remove_id": remove_id});
+        for ref_col in ["group_id", "member_id"] {
+            let found = jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                &format!("find_collisions_{ref_col}"),
+                &params,
+            )
+            .map_err(AppError::from)?;
+            let ids: Vec<Value> = found.data.iter().map(|row| row["id"].clone()).collect();
+            if !ids.is_empty() {
+                jankensqlhub::query_run_sqlite_with_transaction(
+                    tx,
+                    &queries,
+                    "drop_members",
+                    &json!({"ids": ids}),
+                )
+                .map_err(AppError::from)?;
+            }
+            collisions.extend(found.data);
+
+            let count = jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                &format!("count_members_{ref_col}"),
+                &params,
+            )
+            .map_err(AppError::from)?;
+            repointed += count.data[0]["cnt"].as_i64().unwrap_or(0);
+
+            jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                &format!("repoint_members_{ref_col}"),
+                &params,
+            )
+            .map_err(AppError::from)?;
+        }
+    }
+
+    Ok((repointed, collisions))
+}
+
+/// Move the aliases of each removed artist or show to the kept one, and keep
+/// each removed record's name as an alias so it can still be found.
+///
+/// Aliases equal to the kept name or to one of its aliases are dropped
+/// instead. Returns the moved alias count and the names newly added as aliases.
+fn merge_aliases(
+    tx: &rusqlite::Transaction,
+    table: &str,
+    keep_id: &str,
+    remove_ids: &[&str],
+    now: i64,
+) -> Result<(i64, Vec<String>), AppError> {
+    // Both names come from the fixed alias tables, never user input
+    let alias_table = format!("{table}_alias");
+    let ref_col = format!("{table}_id");
+    let query_json = json!({
+        "drop_redundant": {
+            "query": format!(
+                "DELETE FROM {alias_table} WHERE {ref_col}=@remove_id AND ( \
+                   name=(SELECT name FROM {table} WHERE id=@keep_id) \
+                   OR name IN (SELECT name FROM {alias_table} WHERE {ref_col}=@keep_id))"
+            )
+        },
+        "count_aliases": {
+            "query": format!("SELECT COUNT(*) as cnt FROM {alias_table} WHERE {ref_col}=@remove_id"),
+            "returns": ["cnt"]
+        },
+        "repoint_aliases": {
+            "query": format!("UPDATE {alias_table} SET {ref_col}=@keep_id WHERE {ref_col}=@remove_id")
+        },
+        "new_alias_name": {
+            "query": format!(
+                "SELECT r.name FROM {table} r WHERE r.id=@remove_id AND IFNULL(r.name, '') != '' \
+                 AND r.name != (SELECT name FROM {table} WHERE id=@keep_id) \
+                 AND r.name NOT IN (SELECT name FROM {alias_table} WHERE {ref_col}=@keep_id)"
+            ),
+            "returns": ["name"]
+        },
+        "insert_alias": {
+            "query": format!(
+                "INSERT INTO {alias_table} (id, {ref_col}, name, name_key, created_at) \
+                 VALUES (@id, @keep_id, @name, @name_key, @now)"
+            ),
+            "args": {"now": {"type": "integer"}}
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut moved: i64 = 0;
+    let mut added: Vec<String> = Vec::new();
+
+    for remove_id in remove_ids {
+        let params = json!({"keep_id": keep_id, "remove_id": remove_id});
+        jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "drop_redundant", &params)
+            .map_err(AppError::from)?;
+        let count =
+            jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "count_aliases", &params)
+                .map_err(AppError::from)?;
+        moved += count.data[0]["cnt"].as_i64().unwrap_or(0);
+        jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "repoint_aliases", &params)
+            .map_err(AppError::from)?;
+
+        let name = jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "new_alias_name",
+            &params,
+        )
+        .map_err(AppError::from)?;
+        if let Some(name) = name.data.first().and_then(|row| row["name"].as_str()) {
+            jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                "insert_alias",
+                &json!({
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "keep_id": keep_id,
+                    "name": name,
+                    "name_key": normalize::name_key(name),
+                    "now": now
+                }),
+            )
+            .map_err(AppError::from)?;
+            added.push(name.to_string());
+        }
+    }
+
+    Ok((moved, added))
+}
+
+/// Collapse the learning records of all merged songs into the most advanced one.
+///
+/// "Most advanced" ranks graduated first, then by level, then by the most recent
+/// level-up. The winner is repointed to the kept song; every other record is deleted.
+fn merge_learning(
+    tx: &rusqlite::Transaction,
+    keep_id: &str,
+    song_ids_json: &[Value],
+    now: i64,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "learning_by_songs": {
+            "query": "SELECT id FROM learning WHERE song_id IN :[song_ids] \
+                      ORDER BY graduated DESC, level DESC, last_level_up_at DESC, id",
+            "returns": ["id"],
+            "args": {"song_ids": {"itemtype": "string"}}
+        },
+        "delete_learning": {
+            "query": "DELETE FROM learning WHERE id IN :[ids]",
+            "args": {"ids": {"itemtype": "string"}}
+        },
+        "repoint_learning": {
+            "query": "UPDATE learning SET song_id=@keep_id, updated_at=@now WHERE id=@id",
+            "args": {"now": {"type": "integer"}}
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result = jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        &queries,
+        "learning_by_songs",
+        &json!({"song_ids": song_ids_json}),
+    )
+    .map_err(AppError::from)?;
+
+    let ids: Vec<&str> = result
+        .data
+        .iter()
+        .filter_map(|row| row["id"].as_str())
+        .collect();
+    let Some((kept, deleted)) = ids.split_first() else {
+        return Ok(json!({"kept_id": null, "deleted_ids": []}));
+    };
+
+    if !deleted.is_empty() {
+        jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "delete_learning",
+            &json!({"ids": deleted}),
+        )
+        .map_err(AppError::from)?;
+    }
+    jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        &queries,
+        "repoint_learning",
+        &json!({"keep_id": keep_id, "now": now, "id": kept}),
+    )
+    .map_err(AppError::from)?;
+
+    Ok(json!({"kept_id": kept, "deleted_ids": deleted}))
+}
+
 // ---------------------------------------------------------------------------
 // Helpers (local to data_management)
 // ---------------------------------------------------------------------------
 
+/// Check that every reference column present in `data` points at an existing
+/// record, returning `NotFound` naming the offending field otherwise.
+/// Soft-deleted targets still count as existing.
+fn validate_references(
+    conn: &mut Connection,
+    table: &str,
+    data: &Map<String, Value>,
+) -> Result<(), AppError> {
+    let Some(config) = table_config::get(table) else {
+        return Ok(());
+    };
+    if config.references.is_empty() {
+        return Ok(());
+    }
+
+    let query_json = json!({
+        "count_by_id": {
+            "query": "SELECT COUNT(*) as cnt FROM #[table] WHERE id=@id",
+            "returns": ["cnt"],
+            "args": {
+                "table": {"enum": ["artist", "show", "song", "game_session"]},
+                "id": {}
+            }
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    for (col, target) in config.references {
+        let id = match data.get(*col) {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        };
+        let result = jankensqlhub::query_run_sqlite(
+            conn,
+            &queries,
+            "count_by_id",
+            &json!({"table": target, "id": id}),
+        )
+        .map_err(AppError::from)?;
+        let count = result
+            .data
+            .first()
+            .and_then(|r| r.get("cnt"))
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+        if count == 0 {
+            return Err(AppError::NotFound(format!(
+                "{col} not found: {target}/{id}"
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// URL-decode all string values in a JSON object map.
 /// Non-string values (numbers, booleans, nulls, arrays, objects) are left unchanged.
 fn url_decode_map_values(data: &mut Map<String, Value>) -> Result<(), AppError> {
@@ -503,6 +2036,181 @@ fn json_value_to_param(val: &Value) -> (Value, Value) {
     }
 }
 
+/// Columns derived from a written field, as `(column, value)`; `Value::Null`
+/// clears the column. Normalized name keys follow their source name, and a
+/// show's `vintage` fills `season`, `year` and `vintage_key`. A vintage that
+/// does not parse is rejected; an empty one clears the derived columns.
+fn derived_columns(
+    table: &str,
+    key: &str,
+    param_val: &Value,
+) -> Result<Vec<(&'static str, Value)>, AppError> {
+    let text = match param_val {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if let Some(key_col) = table_config::normalized_key(table, key) {
+        return Ok(vec![(key_col, json!(normalize::name_key(&text)))]);
+    }
+    if table == "show" && key == "vintage" {
+        if text.trim().is_empty() {
+            return Ok(vec![
+                ("season", Value::Null),
+                ("year", Value::Null),
+                ("vintage_key", Value::Null),
+            ]);
+        }
+        let parsed = vintage::parse_required(&text, "vintage")?;
+        return Ok(vec![
+            ("season", json!(parsed.season())),
+            ("year", json!(parsed.year)),
+            ("vintage_key", json!(parsed.key())),
+        ]);
+    }
+    Ok(Vec::new())
+}
+
+/// Require `song_id`, `artist_id` and `role` for a new `song_credit`, and store
+/// `role` lowercased. Roles are listed in [`models::CREDIT_ROLES`].
+fn validate_song_credit(data: &mut Map<String, Value>) -> Result<(), AppError> {
+    for field in ["song_id", "artist_id", "role"] {
+        if !data.get(field).is_some_and(Value::is_string) {
+            return Err(AppError::InvalidParameter(format!("{field} is required")));
+        }
+    }
+    let role = data["role"]
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    if !models::CREDIT_ROLES.contains(&role.as_str()) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid role: {}. Allowed: {}",
+            data["role"].as_str().unwrap_or_default(),
+            models::CREDIT_ROLES.join(", ")
+        )));
+    }
+    data.insert("role".into(), json!(role));
+    Ok(())
+}
+
+/// Require the owning record and a non-blank `name` for a new alias; on update
+/// (`creating` false) only a present `name` is checked.
+fn validate_alias(table: &str, data: &Map<String, Value>, creating: bool) -> Result<(), AppError> {
+    let ref_col = if table == "artist_alias" {
+        "artist_id"
+    } else {
+        "show_id"
+    };
+    if creating && !data.get(ref_col).is_some_and(Value::is_string) {
+        return Err(AppError::InvalidParameter(format!("{ref_col} is required")));
+    }
+    let blank = match data.get("name") {
+        Some(Value::String(s)) => s.trim().is_empty(),
+        None => creating,
+        Some(_) => true,
+    };
+    if blank {
+        return Err(AppError::InvalidParameter(
+            "alias name cannot be empty".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Require a non-negative integer `started_at` (AMQ `startTime` as a unix
+/// timestamp) for a new game session; on update (`creating` false) only a
+/// present one is checked.
+fn validate_game_session(data: &Map<String, Value>, creating: bool) -> Result<(), AppError> {
+    match data.get("started_at") {
+        None if !creating => Ok(()),
+        Some(v) if v.as_i64().is_some_and(|t| t >= 0) => Ok(()),
+        None => Err(AppError::InvalidParameter("started_at is required".into())),
+        Some(other) => Err(AppError::InvalidParameter(format!(
+            "started_at must be a unix timestamp, got: {other}"
+        ))),
+    }
+}
+
+/// Normalize the play_history outcome flags `correct_guess` and `wrong_guess`
+/// (AMQ's `correctGuess`/`wrongGuess`) to 0 or 1. Booleans and 0/1 are
+/// accepted; a play cannot be both correct and wrong.
+fn validate_play_outcome(data: &mut Map<String, Value>) -> Result<(), AppError> {
+    for key in ["correct_guess", "wrong_guess"] {
+        let Some(val) = data.get(key) else {
+            continue;
+        };
+        let flag = match val {
+            Value::Bool(b) => i64::from(*b),
+            Value::Number(n) if matches!(n.as_i64(), Some(0 | 1)) => n.as_i64().unwrap_or(0),
+            other => {
+                return Err(AppError::InvalidParameter(format!(
+                    "{key} must be true, false, 0 or 1, got: {other}"
+                )));
+            }
+        };
+        data.insert(key.into(), json!(flag));
+    }
+    if data.get("correct_guess") == Some(&json!(1)) && data.get("wrong_guess") == Some(&json!(1)) {
+        return Err(AppError::InvalidParameter(
+            "correct_guess and wrong_guess cannot both be true".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validated `song_type`/`type_number` values present in rel_show_song `data`,
+/// as `(column, value)`. `song_type` is stored as its code (see
+/// [`song_type::parse`]); null or empty clears either column.
+fn rel_type_fields(data: &Map<String, Value>) -> Result<Vec<(&'static str, Value)>, AppError> {
+    let mut fields = Vec::new();
+    if let Some(val) = data.get("song_type") {
+        let stored = match val {
+            Value::Null => Value::Null,
+            Value::String(s) if s.trim().is_empty() => Value::Null,
+            other => json!(song_type::parse_required(other)?),
+        };
+        fields.push(("song_type", stored));
+    }
+    if let Some(val) = data.get("type_number") {
+        let stored = match val {
+            Value::Null => Value::Null,
+            other => match other.as_i64() {
+                Some(n) if n >= 0 => json!(n),
+                _ => {
+                    return Err(AppError::InvalidParameter(format!(
+                        "type_number must be a non-negative integer, got: {other}"
+                    )));
+                }
+            },
+        };
+        fields.push(("type_number", stored));
+    }
+    Ok(fields)
+}
+
+/// Bind a derived column value and return its placeholder (`NULL` for null,
+/// which JankenSQLHub parameters cannot carry).
+fn bind_derived(
+    col: &str,
+    value: Value,
+    args: &mut serde_json::Map<String, Value>,
+    param_values: &mut serde_json::Map<String, Value>,
+) -> String {
+    if value.is_null() {
+        return "NULL".into();
+    }
+    let param_key = format!("p_{col}");
+    let arg_def = if value.is_i64() {
+        json!({"type": "integer"})
+    } else {
+        json!({})
+    };
+    args.insert(param_key.clone(), arg_def);
+    param_values.insert(param_key.clone(), value);
+    format!("@{param_key}")
+}
+
 /// Add an integer column to the dynamic INSERT builder.
 fn add_integer_column(
     columns: &mut Vec<String>,
@@ -541,9 +2249,9 @@ mod tests {
 
     #[test]
     fn test_json_value_to_param_float() {
-        let (arg, val) = json_value_to_param(&json!(3.14));
+        let (arg, val) = json_value_to_param(&json!(2.5));
         assert_eq!(arg, json!({"type": "float"}));
-        assert_eq!(val, json!(3.14));
+        assert_eq!(val, json!(2.5));
     }
 
     #[test]
@@ -5,12 +5,15 @@ use serde_json::{Value, json};
 use crate::easing::{MAX_LEVEL, generate_level_up_path_json};
 use crate::error::AppError;
 use crate::models;
+use crate::song_type;
 
 /// The shared WHERE clause for finding due-for-review learning records.
 /// Uses `@offset` (integer) as a look-ahead in seconds.
 /// When offset=0, the behavior is identical to comparing against "now".
+/// Requires the query to join `song s`; learning for soft-deleted songs is never due.
 const DUE_WHERE: &str = "\
-    l.graduated = 0 \
+    s.status = 0 \
+    AND l.graduated = 0 \
     AND ( \
         (l.last_level_up_at > 0 AND l.level = 0 \
          AND (CAST(strftime('%s', 'now') AS INTEGER) + @offset) >= (l.last_level_up_at + 300)) \
@@ -31,7 +34,31 @@ pub fn cmd_learning_due(
     conn: &mut Connection,
     limit: u32,
     offset_seconds: u32,
+    song_type: Option<&str>,
 ) -> Result<Value, AppError> {
+    // Optional: only songs linked to some show with the given song type
+    let song_type = song_type
+        .map(|t| song_type::parse_required(&json!(t)))
+        .transpose()?;
+    let type_sql = if song_type.is_some() {
+        " AND EXISTS (SELECT 1 FROM rel_show_song rs \
+           WHERE rs.song_id = l.song_id AND rs.song_type = @song_type)"
+    } else {
+        ""
+    };
+    let mut args = json!({
+        "offset": {"type": "integer"},
+        "limit": {"type": "integer"}
+    });
+    let mut params = json!({
+        "offset": offset_seconds,
+        "limit": limit
+    });
+    if let Some(song_type) = song_type {
+        args["song_type"] = json!({});
+        params["song_type"] = json!(song_type);
+    }
+
     let query_json = json!({
         "learning_due": {
             "query": format!(
@@ -40,26 +67,18 @@ pub fn cmd_learning_due(
                  COALESCE(json_extract(l.level_up_path, '$[' || l.level || ']'), 0) as wait_days \
                  FROM learning l \
                  JOIN song s ON l.song_id = s.id \
-                 WHERE {DUE_WHERE} \
+                 WHERE {DUE_WHERE}{type_sql} \
                  ORDER BY l.level DESC \
                  LIMIT @limit"
             ),
             "returns": ["id", "song_id", "song_name", "level", "display_level", "wait_days"],
-            "args": {
-                "offset": {"type": "integer"},
-                "limit": {"type": "integer"}
-            }
+            "args": args
         }
     });
 
     let queries = QueryDefinitions::from_json(query_json)
         .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
 
-    let params = json!({
-        "offset": offset_seconds,
-        "limit": limit
-    });
-
     let result = jankensqlhub::query_run_sqlite(conn, &queries, "learning_due", &params)
         .map_err(AppError::from)?;
 
@@ -268,11 +287,13 @@ pub fn cmd_learning_song_review(
             "returns": ["name"]
         },
         "get_show_media": {
-            "query": "SELECT ph.show_id, sh.name as show_name, COALESCE(sh.vintage, '') as vintage, ph.media_url \
+            "query": "SELECT ph.show_id, sh.name as show_name, COALESCE(sh.vintage, '') as vintage, ph.media_url, \
+                      COALESCE(rs.song_type, '') as song_type, rs.type_number \
                       FROM play_history ph \
                       JOIN show sh ON ph.show_id = sh.id \
+                      LEFT JOIN rel_show_song rs ON rs.show_id = ph.show_id AND rs.song_id = ph.song_id \
                       WHERE ph.song_id=@song_id AND ph.status=0",
-            "returns": ["show_id", "show_name", "vintage", "media_url"]
+            "returns": ["show_id", "show_name", "vintage", "media_url", "song_type", "type_number"]
         }
     });
 
@@ -324,6 +345,10 @@ pub fn cmd_learning_song_review(
             let show_name_val = ph_row["show_name"].as_str().unwrap_or("").to_string();
             let vintage_val = ph_row["vintage"].as_str().unwrap_or("").to_string();
             let media_url = ph_row["media_url"].as_str().unwrap_or("").to_string();
+            let song_type_val = song_type::label(
+                ph_row["song_type"].as_str().unwrap_or(""),
+                ph_row["type_number"].as_i64(),
+            );
 
             // Find or create the ShowMedia entry for this show
             let show_entry = shows.iter_mut().find(|s| s.show_id == show_id_val);
@@ -340,6 +365,7 @@ pub fn cmd_learning_song_review(
                     show_id: show_id_val,
                     show_name: show_name_val,
                     vintage: vintage_val,
+                    song_type: song_type_val,
                     media_urls,
                 });
             }
@@ -702,6 +728,129 @@ pub fn cmd_learning_song_stats(
     Ok(json!({"count": count, "results": result.data}))
 }
 
+// ---------------------------------------------------------------------------
+// learning-suggest --limit
+// ---------------------------------------------------------------------------
+
+/// Score added per graded miss within [`SUGGEST_RECENT_DAYS`]; each play adds 1.
+const SUGGEST_MISS_WEIGHT: f64 = 3.0;
+/// How far back a miss counts as recent.
+const SUGGEST_RECENT_DAYS: i64 = 90;
+/// Graduated songs become candidates again this long after graduating, and
+/// gain 1 point per this many days since.
+const SUGGEST_RELEARN_AFTER_DAYS: i64 = 180;
+
+/// Rank songs worth adding to learning. Candidates are non-deleted songs
+/// without an active learning record that were played at least once or
+/// graduated at least [`SUGGEST_RELEARN_AFTER_DAYS`] ago. The score adds the
+/// play count, weighted recent misses, time since graduation and the
+/// (log-scaled) play count of the song's most-played show.
+pub fn cmd_learning_suggest(conn: &mut Connection, limit: u32) -> Result<Value, AppError> {
+    if limit == 0 {
+        return Err(AppError::InvalidParameter(
+            "limit must be at least 1".into(),
+        ));
+    }
+
+    let now = models::now_unix();
+    let query_json = json!({
+        "suggest_candidates": {
+            "query": "WITH song_plays AS ( \
+                        SELECT song_id, COUNT(*) as plays, \
+                        SUM(CASE WHEN correct_guess = 0 AND created_at >= @recent_since THEN 1 ELSE 0 END) as recent_misses \
+                        FROM play_history WHERE status = 0 GROUP BY song_id), \
+                      show_plays AS ( \
+                        SELECT show_id, COUNT(*) as plays FROM play_history WHERE status = 0 GROUP BY show_id), \
+                      song_show_plays AS ( \
+                        SELECT rs.song_id, MAX(sp.plays) as plays FROM rel_show_song rs \
+                        JOIN show_plays sp ON sp.show_id = rs.show_id GROUP BY rs.song_id), \
+                      graduations AS ( \
+                        SELECT song_id, MAX(last_level_up_at) as graduated_at FROM learning \
+                        WHERE graduated = 1 GROUP BY song_id) \
+                      SELECT s.id as song_id, s.name as song_name, a.name as artist_name, \
+                      COALESCE(p.plays, 0) as play_count, COALESCE(p.recent_misses, 0) as recent_misses, \
+                      g.graduated_at, COALESCE(ssp.plays, 0) as show_play_count \
+                      FROM song s \
+                      JOIN artist a ON a.id = s.artist_id \
+                      LEFT JOIN song_plays p ON p.song_id = s.id \
+                      LEFT JOIN graduations g ON g.song_id = s.id \
+                      LEFT JOIN song_show_plays ssp ON ssp.song_id = s.id \
+                      WHERE s.status = 0 \
+                      AND NOT EXISTS (SELECT 1 FROM learning l WHERE l.song_id = s.id AND l.graduated = 0) \
+                      AND (g.graduated_at IS NULL OR g.graduated_at <= @relearn_before) \
+                      AND (p.plays > 0 OR g.graduated_at IS NOT NULL)",
+            "returns": ["song_id", "song_name", "artist_name", "play_count", "recent_misses",
+                        "graduated_at", "show_play_count"],
+            "args": {
+                "recent_since": {"type": "integer"},
+                "relearn_before": {"type": "integer"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let params = json!({
+        "recent_since": now - SUGGEST_RECENT_DAYS * 86400,
+        "relearn_before": now - SUGGEST_RELEARN_AFTER_DAYS * 86400
+    });
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "suggest_candidates", &params)
+        .map_err(AppError::from)?;
+
+    let mut scored: Vec<(f64, Value)> = result
+        .data
+        .into_iter()
+        .map(|row| {
+            let plays = row["play_count"].as_i64().unwrap_or(0);
+            let misses = row["recent_misses"].as_i64().unwrap_or(0);
+            let show_plays = row["show_play_count"].as_i64().unwrap_or(0);
+            let graduated_days_ago = row["graduated_at"].as_i64().map(|t| (now - t) / 86400);
+            let score = plays as f64
+                + SUGGEST_MISS_WEIGHT * misses as f64
+                + graduated_days_ago.map_or(0.0, |d| d as f64 / SUGGEST_RELEARN_AFTER_DAYS as f64)
+                + (1.0 + show_plays as f64).ln();
+            let score = (score * 100.0).round() / 100.0;
+            let entry = json!({
+                "song_id": row["song_id"],
+                "song_name": row["song_name"],
+                "artist_name": row["artist_name"],
+                "score": score,
+                "play_count": plays,
+                "recent_misses": misses,
+                "graduated_days_ago": graduated_days_ago,
+                "show_play_count": show_plays
+            });
+            (score, entry)
+        })
+        .collect();
+    scored.sort_by(|(a, ra), (b, rb)| {
+        b.total_cmp(a)
+            .then_with(|| ra["song_name"].as_str().cmp(&rb["song_name"].as_str()))
+            .then_with(|| ra["song_id"].as_str().cmp(&rb["song_id"].as_str()))
+    });
+    scored.truncate(limit as usize);
+
+    let results: Vec<Value> = scored.into_iter().map(|(_, entry)| entry).collect();
+    let song_ids: Vec<&str> = results
+        .iter()
+        .filter_map(|r| r["song_id"].as_str())
+        .collect();
+    // Graduated songs must also be confirmed via --relearn-song-ids
+    let relearn_song_ids: Vec<&str> = results
+        .iter()
+        .filter(|r| !r["graduated_days_ago"].is_null())
+        .filter_map(|r| r["song_id"].as_str())
+        .collect();
+
+    Ok(json!({
+        "count": results.len(),
+        "song_ids": song_ids.join(","),
+        "relearn_song_ids": relearn_song_ids.join(","),
+        "results": results
+    }))
+}
+
 // ---------------------------------------------------------------------------
 // Review HTML helpers
 // ---------------------------------------------------------------------------
@@ -751,6 +900,7 @@ fn build_review_html(
                         "showId": escape_html(&show.show_id),
                         "showName": escape_html(&show.show_name),
                         "vintage": escape_html(&show.vintage),
+                        "songType": escape_html(&show.song_type),
                         "mediaUrls": media_urls
                     })
                 })
@@ -780,6 +930,8 @@ struct ShowMedia {
     show_id: String,
     show_name: String,
     vintage: String,
+    /// Song type label within this show, e.g. "OP2"; empty when unknown
+    song_type: String,
     media_urls: Vec<String>,
 }
 
@@ -969,6 +1121,7 @@ mod tests {
                 show_id: "show-id-1".into(),
                 show_name: "Show A".into(),
                 vintage: "Winter 2024".into(),
+                song_type: "OP2".into(),
                 media_urls: vec!["https://example.com/video.webm".into()],
             }],
         }];
@@ -979,6 +1132,7 @@ mod tests {
         assert!(html.contains("Test Song"));
         assert!(html.contains("Test Artist"));
         assert!(html.contains("Show A"));
+        assert!(html.contains("\"songType\":\"OP2\""));
         assert!(html.contains("https://example.com/video.webm"));
         assert!(html.contains(".webm"));
         // Level display: stored 5 → displayed 6
@@ -1015,6 +1169,7 @@ mod tests {
                 show_id: "show-id-xss".into(),
                 show_name: "Show <1>".into(),
                 vintage: "".into(),
+                song_type: "".into(),
                 media_urls: vec![],
             }],
         }];
@@ -1040,6 +1195,7 @@ mod tests {
                     show_id: "show-1".into(),
                     show_name: "Show Alpha".into(),
                     vintage: "Spring 2023".into(),
+                    song_type: "ED".into(),
                     media_urls: vec![
                         "https://example.com/a1.webm".into(),
                         "https://example.com/a2.mp3".into(),
@@ -1049,6 +1205,7 @@ mod tests {
                     show_id: "show-2".into(),
                     show_name: "Show Beta".into(),
                     vintage: "Fall 2022".into(),
+                    song_type: "".into(),
                     media_urls: vec!["https://example.com/b1.webm".into()],
                 },
             ],
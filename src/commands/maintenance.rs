@@ -0,0 +1,1048 @@
+use jankensqlhub::QueryDefinitions;
+use rusqlite::{Connection, Transaction};
+use serde_json::{Value, json};
+
+use crate::easing::{MAX_LEVEL, generate_level_up_path_json};
+use crate::error::AppError;
+use crate::models;
+use crate::name_index;
+use crate::normalize;
+use crate::table_config;
+use crate::vintage;
+
+use super::querying::scan_orphans;
+
+/// The schema the CLI expects; columns and indexes declared here are checked
+/// by `doctor`.
+const INIT_DB_SQL: &str = include_str!("../../docs/init-db.sql");
+
+// ---------------------------------------------------------------------------
+// doctor [--fix]
+// ---------------------------------------------------------------------------
+
+/// Run every integrity check in order. With `fix`, each check repairs what it
+/// safely can before the next one runs, all inside a single transaction, and
+/// every change is appended to the report.
+pub fn cmd_doctor(conn: &mut Connection, fix: bool) -> Result<Value, AppError> {
+    let now = models::now_unix();
+    let tx = conn.transaction()?;
+    let mut changes: Vec<Value> = Vec::new();
+
+    let checks = vec![
+        check_missing_tables(&tx, fix, &mut changes)?,
+        check_orphaned_references(&tx, fix, &mut changes)?,
+        check_references_to_deleted(&tx)?,
+        check_duplicate_active_learning(&tx, fix, &mut changes)?,
+        check_invalid_level_up_path(&tx, fix, now, &mut changes)?,
+        check_level_beyond_path(&tx, fix, now, &mut changes)?,
+        check_graduated_below_max_level(&tx, fix, now, &mut changes)?,
+        check_missing_columns(&tx, fix, &mut changes)?,
+        check_missing_indexes(&tx, fix, &mut changes)?,
+        check_name_index(&tx, fix, &mut changes)?,
+        check_stale_name_keys(&tx, fix, &mut changes)?,
+        check_stale_vintage_fields(&tx, fix, &mut changes)?,
+    ];
+
+    if fix {
+        tx.commit()?;
+    }
+
+    let issue_count: u64 = checks
+        .iter()
+        .map(|c| c["issue_count"].as_u64().unwrap_or(0))
+        .sum();
+
+    Ok(json!({
+        "ok": issue_count == 0,
+        "fix": fix,
+        "issue_count": issue_count,
+        "checks": checks,
+        "changes": changes
+    }))
+}
+
+/// Build the report entry for a single check.
+fn check_report(name: &str, description: &str, findings: Vec<Value>, fixed_count: usize) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "issue_count": findings.len(),
+        "fixed_count": fixed_count,
+        "findings": findings
+    })
+}
+
+/// Rows whose reference field points at a nonexistent record (same scan as
+/// `fsck`). Fix deletes orphaned `play_history`, `learning`, `rel_show_song`,
+/// `song_credit`, `artist_member` and alias rows, and clears a `play_history`
+/// row's missing `session_id` (the play itself is fine); songs with a missing
+/// artist need a manual reassignment and are left untouched.
+fn check_orphaned_references(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let findings = scan_orphans(tx)?;
+    let mut fixed_count = 0;
+
+    if fix {
+        let query_json = json!({
+            "delete_by_id": {
+                "query": "DELETE FROM #[table] WHERE id=@id",
+                "args": {
+                    "table": {"enum": ["play_history", "learning", "song_credit", "artist_member", "artist_alias", "show_alias"]},
+                    "id": {}
+                }
+            },
+            "delete_rel": {
+                "query": "DELETE FROM rel_show_song WHERE show_id=@show_id AND song_id=@song_id",
+                "args": {"show_id": {}, "song_id": {}}
+            },
+            "clear_session": {
+                "query": "UPDATE play_history SET session_id=NULL WHERE id=@id",
+                "args": {"id": {}}
+            }
+        });
+        let queries = QueryDefinitions::from_json(query_json)
+            .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+        for finding in &findings {
+            let table = finding["table"].as_str().unwrap_or("");
+            let id = finding["id"].as_str().unwrap_or("");
+            if finding["field"] == "session_id" {
+                jankensqlhub::query_run_sqlite_with_transaction(
+                    tx,
+                    &queries,
+                    "clear_session",
+                    &json!({"id": id}),
+                )
+                .map_err(AppError::from)?;
+                fixed_count += 1;
+                changes.push(json!({
+                    "check": "orphaned_references",
+                    "action": "clear_reference",
+                    "table": table,
+                    "id": id,
+                    "field": "session_id"
+                }));
+                continue;
+            }
+            match table {
+                "play_history" | "learning" | "song_credit" | "artist_member" | "artist_alias"
+                | "show_alias" => {
+                    jankensqlhub::query_run_sqlite_with_transaction(
+                        tx,
+                        &queries,
+                        "delete_by_id",
+                        &json!({"table": table, "id": id}),
+                    )
+                    .map_err(AppError::from)?;
+                }
+                "rel_show_song" => {
+                    let Some((show_id, song_id)) = id.split_once(':') else {
+                        continue;
+                    };
+                    jankensqlhub::query_run_sqlite_with_transaction(
+                        tx,
+                        &queries,
+                        "delete_rel",
+                        &json!({"show_id": show_id, "song_id": song_id}),
+                    )
+                    .map_err(AppError::from)?;
+                }
+                _ => continue,
+            }
+            fixed_count += 1;
+            changes.push(json!({
+                "check": "orphaned_references",
+                "action": "delete",
+                "table": table,
+                "id": id
+            }));
+        }
+    }
+
+    Ok(check_report(
+        "orphaned_references",
+        "Rows referencing a nonexistent artist, show, song or game session",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Active rows pointing at a soft-deleted record. Report-only: the right
+/// repair (restore the target, reassign, or soft-delete the row) is a
+/// judgement call.
+fn check_references_to_deleted(tx: &Transaction) -> Result<Value, AppError> {
+    let query_json = json!({
+        "references_to_deleted": {
+            "query": "SELECT 'song' as tbl, s.id, 'artist_id' as field, s.artist_id as target_id \
+                      FROM song s JOIN artist a ON a.id = s.artist_id \
+                      WHERE s.status = 0 AND a.status = 1 \
+                      UNION ALL \
+                      SELECT 'play_history', ph.id, 'show_id', ph.show_id \
+                      FROM play_history ph JOIN show sh ON sh.id = ph.show_id \
+                      WHERE ph.status = 0 AND sh.status = 1 \
+                      UNION ALL \
+                      SELECT 'play_history', ph.id, 'song_id', ph.song_id \
+                      FROM play_history ph JOIN song s ON s.id = ph.song_id \
+                      WHERE ph.status = 0 AND s.status = 1 \
+                      ORDER BY 1, 2, 3",
+            "returns": ["tbl", "id", "field", "target_id"]
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result = jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        &queries,
+        "references_to_deleted",
+        &json!({}),
+    )
+    .map_err(AppError::from)?;
+
+    let findings: Vec<Value> = result
+        .data
+        .into_iter()
+        .map(|row| {
+            json!({
+                "table": row["tbl"],
+                "id": row["id"],
+                "field": row["field"],
+                "target_id": row["target_id"]
+            })
+        })
+        .collect();
+
+    Ok(check_report(
+        "references_to_deleted",
+        "Active rows referencing a soft-deleted record (report only)",
+        findings,
+        0,
+    ))
+}
+
+/// Songs with more than one in-progress learning record. Fix keeps the most
+/// advanced record (level → last level-up → id) and deletes the rest.
+fn check_duplicate_active_learning(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "duplicate_active": {
+            "query": "SELECT song_id, id FROM learning \
+                      WHERE graduated = 0 AND song_id IN ( \
+                        SELECT song_id FROM learning WHERE graduated = 0 \
+                        GROUP BY song_id HAVING COUNT(*) > 1 \
+                      ) \
+                      ORDER BY song_id, level DESC, last_level_up_at DESC, id",
+            "returns": ["song_id", "id"]
+        },
+        "delete_learning": {
+            "query": "DELETE FROM learning WHERE id=@id",
+            "args": {"id": {}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result = jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        &queries,
+        "duplicate_active",
+        &json!({}),
+    )
+    .map_err(AppError::from)?;
+
+    // Rows arrive grouped by song, most advanced first
+    let mut findings: Vec<Value> = Vec::new();
+    for row in &result.data {
+        let song_id = &row["song_id"];
+        match findings.last_mut() {
+            Some(last) if last["song_id"] == *song_id => {
+                last["learning_ids"]
+                    .as_array_mut()
+                    .unwrap()
+                    .push(row["id"].clone());
+            }
+            _ => findings.push(json!({"song_id": song_id, "learning_ids": [row["id"]]})),
+        }
+    }
+
+    let mut fixed_count = 0;
+    if fix {
+        for finding in &findings {
+            let ids = finding["learning_ids"].as_array().unwrap();
+            for id in &ids[1..] {
+                jankensqlhub::query_run_sqlite_with_transaction(
+                    tx,
+                    &queries,
+                    "delete_learning",
+                    &json!({"id": id}),
+                )
+                .map_err(AppError::from)?;
+                changes.push(json!({
+                    "check": "duplicate_active_learning",
+                    "action": "delete",
+                    "table": "learning",
+                    "id": id,
+                    "kept_id": ids[0]
+                }));
+            }
+            fixed_count += 1;
+        }
+    }
+
+    Ok(check_report(
+        "duplicate_active_learning",
+        "Songs with more than one in-progress learning record",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Learning records whose `level_up_path` is not a non-empty JSON array.
+/// Fix replaces the path with the default generated one.
+fn check_invalid_level_up_path(
+    tx: &Transaction,
+    fix: bool,
+    now: i64,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    // json_type() raises on malformed JSON, so guard it with json_valid()
+    let query_json = json!({
+        "invalid_path": {
+            "query": "SELECT id, song_id, level_up_path FROM learning \
+                      WHERE CASE WHEN json_valid(level_up_path) \
+                        THEN json_type(level_up_path) != 'array' \
+                          OR json_array_length(level_up_path) = 0 \
+                        ELSE 1 END \
+                      ORDER BY id",
+            "returns": ["id", "song_id", "level_up_path"]
+        },
+        "reset_path": {
+            "query": "UPDATE learning SET level_up_path=@path, updated_at=@now WHERE id=@id",
+            "args": {"id": {}, "path": {}, "now": {"type": "integer"}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result =
+        jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "invalid_path", &json!({}))
+            .map_err(AppError::from)?;
+
+    let mut fixed_count = 0;
+    if fix {
+        let path = generate_level_up_path_json(MAX_LEVEL);
+        for row in &result.data {
+            jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                "reset_path",
+                &json!({"id": row["id"], "path": path, "now": now}),
+            )
+            .map_err(AppError::from)?;
+            fixed_count += 1;
+            changes.push(json!({
+                "check": "invalid_level_up_path",
+                "action": "update",
+                "table": "learning",
+                "id": row["id"],
+                "field": "level_up_path",
+                "from": row["level_up_path"],
+                "to": path
+            }));
+        }
+    }
+
+    Ok(check_report(
+        "invalid_level_up_path",
+        "Learning records whose level_up_path is not a non-empty JSON array",
+        result.data,
+        fixed_count,
+    ))
+}
+
+/// Learning records whose `level` indexes past the end of `level_up_path`.
+/// Fix clamps the level to the last index of the path.
+fn check_level_beyond_path(
+    tx: &Transaction,
+    fix: bool,
+    now: i64,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "beyond_path": {
+            "query": "SELECT id, song_id, level, json_array_length(level_up_path) - 1 as max_level \
+                      FROM learning \
+                      WHERE json_valid(level_up_path) AND json_type(level_up_path) = 'array' \
+                      AND level >= json_array_length(level_up_path) \
+                      ORDER BY id",
+            "returns": ["id", "song_id", "level", "max_level"]
+        },
+        "set_level": {
+            "query": "UPDATE learning SET level=@level, updated_at=@now WHERE id=@id",
+            "args": {"id": {}, "level": {"type": "integer"}, "now": {"type": "integer"}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result =
+        jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "beyond_path", &json!({}))
+            .map_err(AppError::from)?;
+
+    let mut fixed_count = 0;
+    if fix {
+        for row in &result.data {
+            set_learning_level(tx, &queries, row, now)?;
+            fixed_count += 1;
+            changes.push(level_change("level_beyond_path", row));
+        }
+    }
+
+    Ok(check_report(
+        "level_beyond_path",
+        "Learning records whose level is past the end of level_up_path",
+        result.data,
+        fixed_count,
+    ))
+}
+
+/// Graduated learning records not at the last level of their path.
+/// Fix raises the level to the last index, matching `learning-song-graduate-ids`.
+fn check_graduated_below_max_level(
+    tx: &Transaction,
+    fix: bool,
+    now: i64,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "graduated_below_max": {
+            "query": "SELECT id, song_id, level, json_array_length(level_up_path) - 1 as max_level \
+                      FROM learning \
+                      WHERE graduated = 1 \
+                      AND json_valid(level_up_path) AND json_type(level_up_path) = 'array' \
+                      AND level < json_array_length(level_up_path) - 1 \
+                      ORDER BY id",
+            "returns": ["id", "song_id", "level", "max_level"]
+        },
+        "set_level": {
+            "query": "UPDATE learning SET level=@level, updated_at=@now WHERE id=@id",
+            "args": {"id": {}, "level": {"type": "integer"}, "now": {"type": "integer"}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result = jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        &queries,
+        "graduated_below_max",
+        &json!({}),
+    )
+    .map_err(AppError::from)?;
+
+    let mut fixed_count = 0;
+    if fix {
+        for row in &result.data {
+            set_learning_level(tx, &queries, row, now)?;
+            fixed_count += 1;
+            changes.push(level_change("graduated_below_max_level", row));
+        }
+    }
+
+    Ok(check_report(
+        "graduated_below_max_level",
+        "Graduated learning records below the last level of their path",
+        result.data,
+        fixed_count,
+    ))
+}
+
+/// Set a learning record's level to the `max_level` of a finding row.
+fn set_learning_level(
+    tx: &Transaction,
+    queries: &QueryDefinitions,
+    row: &Value,
+    now: i64,
+) -> Result<(), AppError> {
+    jankensqlhub::query_run_sqlite_with_transaction(
+        tx,
+        queries,
+        "set_level",
+        &json!({"id": row["id"], "level": row["max_level"], "now": now}),
+    )
+    .map_err(AppError::from)?;
+    Ok(())
+}
+
+fn level_change(check: &str, row: &Value) -> Value {
+    json!({
+        "check": check,
+        "action": "update",
+        "table": "learning",
+        "id": row["id"],
+        "field": "level",
+        "from": row["level"],
+        "to": row["max_level"]
+    })
+}
+
+/// Tables declared in `docs/init-db.sql` that the database lacks, as in
+/// databases created before the table was added. Fix runs the declaring
+/// `CREATE TABLE IF NOT EXISTS` statement. Runs first so the other checks can
+/// query every table, even without `fix`. The full-text tables are left to
+/// `name_index`.
+fn check_missing_tables(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "table_exists": {
+            "query": "SELECT COUNT(*) as cnt FROM sqlite_master WHERE type='table' AND name=@name",
+            "returns": ["cnt"],
+            "args": {"name": {}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let name_index_objects = name_index::schema_objects(name_index::schema_sql(INIT_DB_SQL));
+    let mut findings: Vec<Value> = Vec::new();
+    let mut fixed_count = 0;
+    for (name, statement) in schema_tables(INIT_DB_SQL) {
+        if name_index_objects.contains(&name) {
+            continue;
+        }
+        let result = jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "table_exists",
+            &json!({"name": name}),
+        )
+        .map_err(AppError::from)?;
+        if result.data[0]["cnt"].as_i64().unwrap_or(0) > 0 {
+            continue;
+        }
+        // Statement comes verbatim from the bundled init-db.sql. Without
+        // `fix` the transaction is rolled back, so this only lets the later
+        // checks query the table.
+        tx.execute_batch(&statement)?;
+        if fix {
+            fixed_count += 1;
+            changes.push(json!({
+                "check": "missing_tables",
+                "action": "create_table",
+                "name": name
+            }));
+        }
+        findings.push(json!({"name": name}));
+    }
+
+    Ok(check_report(
+        "missing_tables",
+        "Tables declared in init-db.sql that are missing from the database",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Columns declared in `docs/init-db.sql` that an existing table lacks, as in
+/// databases created before a column was added. Fix runs `ALTER TABLE ... ADD
+/// COLUMN` with the declared definition. Runs before the index checks so
+/// indexes on new columns can be created.
+fn check_missing_columns(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "table_columns": {
+            "query": "SELECT name FROM pragma_table_info(@table)",
+            "returns": ["name"],
+            "args": {"table": {}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut findings: Vec<Value> = Vec::new();
+    let mut fixed_count = 0;
+    let mut table_columns: (String, Vec<Value>) = (String::new(), Vec::new());
+    for (table, column, definition) in schema_columns(INIT_DB_SQL) {
+        if table_columns.0 != table {
+            let result = jankensqlhub::query_run_sqlite_with_transaction(
+                tx,
+                &queries,
+                "table_columns",
+                &json!({"table": table}),
+            )
+            .map_err(AppError::from)?;
+            table_columns = (table.clone(), result.data);
+        }
+        // Missing tables are not this check's concern
+        let existing = &table_columns.1;
+        if existing.is_empty() || existing.iter().any(|c| c["name"] == column) {
+            continue;
+        }
+        let statement = format!("ALTER TABLE \"{table}\" ADD COLUMN {definition};");
+        if fix {
+            // Definition comes verbatim from the bundled init-db.sql
+            tx.execute_batch(&statement)?;
+            fixed_count += 1;
+            changes.push(json!({
+                "check": "missing_columns",
+                "action": "add_column",
+                "table": table,
+                "column": column,
+                "sql": statement
+            }));
+        }
+        findings.push(json!({"table": table, "column": column, "sql": statement}));
+    }
+
+    Ok(check_report(
+        "missing_columns",
+        "Columns declared in init-db.sql that are missing from existing tables",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Indexes declared in `docs/init-db.sql` that the database lacks.
+/// Fix runs the declaring `CREATE INDEX IF NOT EXISTS` statement.
+fn check_missing_indexes(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "index_exists": {
+            "query": "SELECT COUNT(*) as cnt FROM sqlite_master WHERE type='index' AND name=@name",
+            "returns": ["cnt"],
+            "args": {"name": {}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut findings: Vec<Value> = Vec::new();
+    let mut fixed_count = 0;
+    for (name, statement) in schema_indexes(INIT_DB_SQL) {
+        let result = jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            "index_exists",
+            &json!({"name": name}),
+        )
+        .map_err(AppError::from)?;
+        if result.data[0]["cnt"].as_i64().unwrap_or(0) > 0 {
+            continue;
+        }
+        if fix {
+            // Statement comes verbatim from the bundled init-db.sql
+            tx.execute_batch(&statement)?;
+            fixed_count += 1;
+            changes.push(json!({
+                "check": "missing_indexes",
+                "action": "create_index",
+                "name": name,
+                "sql": statement
+            }));
+        }
+        findings.push(json!({"name": name, "sql": statement}));
+    }
+
+    Ok(check_report(
+        "missing_indexes",
+        "Indexes declared in init-db.sql that are missing from the database",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// The full-text name index behind `find`: its tables and triggers must exist
+/// and hold one row per indexed name field. Databases created before the
+/// index was added lack it entirely. Fix runs the full-text section of
+/// init-db.sql and rebuilds the index from the entity tables.
+fn check_name_index(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "object_exists": {
+            "query": "SELECT COUNT(*) as cnt FROM sqlite_master WHERE name=@name",
+            "returns": ["cnt"],
+            "args": {"name": {}}
+        },
+        "expected_rows": {
+            "query": "SELECT (SELECT COUNT(*) FROM artist) \
+                      + 2 * (SELECT COUNT(*) FROM song) \
+                      + 2 * (SELECT COUNT(*) FROM show) as cnt",
+            "returns": ["cnt"],
+            "args": {}
+        },
+        "indexed_rows": {
+            "query": "SELECT COUNT(*) as cnt FROM name_fts_row r JOIN name_fts f ON f.rowid = r.id",
+            "returns": ["cnt"],
+            "args": {}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+    let count = |name: &str, params: Value| -> Result<i64, AppError> {
+        let result = jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, name, &params)
+            .map_err(AppError::from)?;
+        Ok(result.data[0]["cnt"].as_i64().unwrap_or(0))
+    };
+
+    let schema = name_index::schema_sql(INIT_DB_SQL);
+    let mut findings: Vec<Value> = Vec::new();
+    for name in name_index::schema_objects(schema) {
+        if count("object_exists", json!({"name": name}))? == 0 {
+            findings.push(json!({"issue": "missing_object", "name": name}));
+        }
+    }
+    let expected = count("expected_rows", json!({}))?;
+    if findings.is_empty() {
+        let indexed = count("indexed_rows", json!({}))?;
+        if indexed != expected {
+            findings.push(json!({
+                "issue": "out_of_sync",
+                "expected_rows": expected,
+                "indexed_rows": indexed
+            }));
+        }
+    }
+
+    let mut fixed_count = 0;
+    if fix && !findings.is_empty() {
+        // Schema comes verbatim from the bundled init-db.sql
+        tx.execute_batch(schema)?;
+        tx.execute_batch(name_index::REBUILD_SQL)?;
+        fixed_count = findings.len();
+        changes.push(json!({
+            "check": "name_index",
+            "action": "rebuild_name_index",
+            "rows": expected
+        }));
+    }
+
+    Ok(check_report(
+        "name_index",
+        "Full-text name index used by find is missing or out of sync",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Normalized name keys (`name_key`, `name_romaji_key`) that are missing or
+/// differ from `normalize::name_key` of their source column: rows written
+/// before the keys existed, by other tools, or under older normalization
+/// rules. Fix recomputes the key; `updated_at` is left alone since the key
+/// is derived data.
+fn check_stale_name_keys(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let mut findings: Vec<Value> = Vec::new();
+    let mut fixed_count = 0;
+    for (table, column, key_column) in table_config::all_normalized() {
+        // Table and column names come from the static table config
+        let query_json = json!({
+            "rows": {
+                "query": format!("SELECT id, \"{column}\" as source, \"{key_column}\" as stored FROM \"{table}\" ORDER BY id"),
+                "returns": ["id", "source", "stored"],
+                "args": {}
+            },
+            "set_key": {
+                "query": format!("UPDATE \"{table}\" SET \"{key_column}\"=@key WHERE id=@id"),
+                "args": {"key": {}, "id": {}}
+            }
+        });
+        let queries = QueryDefinitions::from_json(query_json)
+            .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+        let rows =
+            jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "rows", &json!({}))
+                .map_err(AppError::from)?;
+
+        for row in rows.data {
+            let Some(source) = row["source"].as_str() else {
+                continue;
+            };
+            let expected = normalize::name_key(source);
+            if row["stored"].as_str() == Some(expected.as_str()) {
+                continue;
+            }
+            if fix {
+                jankensqlhub::query_run_sqlite_with_transaction(
+                    tx,
+                    &queries,
+                    "set_key",
+                    &json!({"key": expected, "id": row["id"]}),
+                )
+                .map_err(AppError::from)?;
+                fixed_count += 1;
+                changes.push(json!({
+                    "check": "stale_name_keys",
+                    "action": "update",
+                    "table": table,
+                    "id": row["id"],
+                    "field": key_column,
+                    "from": row["stored"],
+                    "to": expected
+                }));
+            }
+            findings.push(json!({
+                "table": table,
+                "id": row["id"],
+                "field": key_column,
+                "stored": row["stored"],
+                "expected": expected
+            }));
+        }
+    }
+
+    Ok(check_report(
+        "stale_name_keys",
+        "Normalized name keys that are missing or out of date",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Shows whose `season`, `year` and `vintage_key` do not match their parsed
+/// `vintage`: rows written before the columns existed or by other tools.
+/// Unparseable vintages are expected to have all three unset. Fix rewrites
+/// the three columns; `updated_at` is left alone.
+fn check_stale_vintage_fields(
+    tx: &Transaction,
+    fix: bool,
+    changes: &mut Vec<Value>,
+) -> Result<Value, AppError> {
+    let query_json = json!({
+        "shows": {
+            "query": "SELECT id, COALESCE(vintage, '') as vintage, season, year, vintage_key FROM show ORDER BY id",
+            "returns": ["id", "vintage", "season", "year", "vintage_key"],
+            "args": {}
+        },
+        "set_fields": {
+            "query": "UPDATE show SET season=@season, year=@year, vintage_key=@vintage_key WHERE id=@id",
+            "args": {"season": {}, "year": {"type": "integer"}, "vintage_key": {"type": "integer"}, "id": {}}
+        },
+        "clear_fields": {
+            "query": "UPDATE show SET season=NULL, year=NULL, vintage_key=NULL WHERE id=@id",
+            "args": {"id": {}}
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+    let rows = jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, "shows", &json!({}))
+        .map_err(AppError::from)?;
+
+    let mut findings: Vec<Value> = Vec::new();
+    let mut fixed_count = 0;
+    for row in rows.data {
+        let parsed = vintage::parse(row["vintage"].as_str().unwrap_or_default());
+        let expected = match parsed {
+            Some(v) => json!({"season": v.season(), "year": v.year, "vintage_key": v.key()}),
+            None => json!({"season": null, "year": null, "vintage_key": null}),
+        };
+        let stored = json!({
+            "season": row["season"],
+            "year": row["year"],
+            "vintage_key": row["vintage_key"]
+        });
+        if stored == expected {
+            continue;
+        }
+        if fix {
+            let (name, params) = match parsed {
+                Some(_) => {
+                    let mut params = expected.clone();
+                    params["id"] = row["id"].clone();
+                    ("set_fields", params)
+                }
+                None => ("clear_fields", json!({"id": row["id"]})),
+            };
+            jankensqlhub::query_run_sqlite_with_transaction(tx, &queries, name, &params)
+                .map_err(AppError::from)?;
+            fixed_count += 1;
+            changes.push(json!({
+                "check": "stale_vintage_fields",
+                "action": "update",
+                "table": "show",
+                "id": row["id"],
+                "field": "season, year, vintage_key",
+                "from": stored,
+                "to": expected
+            }));
+        }
+        findings.push(json!({
+            "id": row["id"],
+            "vintage": row["vintage"],
+            "stored": stored,
+            "expected": expected
+        }));
+    }
+
+    Ok(check_report(
+        "stale_vintage_fields",
+        "Shows whose season, year and vintage_key do not match their vintage",
+        findings,
+        fixed_count,
+    ))
+}
+
+/// Extract `(table, column, definition)` for every column of every
+/// `CREATE TABLE` in a schema script. Definitions drop trailing comments.
+fn schema_columns(sql: &str) -> Vec<(String, String, String)> {
+    let mut columns = Vec::new();
+    let mut table: Option<String> = None;
+    for line in sql.lines() {
+        let line = line.split("--").next().unwrap_or_default().trim();
+        if line.to_uppercase().starts_with("CREATE TABLE") {
+            table = line.split('"').nth(1).map(str::to_string);
+        } else if line.starts_with(')') {
+            table = None;
+        } else if let (Some(table), true) = (&table, line.starts_with('"')) {
+            let definition = line
+                .trim_end_matches(',')
+                .split_whitespace()
+                .collect::<Vec<_>>();
+            let name = definition[0].trim_matches('"').to_string();
+            columns.push((table.clone(), name, definition.join(" ")));
+        }
+    }
+    columns
+}
+
+/// Extract `(table name, statement)` for every regular `CREATE TABLE` in a
+/// schema script; virtual tables are skipped.
+fn schema_tables(sql: &str) -> Vec<(String, String)> {
+    // Comments go first: they may contain semicolons
+    let sql = sql
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    sql.split(';')
+        .filter_map(|stmt| {
+            let stmt = stmt.trim();
+            if !stmt.to_uppercase().starts_with("CREATE TABLE") {
+                return None;
+            }
+            let name = stmt.split('"').nth(1)?;
+            Some((name.to_string(), format!("{stmt};")))
+        })
+        .collect()
+}
+
+/// Extract `(index name, statement)` for every `CREATE INDEX` in a schema script.
+fn schema_indexes(sql: &str) -> Vec<(String, String)> {
+    sql.split(';')
+        .filter_map(|stmt| {
+            let stmt = stmt
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let stmt = stmt.trim();
+            let upper = stmt.to_uppercase();
+            if !upper.starts_with("CREATE INDEX") && !upper.starts_with("CREATE UNIQUE INDEX") {
+                return None;
+            }
+            let mut words = stmt.split_whitespace();
+            words.find(|w| w.eq_ignore_ascii_case("INDEX"))?;
+            let mut next = words.next()?;
+            if next.eq_ignore_ascii_case("IF") {
+                words.next(); // NOT
+                words.next(); // EXISTS
+                next = words.next()?;
+            }
+            Some((next.trim_matches('"').to_string(), format!("{stmt};")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_indexes_from_init_db() {
+        let indexes = schema_indexes(INIT_DB_SQL);
+        assert_eq!(indexes.len(), 14);
+        assert_eq!(indexes[0].0, "idx_learning_song_id");
+        assert_eq!(
+            indexes[0].1,
+            "CREATE INDEX IF NOT EXISTS idx_learning_song_id ON learning(song_id);"
+        );
+    }
+
+    #[test]
+    fn test_schema_tables_from_init_db() {
+        let tables = schema_tables(INIT_DB_SQL);
+        let names: Vec<&str> = tables.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "artist",
+                "show",
+                "song",
+                "play_history",
+                "learning",
+                "rel_show_song",
+                "song_credit",
+                "artist_member",
+                "artist_alias",
+                "show_alias",
+                "game_session",
+                "name_fts_row"
+            ]
+        );
+        assert!(
+            tables[6]
+                .1
+                .starts_with("CREATE TABLE IF NOT EXISTS \"song_credit\" (")
+        );
+        assert!(tables[6].1.ends_with(");"));
+    }
+
+    #[test]
+    fn test_schema_columns_from_init_db() {
+        let columns = schema_columns(INIT_DB_SQL);
+        assert!(columns.contains(&(
+            "show".to_string(),
+            "name_romaji_key".to_string(),
+            "\"name_romaji_key\" TEXT".to_string()
+        )));
+        assert!(columns.contains(&(
+            "artist".to_string(),
+            "status".to_string(),
+            "\"status\" INTEGER NOT NULL DEFAULT 0".to_string()
+        )));
+        // Table constraints and virtual tables are not columns
+        assert!(!columns.iter().any(|(t, _, _)| t == "name_fts"));
+        assert_eq!(columns.iter().filter(|(t, _, _)| t == "artist").count(), 7);
+    }
+
+    #[test]
+    fn test_schema_indexes_without_if_not_exists() {
+        let sql = "-- comment\nCREATE UNIQUE INDEX \"idx_a\" ON a(x);\nCREATE TABLE t (x);";
+        assert_eq!(
+            schema_indexes(sql),
+            vec![(
+                "idx_a".to_string(),
+                "CREATE UNIQUE INDEX \"idx_a\" ON a(x);".to_string()
+            )]
+        );
+    }
+}
@@ -1,13 +1,23 @@
 mod data_management;
 mod learning;
+mod maintenance;
 mod querying;
+mod transfer;
 
-pub use data_management::{cmd_bulk_reassign, cmd_create, cmd_delete, cmd_update};
+pub use data_management::{
+    cmd_add_member, cmd_bulk_reassign, cmd_create, cmd_delete, cmd_merge, cmd_promote_alias,
+    cmd_remove_member, cmd_restore, cmd_soft_delete, cmd_update,
+};
 pub use learning::{
     cmd_learning_batch, cmd_learning_by_song_ids, cmd_learning_due, cmd_learning_song_graduate_ids,
     cmd_learning_song_levelup_ids, cmd_learning_song_review, cmd_learning_song_stats,
+    cmd_learning_suggest,
 };
+pub use maintenance::cmd_doctor;
 pub use querying::{
-    cmd_batch_get, cmd_duplicates, cmd_get, cmd_search, cmd_shows_by_artist_ids,
-    cmd_songs_by_artist_ids,
+    cmd_batch_get, cmd_duplicates, cmd_duplicates_fuzzy, cmd_find, cmd_fsck, cmd_game_sessions,
+    cmd_get, cmd_play_stats, cmd_search, cmd_shows_by_artist_ids, cmd_shows_by_vintage,
+    cmd_songs_by_artist_ids, cmd_songs_by_composer_ids, cmd_songs_by_session_ids,
+    cmd_songs_by_show_ids,
 };
+pub use transfer::{cmd_export, cmd_import_dump, cmd_sync};
@@ -1,11 +1,17 @@
+use std::collections::{BTreeSet, HashMap};
+
 use jankensqlhub::QueryDefinitions;
-use rusqlite::Connection;
+use rusqlite::{Connection, Transaction};
 use serde_json::{Map, Value, json};
 
-use crate::encoding::url_decode;
 use crate::error::AppError;
+use crate::fuzzy;
 use crate::models;
+use crate::name_index;
+use crate::normalize;
+use crate::search_term;
 use crate::table_config;
+use crate::vintage;
 
 // ---------------------------------------------------------------------------
 // get <table> <id> --fields
@@ -16,6 +22,7 @@ pub fn cmd_get(
     table: &str,
     id: &str,
     fields_str: &str,
+    include_deleted: bool,
 ) -> Result<Value, AppError> {
     models::validate_table(table, models::GET_TABLES)?;
     let fields = models::parse_fields(fields_str);
@@ -27,7 +34,10 @@ pub fn cmd_get(
 
     let query_json = json!({
         "read_by_id": {
-            "query": "SELECT ~[fields] FROM #[table] WHERE id=@id",
+            "query": format!(
+                "SELECT ~[fields] FROM #[table] WHERE id=@id{}",
+                models::status_filter(table, include_deleted)
+            ),
             "returns": "~[fields]",
             "args": {
                 "table": {"enum": table_config::build_table_enum(models::GET_TABLES)},
@@ -62,6 +72,7 @@ pub fn cmd_batch_get(
     table: &str,
     ids_str: &str,
     fields_str: &str,
+    include_deleted: bool,
 ) -> Result<Value, AppError> {
     models::validate_table(table, models::GET_TABLES)?;
 
@@ -84,7 +95,10 @@ pub fn cmd_batch_get(
 
     let query_json = json!({
         "batch_read_by_ids": {
-            "query": "SELECT ~[fields] FROM #[table] WHERE id IN :[ids]",
+            "query": format!(
+                "SELECT ~[fields] FROM #[table] WHERE id IN :[ids]{}",
+                models::status_filter(table, include_deleted)
+            ),
             "returns": "~[fields]",
             "args": {
                 "table": {"enum": table_config::build_table_enum(models::GET_TABLES)},
@@ -122,6 +136,7 @@ pub fn cmd_search(
     table: &str,
     term_json: &str,
     fields_str: &str,
+    options: &models::SearchOptions,
 ) -> Result<Value, AppError> {
     let fields = models::parse_fields(fields_str);
     if fields.is_empty() {
@@ -132,82 +147,61 @@ pub fn cmd_search(
     if term.is_empty() {
         return Err(AppError::InvalidParameter("term cannot be empty".into()));
     }
+    if options.limit == Some(0) {
+        return Err(AppError::InvalidParameter(
+            "limit must be at least 1".into(),
+        ));
+    }
+
+    // Any `table.column` key, field or sort switches to joined mode
+    let namespaced = |key: &str| key.contains('.');
+    if search_term::column_keys(&term).into_iter().any(namespaced)
+        || fields.iter().any(|f| namespaced(f))
+        || options.order_by.as_deref().is_some_and(namespaced)
+    {
+        return search_joined(conn, table, &term, &fields, options);
+    }
 
-    // Validate term keys against searchable fields for this table
+    // Term keys are validated against the searchable whitelist before they
+    // are embedded; all values are bound as parameters
     let searchable = models::allowed_term_keys(table)?;
-    let mut where_parts: Vec<String> = Vec::new();
-    let mut val_args = serde_json::Map::new();
-    let mut val_values = serde_json::Map::new();
-
-    for (col, cond) in &term {
-        // Validate column name against searchable whitelist
-        if !searchable.contains(&col.as_str()) {
-            return Err(AppError::InvalidParameter(format!(
+    let compiled = search_term::compile(&term, &|col, normalized| {
+        if searchable.contains(&col) {
+            Ok(format!("\"{}\"", term_column(table, col, normalized)?))
+        } else {
+            Err(AppError::InvalidParameter(format!(
                 "Invalid term key for {table}: {col}. Allowed: {}",
                 searchable.join(", ")
-            )));
+            )))
         }
+    })?;
 
-        let cond_obj = cond.as_object().ok_or_else(|| {
-            AppError::InvalidParameter(format!("Term condition for '{col}' must be an object"))
-        })?;
-        let raw_value = cond_obj
-            .get("value")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                AppError::InvalidParameter(format!(
-                    "Term condition for '{col}' must have a 'value' string"
-                ))
-            })?;
-        let value = url_decode(raw_value).map_err(|e| {
-            AppError::InvalidParameter(format!(
-                "URL decoding error for search value of '{col}': {e}"
-            ))
-        })?;
-        let match_mode = cond_obj
-            .get("match")
-            .and_then(|v| v.as_str())
-            .unwrap_or("exact");
-
-        if !models::MATCH_MODES.contains(&match_mode) {
-            return Err(AppError::InvalidParameter(format!(
-                "Invalid match mode: {match_mode}. Allowed: {}",
-                models::MATCH_MODES.join(", ")
-            )));
-        }
-
-        let val_key = format!("val_{col}");
-
-        // Column name is safe to embed directly — validated against searchable whitelist above
-        let prepared_value = match match_mode {
-            "exact" => {
-                where_parts.push(format!("\"{col}\"=@{val_key}"));
-                value
-            }
-            "exact-i" => {
-                where_parts.push(format!("LOWER(\"{col}\")=LOWER(@{val_key})"));
-                value
-            }
-            "starts-with" => {
-                where_parts.push(format!("LOWER(\"{col}\") LIKE LOWER(@{val_key})"));
-                format!("{value}%")
-            }
-            "ends-with" => {
-                where_parts.push(format!("LOWER(\"{col}\") LIKE LOWER(@{val_key})"));
-                format!("%{value}")
-            }
-            "contains" => {
-                where_parts.push(format!("LOWER(\"{col}\") LIKE LOWER(@{val_key})"));
-                format!("%{value}%")
-            }
-            _ => unreachable!(),
-        };
-
-        val_args.insert(val_key.clone(), json!({}));
-        val_values.insert(val_key, json!(prepared_value));
-    }
+    let where_sql = format!(
+        "({}){}",
+        compiled.sql,
+        models::status_filter(table, options.include_deleted)
+    );
 
-    let where_sql = where_parts.join(" AND ");
+    let paginated = options.limit.is_some() || options.offset > 0;
+    let key: &[&str] = if table == "rel_show_song" {
+        &["show_id", "song_id"]
+    } else {
+        &["id"]
+    };
+    let order_sql = match options.order_by.as_deref() {
+        Some(order_by) => format!(
+            " ORDER BY {}",
+            models::parse_order_by(order_by, models::get_fields(table)?, key)?
+        ),
+        None if paginated => format!(" ORDER BY {}", models::parse_order_by("", &[], key)?),
+        None => String::new(),
+    };
+    // SQLite requires LIMIT before OFFSET; -1 means no limit
+    let page_sql = if paginated {
+        " LIMIT @limit OFFSET @offset"
+    } else {
+        ""
+    };
 
     // Build args: table + fields + per-value params
     let mut args = json!({
@@ -217,15 +211,26 @@ pub fn cmd_search(
         }
     });
     let args_map = args.as_object_mut().unwrap();
-    for (k, v) in val_args {
-        args_map.insert(k, v);
+    for (k, v) in &compiled.args {
+        args_map.insert(k.clone(), v.clone());
+    }
+    let mut count_args = args_map.clone();
+    count_args.remove("fields");
+    if paginated {
+        args_map.insert("limit".into(), json!({"type": "integer"}));
+        args_map.insert("offset".into(), json!({"type": "integer"}));
     }
 
     let query_json = json!({
         "search": {
-            "query": format!("SELECT ~[fields] FROM #[table] WHERE {where_sql}"),
+            "query": format!("SELECT ~[fields] FROM #[table] WHERE {where_sql}{order_sql}{page_sql}"),
             "returns": "~[fields]",
             "args": args
+        },
+        "count": {
+            "query": format!("SELECT COUNT(*) as total FROM #[table] WHERE {where_sql}"),
+            "returns": ["total"],
+            "args": count_args
         }
     });
 
@@ -238,14 +243,285 @@ pub fn cmd_search(
         "fields": fields,
     });
     let params_map = params.as_object_mut().unwrap();
-    for (k, v) in val_values {
+    for (k, v) in compiled.params {
         params_map.insert(k, v);
     }
+    let mut count_params = params_map.clone();
+    count_params.remove("fields");
+    if paginated {
+        params_map.insert("limit".into(), json!(options.limit.map_or(-1, i64::from)));
+        params_map.insert("offset".into(), json!(options.offset));
+    }
 
     let result = jankensqlhub::query_run_sqlite(conn, &queries, "search", &params)
         .map_err(AppError::from)?;
+    let count = jankensqlhub::query_run_sqlite(conn, &queries, "count", &json!(count_params))
+        .map_err(AppError::from)?;
+    let total = count.data[0]["total"].as_u64().unwrap_or(0);
 
-    Ok(json!({"results": result.data}))
+    Ok(search_response(result.data, total, options))
+}
+
+/// Build the `search` output, with the next page's offset (or null once the
+/// last matching row is returned).
+fn search_response(rows: Vec<Value>, total: u64, options: &models::SearchOptions) -> Value {
+    let paginated = options.limit.is_some() || options.offset > 0;
+    let next = u64::from(options.offset) + rows.len() as u64;
+    let next_offset = if paginated && next < total {
+        json!(next)
+    } else {
+        Value::Null
+    };
+
+    json!({
+        "total": total,
+        "count": rows.len(),
+        "next_offset": next_offset,
+        "results": rows
+    })
+}
+
+/// The column a term condition compares: the column itself, or its stored
+/// normalized key for `"match": "normalized"`.
+fn term_column<'a>(table: &str, col: &'a str, normalized: bool) -> Result<&'a str, AppError> {
+    if !normalized {
+        return Ok(col);
+    }
+    table_config::normalized_key(table, col).ok_or_else(|| {
+        let supported: Vec<String> = table_config::all_normalized()
+            .into_iter()
+            .map(|(t, c, _)| format!("{t}.{c}"))
+            .collect();
+        AppError::InvalidParameter(format!(
+            "Match 'normalized' is not supported for {table}.{col}. Supported: {}",
+            supported.join(", ")
+        ))
+    })
+}
+
+/// Split a `table.column` key (bare keys belong to `base`) and validate the
+/// column against `allowed` for its table. Both parts are safe to embed.
+fn split_namespaced<'a>(
+    base: &'a str,
+    key: &'a str,
+    allowed: fn(&str) -> Result<&'static [&'static str], AppError>,
+    what: &str,
+) -> Result<(&'a str, &'a str), AppError> {
+    let (table, col) = key.split_once('.').unwrap_or((base, key));
+    if !models::SEARCH_TABLES.contains(&table) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid table in {what}: {key}. Allowed: {}",
+            models::SEARCH_TABLES.join(", ")
+        )));
+    }
+    let cols = allowed(table)?;
+    if !cols.contains(&col) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid {what} for {table}: {col}. Allowed: {}",
+            cols.join(", ")
+        )));
+    }
+    Ok((table, col))
+}
+
+/// Joined mode of `search`: term keys, fields and order-by may be namespaced
+/// as `table.column` and are reached along [`table_config::JOIN_EDGES`].
+///
+/// Joins are LEFT JOINs, so `{"learning.song_id": {"match": "is-null"}}`
+/// finds rows without a related record. Soft-deleted joined rows are treated
+/// as missing, and `DISTINCT` collapses the fan-out from one-to-many joins.
+fn search_joined(
+    conn: &mut Connection,
+    base: &str,
+    term: &Map<String, Value>,
+    fields: &[String],
+    options: &models::SearchOptions,
+) -> Result<Value, AppError> {
+    models::validate_table(base, models::SEARCH_TABLES)?;
+
+    let mut tables: Vec<&str> = Vec::new();
+    let mut columns: Vec<String> = Vec::new();
+    for field in fields {
+        let (table, col) = split_namespaced(base, field, models::get_fields, "field")?;
+        tables.push(table);
+        columns.push(format!("\"{table}\".\"{col}\" AS \"{field}\""));
+    }
+    for key in search_term::column_keys(term) {
+        tables.push(split_namespaced(base, key, models::allowed_term_keys, "term key")?.0);
+    }
+    let compiled = search_term::compile(term, &|key, normalized| {
+        let (table, col) = split_namespaced(base, key, models::allowed_term_keys, "term key")?;
+        Ok(format!(
+            "\"{table}\".\"{}\"",
+            term_column(table, col, normalized)?
+        ))
+    })?;
+
+    let visible = |table: &str| {
+        if !options.include_deleted && models::SOFT_DELETE_TABLES.contains(&table) {
+            format!(" AND \"{table}\".\"status\" = 0")
+        } else {
+            String::new()
+        }
+    };
+    let joins: String = table_config::join_plan(base, &tables)
+        .into_iter()
+        .map(|(table, on)| format!(" LEFT JOIN \"{table}\" ON {on}{}", visible(table)))
+        .collect();
+    let select_sql = format!(
+        "SELECT DISTINCT {} FROM \"{base}\"{joins} WHERE ({}){}",
+        columns.join(", "),
+        compiled.sql,
+        visible(base)
+    );
+
+    // Rows are distinct over the selected fields, so they double as the sort key
+    let paginated = options.limit.is_some() || options.offset > 0;
+    let field_refs: Vec<&str> = fields.iter().map(String::as_str).collect();
+    let order_sql = match options.order_by.as_deref() {
+        Some(order_by) => format!(
+            " ORDER BY {}",
+            models::parse_order_by(order_by, &field_refs, &field_refs)?
+        ),
+        None if paginated => format!(
+            " ORDER BY {}",
+            models::parse_order_by("", &[], &field_refs)?
+        ),
+        None => String::new(),
+    };
+    let page_sql = if paginated {
+        " LIMIT @limit OFFSET @offset"
+    } else {
+        ""
+    };
+
+    let mut args = compiled.args.clone();
+    let count_args = compiled.args;
+    let mut params = compiled.params.clone();
+    let count_params = compiled.params;
+    if paginated {
+        args.insert("limit".into(), json!({"type": "integer"}));
+        args.insert("offset".into(), json!({"type": "integer"}));
+        params.insert("limit".into(), json!(options.limit.map_or(-1, i64::from)));
+        params.insert("offset".into(), json!(options.offset));
+    }
+
+    let query_json = json!({
+        "search": {
+            "query": format!("{select_sql}{order_sql}{page_sql}"),
+            "returns": fields,
+            "args": args
+        },
+        "count": {
+            "query": format!("SELECT COUNT(*) as total FROM ({select_sql})"),
+            "returns": ["total"],
+            "args": count_args
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "search", &json!(params))
+        .map_err(AppError::from)?;
+    let count = jankensqlhub::query_run_sqlite(conn, &queries, "count", &json!(count_params))
+        .map_err(AppError::from)?;
+    let total = count.data[0]["total"].as_u64().unwrap_or(0);
+
+    Ok(search_response(result.data, total, options))
+}
+
+// ---------------------------------------------------------------------------
+// find <query> [--tables] [--limit]
+// ---------------------------------------------------------------------------
+
+/// Ranked full-text search over artist, song and show names via `name_fts`.
+///
+/// Each entity appears once, with the field that matched best. `score` is
+/// the negated FTS5 bm25 rank, so higher is better.
+pub fn cmd_find(
+    conn: &mut Connection,
+    query: &str,
+    tables_str: Option<&str>,
+    limit: u32,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    let match_expr = name_index::match_expression(query)?;
+    let tables = match tables_str {
+        Some(t) => models::parse_fields(t),
+        None => models::FIND_TABLES.iter().map(|t| t.to_string()).collect(),
+    };
+    if tables.is_empty() {
+        return Err(AppError::InvalidParameter("tables cannot be empty".into()));
+    }
+    for table in &tables {
+        models::validate_table(table, models::FIND_TABLES)?;
+    }
+    if limit == 0 {
+        return Err(AppError::InvalidParameter(
+            "limit must be at least 1".into(),
+        ));
+    }
+
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " WHERE COALESCE(a.status, s.status, sh.status, 0) = 0"
+    };
+    let query_json = json!({
+        "find": {
+            "query": format!("SELECT h.type as type, h.id as id, \
+                      COALESCE(a.name, s.name, sh.name) as name, \
+                      h.matched_field as matched_field, h.matched_text as matched_text, \
+                      MIN(h.rank) as rank \
+                      FROM (SELECT r.entity as type, r.entity_id as id, r.field as matched_field, \
+                            name_fts.text as matched_text, name_fts.rank as rank \
+                            FROM name_fts JOIN name_fts_row r ON r.id = name_fts.rowid \
+                            WHERE name_fts MATCH @match AND r.entity IN :[tables]) h \
+                      LEFT JOIN artist a ON h.type = 'artist' AND a.id = h.id \
+                      LEFT JOIN song s ON h.type = 'song' AND s.id = h.id \
+                      LEFT JOIN show sh ON h.type = 'show' AND sh.id = h.id\
+                      {status_sql} \
+                      GROUP BY h.type, h.id \
+                      ORDER BY rank, name \
+                      LIMIT @limit"),
+            "returns": ["type", "id", "name", "matched_field", "matched_text", "rank"],
+            "args": {
+                "match": {},
+                "tables": {"itemtype": "string"},
+                "limit": {"type": "integer"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let params = json!({
+        "match": match_expr,
+        "tables": tables,
+        "limit": limit
+    });
+    let result =
+        jankensqlhub::query_run_sqlite(conn, &queries, "find", &params).map_err(AppError::from)?;
+
+    let results: Vec<Value> = result
+        .data
+        .into_iter()
+        .map(|mut row| {
+            let rank = row["rank"].as_f64().unwrap_or(0.0);
+            let obj = row.as_object_mut().unwrap();
+            obj.remove("rank");
+            obj.insert("score".into(), json!((-rank * 1000.0).round() / 1000.0));
+            row
+        })
+        .collect();
+
+    Ok(json!({
+        "query": query,
+        "count": results.len(),
+        "results": results
+    }))
 }
 
 // ---------------------------------------------------------------------------
@@ -255,89 +531,396 @@ pub fn cmd_search(
 pub fn cmd_duplicates(conn: &mut Connection, table: &str) -> Result<Value, AppError> {
     models::validate_table(table, models::DUPLICATES_TABLES)?;
 
-    let table_enum = table_config::build_table_enum(models::DUPLICATES_TABLES);
-
-    // Two query variants: artist/song include a song_count subquery; show uses 0
-    let (query_name, query_sql) = match table {
-        "artist" | "song" => (
-            "duplicates_with_song_count",
-            "SELECT a.id, a.name, \
+    // One query per table so each returns context that makes sense for it:
+    // artists count their songs, shows their linked songs and plays, and songs
+    // their plays and learning records. Songs are grouped by (name, artist),
+    // since different artists legitimately share titles. Artists and shows
+    // are also grouped through their aliases: each name or alias is a
+    // (group_key, id) pair, and a record that only joins a group through an
+    // alias reports that alias.
+    let (query_sql, returns): (&str, &[&str]) = match table {
+        "artist" => (
+            "WITH names AS ( \
+               SELECT id, LOWER(name) as k, '' as alias FROM artist WHERE status = 0 \
+               UNION ALL \
+               SELECT al.artist_id, LOWER(al.name), al.name FROM artist_alias al \
+               JOIN artist a ON a.id = al.artist_id WHERE a.status = 0 \
+             ) \
+             SELECT n.k as group_key, a.id, a.name, MIN(n.alias) as alias, \
              (SELECT COUNT(*) FROM song s WHERE s.artist_id = a.id) as song_count \
-             FROM #[table] a \
-             WHERE LOWER(a.name) IN ( \
-               SELECT LOWER(name) FROM #[table] \
-               WHERE status = 0 \
-               GROUP BY LOWER(name) HAVING COUNT(*) > 1 \
-             ) AND a.status = 0 \
-             ORDER BY LOWER(a.name), a.name",
+             FROM names n JOIN artist a ON a.id = n.id \
+             WHERE n.k IN (SELECT k FROM names GROUP BY k HAVING COUNT(DISTINCT id) > 1) \
+             GROUP BY n.k, a.id \
+             ORDER BY n.k, a.name",
+            &["group_key", "id", "name", "alias", "song_count"],
         ),
         "show" => (
-            "duplicates_no_song_count",
-            "SELECT a.id, a.name, \
-             0 as song_count \
-             FROM #[table] a \
-             WHERE LOWER(a.name) IN ( \
-               SELECT LOWER(name) FROM #[table] \
+            "WITH names AS ( \
+               SELECT id, LOWER(name) as k, '' as alias FROM show WHERE status = 0 \
+               UNION ALL \
+               SELECT al.show_id, LOWER(al.name), al.name FROM show_alias al \
+               JOIN show sh ON sh.id = al.show_id WHERE sh.status = 0 \
+             ) \
+             SELECT n.k as group_key, sh.id, sh.name, MIN(n.alias) as alias, \
+             COALESCE(sh.vintage, '') as vintage, \
+             (SELECT COUNT(*) FROM rel_show_song rs WHERE rs.show_id = sh.id) as song_count, \
+             (SELECT COUNT(*) FROM play_history ph \
+              WHERE ph.show_id = sh.id AND ph.status = 0) as play_count \
+             FROM names n JOIN show sh ON sh.id = n.id \
+             WHERE n.k IN (SELECT k FROM names GROUP BY k HAVING COUNT(DISTINCT id) > 1) \
+             GROUP BY n.k, sh.id \
+             ORDER BY n.k, sh.name",
+            &[
+                "group_key",
+                "id",
+                "name",
+                "alias",
+                "vintage",
+                "song_count",
+                "play_count",
+            ],
+        ),
+        "song" => (
+            "SELECT s.id, s.name, s.artist_id, COALESCE(a.name, '') as artist_name, \
+             (SELECT COUNT(*) FROM play_history ph \
+              WHERE ph.song_id = s.id AND ph.status = 0) as play_count, \
+             (SELECT COUNT(*) FROM learning l WHERE l.song_id = s.id) as learning_count \
+             FROM song s \
+             LEFT JOIN artist a ON a.id = s.artist_id \
+             WHERE (LOWER(s.name), s.artist_id) IN ( \
+               SELECT LOWER(name), artist_id FROM song \
                WHERE status = 0 \
-               GROUP BY LOWER(name) HAVING COUNT(*) > 1 \
-             ) AND a.status = 0 \
-             ORDER BY LOWER(a.name), a.name",
+               GROUP BY LOWER(name), artist_id HAVING COUNT(*) > 1 \
+             ) AND s.status = 0 \
+             ORDER BY LOWER(s.name), s.artist_id, s.name",
+            &[
+                "id",
+                "name",
+                "artist_id",
+                "artist_name",
+                "play_count",
+                "learning_count",
+            ],
         ),
         _ => unreachable!(),
     };
 
     let query_json = json!({
-        query_name: {
+        "duplicates": {
             "query": query_sql,
-            "returns": ["id", "name", "song_count"],
-            "args": {
-                "table": {"enum": table_enum}
-            }
+            "returns": returns
         }
     });
 
     let queries = QueryDefinitions::from_json(query_json)
         .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
 
-    let params = json!({ "table": table });
-
-    let result = jankensqlhub::query_run_sqlite(conn, &queries, query_name, &params)
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "duplicates", &json!({}))
         .map_err(AppError::from)?;
 
-    // Group rows by lowercase name (rows are already ordered by LOWER(name))
+    // Group consecutive rows by lowercase name (plus artist for songs);
+    // rows are already ordered by the grouping key.
     let mut groups: Vec<Value> = Vec::new();
-    let mut current_group_name: Option<String> = None;
+    let mut current_key: Option<(String, String)> = None;
+    let mut current_group = Map::new();
     let mut current_records: Vec<Value> = Vec::new();
 
     for row in &result.data {
-        let name = row["name"].as_str().unwrap_or("");
-        let lower = name.to_lowercase();
-        if current_group_name.as_ref() != Some(&lower) {
+        let lower = match row.get("group_key").and_then(Value::as_str) {
+            Some(key) => key.to_string(),
+            None => row["name"].as_str().unwrap_or("").to_lowercase(),
+        };
+        let artist_id = row["artist_id"].as_str().unwrap_or("").to_string();
+        let key = (lower, artist_id);
+        if current_key.as_ref() != Some(&key) {
             if !current_records.is_empty() {
-                groups.push(json!({
-                    "name": current_group_name.as_deref().unwrap_or(""),
-                    "records": current_records
-                }));
+                current_group.insert("records".into(), json!(current_records));
+                groups.push(Value::Object(current_group));
                 current_records = Vec::new();
             }
-            current_group_name = Some(lower);
+            current_group = Map::new();
+            current_group.insert("name".into(), json!(key.0));
+            if table == "song" {
+                current_group.insert("artist_id".into(), row["artist_id"].clone());
+                current_group.insert("artist_name".into(), row["artist_name"].clone());
+            }
+            current_key = Some(key);
+        }
+        let mut record = row.clone();
+        if let Some(fields) = record.as_object_mut() {
+            fields.remove("group_key");
+            if fields.get("alias").and_then(Value::as_str) == Some("") {
+                fields.remove("alias");
+            }
         }
-        current_records.push(json!({
-            "id": row["id"],
-            "name": row["name"],
-            "song_count": row["song_count"]
-        }));
+        current_records.push(record);
     }
     if !current_records.is_empty() {
-        groups.push(json!({
-            "name": current_group_name.as_deref().unwrap_or(""),
-            "records": current_records
-        }));
+        current_group.insert("records".into(), json!(current_records));
+        groups.push(Value::Object(current_group));
     }
 
     Ok(json!({"duplicates": groups}))
 }
 
+// ---------------------------------------------------------------------------
+// duplicates <table> --fuzzy
+// ---------------------------------------------------------------------------
+
+pub fn cmd_duplicates_fuzzy(
+    conn: &mut Connection,
+    table: &str,
+    max_distance: u32,
+    min_score: f64,
+) -> Result<Value, AppError> {
+    models::validate_table(table, models::DUPLICATES_TABLES)?;
+    if !(0.0..=1.0).contains(&min_score) {
+        return Err(AppError::InvalidParameter(format!(
+            "min_score must be between 0 and 1, got {min_score}"
+        )));
+    }
+
+    let query_json = json!({
+        "artist": {
+            "query": "SELECT id, name FROM artist WHERE status = 0",
+            "returns": ["id", "name"]
+        },
+        "show": {
+            "query": "SELECT id, name, COALESCE(name_romaji, '') as name_romaji, \
+                      COALESCE(vintage, '') as vintage \
+                      FROM show WHERE status = 0",
+            "returns": ["id", "name", "name_romaji", "vintage"]
+        },
+        "song": {
+            "query": "SELECT s.id, s.name, s.artist_id, COALESCE(a.name, '') as artist_name \
+                      FROM song s LEFT JOIN artist a ON a.id = s.artist_id \
+                      WHERE s.status = 0",
+            "returns": ["id", "name", "artist_id", "artist_name"]
+        },
+        "artist_aliases": {
+            "query": "SELECT artist_id as owner_id, name FROM artist_alias ORDER BY name",
+            "returns": ["owner_id", "name"]
+        },
+        "show_aliases": {
+            "query": "SELECT show_id as owner_id, name FROM show_alias ORDER BY name",
+            "returns": ["owner_id", "name"]
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, table, &json!({}))
+        .map_err(AppError::from)?;
+
+    // Aliases are compared like another name field
+    let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+    if table != "song" {
+        let rows =
+            jankensqlhub::query_run_sqlite(conn, &queries, &format!("{table}_aliases"), &json!({}))
+                .map_err(AppError::from)?;
+        for row in rows.data {
+            if let (Some(owner), Some(name)) = (row["owner_id"].as_str(), row["name"].as_str()) {
+                aliases
+                    .entry(owner.to_string())
+                    .or_default()
+                    .push(name.to_string());
+            }
+        }
+    }
+    let aliases_of = |row: &Value| -> &[String] {
+        row["id"]
+            .as_str()
+            .and_then(|id| aliases.get(id))
+            .map_or(&[], Vec::as_slice)
+    };
+
+    // Comparable names per record: (field, comparison key)
+    let name_fields: &[&str] = if table == "show" {
+        &["name", "name_romaji"]
+    } else {
+        &["name"]
+    };
+    let keyed: Vec<Vec<(&str, String)>> = result
+        .data
+        .iter()
+        .map(|row| {
+            name_fields
+                .iter()
+                .map(|f| (*f, fuzzy::comparison_key(row[*f].as_str().unwrap_or(""))))
+                .chain(
+                    aliases_of(row)
+                        .iter()
+                        .map(|alias| ("alias", fuzzy::comparison_key(alias))),
+                )
+                .filter(|(_, key)| !key.is_empty())
+                .collect()
+        })
+        .collect();
+
+    // Blocking: only records sharing a token or a 3-char key prefix are compared,
+    // which keeps this far below a full pairwise scan on large tables.
+    let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, row) in result.data.iter().enumerate() {
+        let mut block_keys: BTreeSet<String> = BTreeSet::new();
+        let names = name_fields
+            .iter()
+            .map(|f| row[*f].as_str().unwrap_or(""))
+            .chain(aliases_of(row).iter().map(String::as_str));
+        for name in names {
+            for tok in normalize::fold_tokens(name) {
+                if tok.chars().count() >= 2 {
+                    block_keys.insert(format!("t:{tok}"));
+                }
+            }
+        }
+        for (_, key) in &keyed[idx] {
+            block_keys.insert(format!("p:{}", key.chars().take(3).collect::<String>()));
+        }
+        for bk in block_keys {
+            blocks.entry(bk).or_default().push(idx);
+        }
+    }
+    let mut candidates: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for members in blocks.values() {
+        for (pos, a) in members.iter().enumerate() {
+            for b in &members[pos + 1..] {
+                candidates.insert((*a, *b));
+            }
+        }
+    }
+
+    let mut pairs: Vec<(f64, Value)> = Vec::new();
+    for (a, b) in candidates {
+        let (row_a, row_b) = (&result.data[a], &result.data[b]);
+        // Different artists legitimately share song titles
+        if table == "song" && row_a["artist_id"] != row_b["artist_id"] {
+            continue;
+        }
+        // Same-named shows from different seasons are different shows
+        if table == "show" {
+            let vintage_a = fuzzy::comparison_key(row_a["vintage"].as_str().unwrap_or(""));
+            let vintage_b = fuzzy::comparison_key(row_b["vintage"].as_str().unwrap_or(""));
+            if !vintage_a.is_empty() && !vintage_b.is_empty() && vintage_a != vintage_b {
+                continue;
+            }
+        }
+
+        let mut best: Option<(usize, f64, String)> = None;
+        for (field_a, key_a) in &keyed[a] {
+            for (field_b, key_b) in &keyed[b] {
+                let distance = fuzzy::edit_distance(key_a, key_b);
+                let score = fuzzy::similarity(key_a, key_b, distance);
+                if best.as_ref().is_none_or(|(_, s, _)| score > *s) {
+                    let matched_on = if field_a == field_b {
+                        field_a.to_string()
+                    } else {
+                        format!("{field_a}/{field_b}")
+                    };
+                    best = Some((distance, score, matched_on));
+                }
+            }
+        }
+
+        if let Some((distance, score, matched_on)) = best
+            && distance <= max_distance as usize
+            && score >= min_score
+        {
+            pairs.push((
+                score,
+                json!({
+                    "score": score,
+                    "distance": distance,
+                    "matched_on": matched_on,
+                    "records": [row_a, row_b]
+                }),
+            ));
+        }
+    }
+
+    pairs.sort_by(|(sa, pa), (sb, pb)| {
+        sb.total_cmp(sa).then_with(|| {
+            let name_a = pa["records"][0]["name"].as_str().unwrap_or("");
+            let name_b = pb["records"][0]["name"].as_str().unwrap_or("");
+            name_a.cmp(name_b)
+        })
+    });
+    let pairs: Vec<Value> = pairs.into_iter().map(|(_, p)| p).collect();
+
+    Ok(json!({"count": pairs.len(), "pairs": pairs}))
+}
+
+// ---------------------------------------------------------------------------
+// fsck
+// ---------------------------------------------------------------------------
+
+/// Scan every reference column (see `TableConfig::references`) for values
+/// that point at a nonexistent record. Soft-deleted rows are included.
+pub fn cmd_fsck(conn: &mut Connection) -> Result<Value, AppError> {
+    let tx = conn.transaction()?;
+    let orphans = scan_orphans(&tx)?;
+    tx.commit()?;
+
+    Ok(json!({
+        "ok": orphans.is_empty(),
+        "orphan_count": orphans.len(),
+        "orphans": orphans
+    }))
+}
+
+/// List orphaned references as `{table, id, field, references, missing_id}`.
+/// `rel_show_song` rows are identified as `show_id:song_id`.
+pub(crate) fn scan_orphans(tx: &Transaction) -> Result<Vec<Value>, AppError> {
+    let references = table_config::all_references();
+
+    // Table and column names come from the static table config, so they are
+    // safe to embed directly.
+    let mut query_map = Map::new();
+    for (table, col, target) in &references {
+        let id_expr = if *table == "rel_show_song" {
+            "t.show_id || ':' || t.song_id"
+        } else {
+            "t.id"
+        };
+        query_map.insert(
+            format!("{table}.{col}"),
+            json!({
+                "query": format!(
+                    "SELECT {id_expr} as id, t.\"{col}\" as missing_id FROM \"{table}\" t \
+                     WHERE t.\"{col}\" IS NOT NULL \
+                     AND NOT EXISTS (SELECT 1 FROM \"{target}\" r WHERE r.id = t.\"{col}\") \
+                     ORDER BY 1"
+                ),
+                "returns": ["id", "missing_id"]
+            }),
+        );
+    }
+
+    let queries = QueryDefinitions::from_json(Value::Object(query_map))
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let mut orphans: Vec<Value> = Vec::new();
+    for (table, col, target) in &references {
+        let result = jankensqlhub::query_run_sqlite_with_transaction(
+            tx,
+            &queries,
+            &format!("{table}.{col}"),
+            &json!({}),
+        )
+        .map_err(AppError::from)?;
+        for row in result.data {
+            orphans.push(json!({
+                "table": table,
+                "id": row["id"],
+                "field": col,
+                "references": target,
+                "missing_id": row["missing_id"],
+            }));
+        }
+    }
+    Ok(orphans)
+}
+
 // ---------------------------------------------------------------------------
 // shows-by-artist-ids --artist-ids
 // ---------------------------------------------------------------------------
@@ -345,6 +928,7 @@ pub fn cmd_duplicates(conn: &mut Connection, table: &str) -> Result<Value, AppEr
 pub fn cmd_shows_by_artist_ids(
     conn: &mut Connection,
     artist_ids_str: &str,
+    include_deleted: bool,
 ) -> Result<Value, AppError> {
     let artist_ids: Vec<&str> = artist_ids_str
         .split(',')
@@ -358,17 +942,23 @@ pub fn cmd_shows_by_artist_ids(
         ));
     }
 
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND sh.status = 0 AND s.status = 0 AND a.status = 0"
+    };
+
     let query_json = json!({
         "shows_by_artists": {
-            "query": "SELECT DISTINCT sh.id as show_id, sh.name as show_name, sh.vintage, \
+            "query": format!("SELECT DISTINCT sh.id as show_id, sh.name as show_name, sh.vintage, \
                       s.id as song_id, s.name as song_name, \
                       a.id as artist_id, a.name as artist_name \
                       FROM show sh \
                       JOIN rel_show_song rs ON rs.show_id = sh.id \
                       JOIN song s ON rs.song_id = s.id \
                       JOIN artist a ON s.artist_id = a.id \
-                      WHERE a.id IN :[artist_ids] \
-                      ORDER BY a.name, sh.name, s.name",
+                      WHERE a.id IN :[artist_ids]{status_sql} \
+                      ORDER BY a.name, sh.name, s.name"),
             "returns": ["show_id", "show_name", "vintage", "song_id", "song_name", "artist_id", "artist_name"],
             "args": {
                 "artist_ids": {"itemtype": "string"}
@@ -393,9 +983,14 @@ pub fn cmd_shows_by_artist_ids(
 // songs-by-artist-ids --artist-ids
 // ---------------------------------------------------------------------------
 
+/// With `include_groups`, songs performed by groups the artists belong to
+/// (`artist_member`, one level) are returned too, each with the requested
+/// artist it was found through as `via_artist_id`.
 pub fn cmd_songs_by_artist_ids(
     conn: &mut Connection,
     artist_ids_str: &str,
+    include_groups: bool,
+    include_deleted: bool,
 ) -> Result<Value, AppError> {
     let artist_ids: Vec<&str> = artist_ids_str
         .split(',')
@@ -409,17 +1004,190 @@ pub fn cmd_songs_by_artist_ids(
         ));
     }
 
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND s.status = 0 AND a.status = 0"
+    };
+
+    let query_json = if include_groups {
+        let member_status_sql = if include_deleted {
+            ""
+        } else {
+            " AND v.status = 0"
+        };
+        // A song found both directly and through a membership is reported
+        // once, as a direct match
+        json!({
+            "songs_by_artists": {
+                "query": format!("SELECT s.id as song_id, s.name as song_name, \
+                          a.id as artist_id, a.name as artist_name, q.via_artist_id \
+                          FROM song s \
+                          JOIN artist a ON s.artist_id = a.id \
+                          JOIN (SELECT performer_id, \
+                                  CASE WHEN MAX(direct) = 1 THEN performer_id ELSE MIN(via) END as via_artist_id \
+                                FROM (SELECT id as performer_id, id as via, 1 as direct \
+                                      FROM artist WHERE id IN :[artist_ids] \
+                                      UNION ALL \
+                                      SELECT m.group_id, m.member_id, 0 \
+                                      FROM artist_member m JOIN artist v ON v.id = m.member_id \
+                                      WHERE m.member_id IN :[artist_ids]{member_status_sql}) \
+                                GROUP BY performer_id) q ON q.performer_id = a.id \
+                          WHERE 1 = 1{status_sql} \
+                          ORDER BY a.name, s.name"),
+                "returns": ["song_id", "song_name", "artist_id", "artist_name", "via_artist_id"],
+                "args": {
+                    "artist_ids": {"itemtype": "string"}
+                }
+            }
+        })
+    } else {
+        json!({
+            "songs_by_artists": {
+                "query": format!("SELECT s.id as song_id, s.name as song_name, \
+                          a.id as artist_id, a.name as artist_name \
+                          FROM song s \
+                          JOIN artist a ON s.artist_id = a.id \
+                          WHERE a.id IN :[artist_ids]{status_sql} \
+                          ORDER BY a.name, s.name"),
+                "returns": ["song_id", "song_name", "artist_id", "artist_name"],
+                "args": {
+                    "artist_ids": {"itemtype": "string"}
+                }
+            }
+        })
+    };
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let ids_json: Vec<Value> = artist_ids.iter().map(|s| json!(s)).collect();
+    let params = json!({ "artist_ids": ids_json });
+
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "songs_by_artists", &params)
+        .map_err(AppError::from)?;
+
+    let count = result.data.len();
+    Ok(json!({"count": count, "results": result.data}))
+}
+
+// ---------------------------------------------------------------------------
+// songs-by-show-ids --show-ids
+// ---------------------------------------------------------------------------
+
+pub fn cmd_songs_by_show_ids(
+    conn: &mut Connection,
+    show_ids_str: &str,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    let show_ids: Vec<&str> = show_ids_str
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if show_ids.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "show_ids cannot be empty".into(),
+        ));
+    }
+
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND sh.status = 0 AND s.status = 0 AND a.status = 0"
+    };
+
+    // Learning status: an active record wins over graduated ones
+    let query_json = json!({
+        "songs_by_shows": {
+            "query": format!("SELECT sh.id as show_id, sh.name as show_name, \
+                      s.id as song_id, s.name as song_name, \
+                      a.id as artist_id, a.name as artist_name, \
+                      COALESCE(rs.media_url, '') as media_url, \
+                      COALESCE(rs.song_type, '') as song_type, rs.type_number, \
+                      CASE \
+                        WHEN EXISTS (SELECT 1 FROM learning l WHERE l.song_id = s.id AND l.graduated = 0) THEN 'learning' \
+                        WHEN EXISTS (SELECT 1 FROM learning l WHERE l.song_id = s.id) THEN 'graduated' \
+                        ELSE 'none' \
+                      END as learning_status, \
+                      (SELECT MAX(l.level) FROM learning l WHERE l.song_id = s.id AND l.graduated = 0) as learning_level \
+                      FROM show sh \
+                      JOIN rel_show_song rs ON rs.show_id = sh.id \
+                      JOIN song s ON rs.song_id = s.id \
+                      JOIN artist a ON s.artist_id = a.id \
+                      WHERE sh.id IN :[show_ids]{status_sql} \
+                      ORDER BY sh.name, s.name, a.name"),
+            "returns": ["show_id", "show_name", "song_id", "song_name", "artist_id", "artist_name", "media_url", "song_type", "type_number", "learning_status", "learning_level"],
+            "args": {
+                "show_ids": {"itemtype": "string"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let ids_json: Vec<Value> = show_ids.iter().map(|s| json!(s)).collect();
+    let params = json!({ "show_ids": ids_json });
+
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "songs_by_shows", &params)
+        .map_err(AppError::from)?;
+
+    let count = result.data.len();
+    Ok(json!({"count": count, "results": result.data}))
+}
+
+// ---------------------------------------------------------------------------
+// songs-by-composer-ids --composer-ids [--include-arrangers]
+// ---------------------------------------------------------------------------
+
+/// Songs credited to the given artists as composer (and, with
+/// `include_arrangers`, as arranger) through `song_credit`, alongside the
+/// performing artist.
+pub fn cmd_songs_by_composer_ids(
+    conn: &mut Connection,
+    composer_ids_str: &str,
+    include_arrangers: bool,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    let composer_ids: Vec<&str> = composer_ids_str
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if composer_ids.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "composer_ids cannot be empty".into(),
+        ));
+    }
+
+    let role_sql = if include_arrangers {
+        ""
+    } else {
+        " AND sc.role = 'composer'"
+    };
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND s.status = 0 AND a.status = 0 AND c.status = 0"
+    };
+
     let query_json = json!({
-        "songs_by_artists": {
-            "query": "SELECT s.id as song_id, s.name as song_name, \
+        "songs_by_composers": {
+            "query": format!("SELECT c.id as credit_artist_id, c.name as credit_artist_name, sc.role, \
+                      s.id as song_id, s.name as song_name, \
                       a.id as artist_id, a.name as artist_name \
-                      FROM song s \
+                      FROM song_credit sc \
+                      JOIN artist c ON sc.artist_id = c.id \
+                      JOIN song s ON sc.song_id = s.id \
                       JOIN artist a ON s.artist_id = a.id \
-                      WHERE a.id IN :[artist_ids] \
-                      ORDER BY a.name, s.name",
-            "returns": ["song_id", "song_name", "artist_id", "artist_name"],
+                      WHERE c.id IN :[composer_ids]{role_sql}{status_sql} \
+                      ORDER BY c.name, sc.role DESC, s.name, a.name"),
+            "returns": ["credit_artist_id", "credit_artist_name", "role", "song_id", "song_name", "artist_id", "artist_name"],
             "args": {
-                "artist_ids": {"itemtype": "string"}
+                "composer_ids": {"itemtype": "string"}
             }
         }
     });
@@ -427,10 +1195,329 @@ pub fn cmd_songs_by_artist_ids(
     let queries = QueryDefinitions::from_json(query_json)
         .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
 
-    let ids_json: Vec<Value> = artist_ids.iter().map(|s| json!(s)).collect();
-    let params = json!({ "artist_ids": ids_json });
+    let ids_json: Vec<Value> = composer_ids.iter().map(|s| json!(s)).collect();
+    let params = json!({ "composer_ids": ids_json });
 
-    let result = jankensqlhub::query_run_sqlite(conn, &queries, "songs_by_artists", &params)
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "songs_by_composers", &params)
+        .map_err(AppError::from)?;
+
+    let count = result.data.len();
+    Ok(json!({"count": count, "results": result.data}))
+}
+
+// ---------------------------------------------------------------------------
+// shows-by-vintage --season | --year-range
+// ---------------------------------------------------------------------------
+
+/// Shows of one season (`--season "Winter 2024"`) or a range of years
+/// (`--year-range 2020-2024`), ordered chronologically by the stored
+/// `vintage_key`. Shows without parsed vintage columns (unparseable vintage,
+/// or rows not yet backfilled by `doctor --fix`) never match.
+pub fn cmd_shows_by_vintage(
+    conn: &mut Connection,
+    season: Option<&str>,
+    year_range: Option<&str>,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    let season = season
+        .map(|s| vintage::parse_required(s, "season"))
+        .transpose()?;
+    let years = year_range.map(vintage::parse_year_range).transpose()?;
+    if season.is_none() && years.is_none() {
+        return Err(AppError::InvalidParameter(
+            "shows-by-vintage requires --season or --year-range".into(),
+        ));
+    }
+    // Both filters reduce to a vintage_key range
+    let (mut from_key, mut to_key) = (i64::MIN, i64::MAX);
+    if let Some(season) = season {
+        (from_key, to_key) = (season.key(), season.key());
+    }
+    if let Some((from, to)) = years {
+        from_key = from_key.max(from * 10 + 1);
+        to_key = to_key.min(to * 10 + 4);
+    }
+
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND sh.status = 0"
+    };
+    let song_status_sql = if include_deleted {
+        ""
+    } else {
+        " AND s.status = 0"
+    };
+
+    let query_json = json!({
+        "shows_by_vintage": {
+            "query": format!("SELECT sh.id as show_id, sh.name as show_name, \
+                      COALESCE(sh.name_romaji, '') as name_romaji, sh.vintage, \
+                      COALESCE(sh.s_type, '') as s_type, sh.season, sh.year, \
+                      (SELECT COUNT(*) FROM rel_show_song rs JOIN song s ON s.id = rs.song_id \
+                       WHERE rs.show_id = sh.id{song_status_sql}) as song_count \
+                      FROM show sh \
+                      WHERE sh.vintage_key BETWEEN @from_key AND @to_key{status_sql} \
+                      ORDER BY sh.vintage_key, sh.name, sh.id"),
+            "returns": ["show_id", "show_name", "name_romaji", "vintage", "s_type", "season", "year", "song_count"],
+            "args": {
+                "from_key": {"type": "integer"},
+                "to_key": {"type": "integer"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let params = json!({"from_key": from_key, "to_key": to_key});
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "shows_by_vintage", &params)
+        .map_err(AppError::from)?;
+
+    let count = result.data.len();
+    Ok(json!({"count": count, "results": result.data}))
+}
+
+// ---------------------------------------------------------------------------
+// play-stats [--by song|show|day|week|month]
+// ---------------------------------------------------------------------------
+
+/// Valid `--by` groupings for `play-stats`.
+const PLAY_STATS_GROUPS: &[&str] = &["song", "show", "day", "week", "month"];
+
+/// Guess accuracy from play_history outcomes, grouped per song, per show, or
+/// per UTC day/week/month. Only graded plays (`correct_guess` set) count;
+/// plays imported before outcomes were stored are reported as
+/// `ungraded_plays`. Songs and shows come most-missed first; periods come in
+/// chronological order, `limit` keeping the most recent ones.
+pub fn cmd_play_stats(
+    conn: &mut Connection,
+    by: &str,
+    min_plays: u32,
+    limit: Option<u32>,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    if !PLAY_STATS_GROUPS.contains(&by) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid grouping: {by}. Allowed: {}",
+            PLAY_STATS_GROUPS.join(", ")
+        )));
+    }
+
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND ph.status = 0 AND s.status = 0 AND sh.status = 0"
+    };
+    let graded_cte = format!(
+        "WITH graded AS (SELECT ph.* FROM play_history ph \
+         JOIN song s ON s.id = ph.song_id JOIN show sh ON sh.id = ph.show_id \
+         WHERE ph.correct_guess IS NOT NULL{status_sql})"
+    );
+    let aggregates = "COUNT(*) as plays, COALESCE(SUM(g.correct_guess), 0) as correct, \
+                      COALESCE(SUM(g.wrong_guess), 0) as wrong, \
+                      COUNT(*) - COALESCE(SUM(g.correct_guess), 0) as missed, \
+                      ROUND(SUM(g.correct_guess) * 1.0 / COUNT(*), 4) as hit_rate, \
+                      MAX(g.created_at) as last_played_at";
+    let totals = [
+        "plays",
+        "correct",
+        "wrong",
+        "missed",
+        "hit_rate",
+        "last_played_at",
+    ];
+
+    let (group_sql, mut returns): (String, Vec<&str>) = match by {
+        "song" => (
+            format!(
+                "{graded_cte} SELECT g.song_id, s.name as song_name, \
+                 a.id as artist_id, a.name as artist_name, {aggregates} \
+                 FROM graded g JOIN song s ON s.id = g.song_id \
+                 JOIN artist a ON a.id = s.artist_id \
+                 GROUP BY g.song_id HAVING COUNT(*) >= @min_plays \
+                 ORDER BY missed DESC, hit_rate, song_name, g.song_id LIMIT @limit"
+            ),
+            vec!["song_id", "song_name", "artist_id", "artist_name"],
+        ),
+        "show" => (
+            format!(
+                "{graded_cte} SELECT g.show_id, sh.name as show_name, sh.vintage, {aggregates} \
+                 FROM graded g JOIN show sh ON sh.id = g.show_id \
+                 GROUP BY g.show_id HAVING COUNT(*) >= @min_plays \
+                 ORDER BY missed DESC, hit_rate, show_name, g.show_id LIMIT @limit"
+            ),
+            vec!["show_id", "show_name", "vintage"],
+        ),
+        period => {
+            let format = match period {
+                "day" => "%Y-%m-%d",
+                "week" => "%Y-W%W",
+                _ => "%Y-%m",
+            };
+            (
+                format!(
+                    "{graded_cte} SELECT * FROM (SELECT \
+                     strftime('{format}', g.created_at, 'unixepoch') as period, {aggregates} \
+                     FROM graded g GROUP BY period HAVING COUNT(*) >= @min_plays \
+                     ORDER BY period DESC LIMIT @limit) ORDER BY period"
+                ),
+                vec!["period"],
+            )
+        }
+    };
+    returns.extend(totals);
+
+    let query_json = json!({
+        "play_stats": {
+            "query": group_sql,
+            "returns": returns,
+            "args": {
+                "min_plays": {"type": "integer"},
+                "limit": {"type": "integer"}
+            }
+        },
+        "play_summary": {
+            "query": format!("{graded_cte} SELECT {aggregates}, \
+                      (SELECT COUNT(*) FROM play_history ph \
+                       JOIN song s ON s.id = ph.song_id JOIN show sh ON sh.id = ph.show_id \
+                       WHERE ph.correct_guess IS NULL{status_sql}) as ungraded_plays \
+                      FROM graded g"),
+            "returns": ["plays", "correct", "wrong", "missed", "hit_rate", "ungraded_plays"],
+            "args": {}
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    // SQLite treats a negative LIMIT as no limit
+    let params = json!({
+        "min_plays": min_plays,
+        "limit": limit.map_or(-1, i64::from)
+    });
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "play_stats", &params)
+        .map_err(AppError::from)?;
+    let summary = jankensqlhub::query_run_sqlite(conn, &queries, "play_summary", &json!({}))
+        .map_err(AppError::from)?;
+
+    let count = result.data.len();
+    Ok(json!({
+        "by": by,
+        "summary": summary.data[0],
+        "count": count,
+        "results": result.data
+    }))
+}
+
+// ---------------------------------------------------------------------------
+// game-sessions [--limit]
+// ---------------------------------------------------------------------------
+
+/// Game sessions, most recent first, with the results of their plays. Hit
+/// rates follow `play-stats`: only graded plays (`correct_guess` set) count.
+pub fn cmd_game_sessions(
+    conn: &mut Connection,
+    limit: u32,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND ph.status = 0"
+    };
+
+    let query_json = json!({
+        "game_sessions": {
+            "query": format!("SELECT gs.id, gs.room_name, gs.started_at, \
+                      COUNT(ph.id) as play_count, \
+                      COUNT(ph.correct_guess) as graded_count, \
+                      COALESCE(SUM(ph.correct_guess), 0) as correct, \
+                      COALESCE(SUM(ph.wrong_guess), 0) as wrong, \
+                      ROUND(SUM(ph.correct_guess) * 1.0 / COUNT(ph.correct_guess), 4) as hit_rate \
+                      FROM game_session gs \
+                      LEFT JOIN play_history ph ON ph.session_id = gs.id{status_sql} \
+                      GROUP BY gs.id \
+                      ORDER BY gs.started_at DESC, gs.id \
+                      LIMIT @limit"),
+            "returns": ["id", "room_name", "started_at", "play_count", "graded_count", "correct", "wrong", "hit_rate"],
+            "args": {
+                "limit": {"type": "integer"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let result =
+        jankensqlhub::query_run_sqlite(conn, &queries, "game_sessions", &json!({"limit": limit}))
+            .map_err(AppError::from)?;
+
+    let count = result.data.len();
+    Ok(json!({"count": count, "results": result.data}))
+}
+
+// ---------------------------------------------------------------------------
+// songs-by-session-ids --session-ids
+// ---------------------------------------------------------------------------
+
+/// The plays of the given game sessions in play order, with their show, song
+/// and outcome.
+pub fn cmd_songs_by_session_ids(
+    conn: &mut Connection,
+    session_ids_str: &str,
+    include_deleted: bool,
+) -> Result<Value, AppError> {
+    let session_ids: Vec<&str> = session_ids_str
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if session_ids.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "session_ids cannot be empty".into(),
+        ));
+    }
+
+    let status_sql = if include_deleted {
+        ""
+    } else {
+        " AND ph.status = 0 AND s.status = 0 AND sh.status = 0"
+    };
+
+    let query_json = json!({
+        "songs_by_sessions": {
+            "query": format!("SELECT gs.id as session_id, gs.room_name, ph.id as play_id, ph.created_at, \
+                      sh.id as show_id, sh.name as show_name, \
+                      s.id as song_id, s.name as song_name, \
+                      a.id as artist_id, a.name as artist_name, \
+                      COALESCE(ph.media_url, '') as media_url, \
+                      ph.correct_guess, ph.wrong_guess, COALESCE(ph.answer, '') as answer \
+                      FROM game_session gs \
+                      JOIN play_history ph ON ph.session_id = gs.id \
+                      JOIN song s ON s.id = ph.song_id \
+                      JOIN artist a ON a.id = s.artist_id \
+                      JOIN show sh ON sh.id = ph.show_id \
+                      WHERE gs.id IN :[session_ids]{status_sql} \
+                      ORDER BY gs.started_at, gs.id, ph.created_at, ph.id"),
+            "returns": ["session_id", "room_name", "play_id", "created_at", "show_id", "show_name",
+                        "song_id", "song_name", "artist_id", "artist_name", "media_url",
+                        "correct_guess", "wrong_guess", "answer"],
+            "args": {
+                "session_ids": {"itemtype": "string"}
+            }
+        }
+    });
+
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+
+    let ids_json: Vec<Value> = session_ids.iter().map(|s| json!(s)).collect();
+    let params = json!({ "session_ids": ids_json });
+
+    let result = jankensqlhub::query_run_sqlite(conn, &queries, "songs_by_sessions", &params)
         .map_err(AppError::from)?;
 
     let count = result.data.len();
@@ -0,0 +1,959 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use jankensqlhub::QueryDefinitions;
+use rusqlite::{Connection, Transaction};
+use serde_json::{Map, Value, json};
+
+use crate::error::AppError;
+use crate::models;
+use crate::normalize;
+use crate::table_config;
+
+use super::querying::cmd_search;
+
+/// Row key of a table: the columns a dump is ordered by.
+fn row_key(table: &str) -> &'static [&'static str] {
+    if table == "rel_show_song" {
+        &["show_id", "song_id"]
+    } else {
+        &["id"]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// export [--tables] [--format] [--output] [--term]
+// ---------------------------------------------------------------------------
+
+pub fn cmd_export(
+    conn: &mut Connection,
+    tables_str: Option<&str>,
+    format: &str,
+    output_dir: &str,
+    term_json: Option<&str>,
+) -> Result<Value, AppError> {
+    if !models::EXPORT_FORMATS.contains(&format) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid format: {format}. Allowed: {}",
+            models::EXPORT_FORMATS.join(", ")
+        )));
+    }
+
+    let tables: Vec<&str> = match tables_str {
+        Some(s) => {
+            let requested: Vec<&str> = s
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect();
+            if requested.is_empty() {
+                return Err(AppError::InvalidParameter("tables cannot be empty".into()));
+            }
+            for t in &requested {
+                models::validate_table(t, models::EXPORT_TABLES)?;
+            }
+            // Keep dependency order regardless of how --tables was written
+            models::EXPORT_TABLES
+                .iter()
+                .copied()
+                .filter(|t| requested.contains(t))
+                .collect()
+        }
+        None => models::EXPORT_TABLES.to_vec(),
+    };
+    if term_json.is_some() && tables.len() != 1 {
+        return Err(AppError::InvalidParameter(
+            "term requires exactly one table in --tables".into(),
+        ));
+    }
+
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| AppError::Internal(format!("Failed to create directory: {e}")))?;
+
+    let mut summary = Map::new();
+    let mut total_rows = 0usize;
+    for table in tables {
+        let fields = models::get_fields(table)?;
+        let rows = match term_json {
+            Some(term) => {
+                // Filtered subsets reuse search so the term grammar matches
+                let options = models::SearchOptions {
+                    include_deleted: true,
+                    order_by: Some(String::new()),
+                    ..Default::default()
+                };
+                let result = cmd_search(conn, table, term, &fields.join(","), &options)?;
+                result["results"].as_array().cloned().unwrap_or_default()
+            }
+            None => export_rows(conn, table, fields)?,
+        };
+
+        let file = Path::new(output_dir).join(format!("{table}.{format}"));
+        let content = match format {
+            "csv" => to_csv(fields, &rows),
+            _ => to_jsonl(&rows)?,
+        };
+        std::fs::write(&file, content)
+            .map_err(|e| AppError::Internal(format!("Failed to write {table} export: {e}")))?;
+
+        total_rows += rows.len();
+        summary.insert(
+            table.to_string(),
+            json!({"file": file.to_string_lossy(), "rows": rows.len()}),
+        );
+    }
+
+    Ok(json!({
+        "format": format,
+        "output": output_dir,
+        "tables": summary,
+        "total_rows": total_rows
+    }))
+}
+
+/// Every row of a table, soft-deleted ones included, in row key order.
+fn export_rows(
+    conn: &mut Connection,
+    table: &str,
+    fields: &[&str],
+) -> Result<Vec<Value>, AppError> {
+    let order_sql = models::parse_order_by("", &[], row_key(table))?;
+    let query_json = json!({
+        "export": {
+            "query": format!("SELECT ~[fields] FROM #[table] ORDER BY {order_sql}"),
+            "returns": "~[fields]",
+            "args": {
+                "table": {"enum": table_config::build_table_enum(models::EXPORT_TABLES)},
+                "fields": {
+                    "enumif": table_config::build_selectable_enumif(models::EXPORT_TABLES)
+                }
+            }
+        }
+    });
+    let queries = QueryDefinitions::from_json(query_json)
+        .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+    let result = jankensqlhub::query_run_sqlite(
+        conn,
+        &queries,
+        "export",
+        &json!({"table": table, "fields": fields}),
+    )
+    .map_err(AppError::from)?;
+    Ok(result.data)
+}
+
+/// One JSON object per line.
+fn to_jsonl(rows: &[Value]) -> Result<String, AppError> {
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&serde_json::to_string(row).map_err(|e| AppError::Internal(e.to_string()))?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// RFC 4180 CSV with a header row; `null` becomes an empty field.
+fn to_csv(fields: &[&str], rows: &[Value]) -> String {
+    let mut out = String::new();
+    let header: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    out.push_str(&header.join(","));
+    out.push_str("\r\n");
+    for row in rows {
+        let cells: Vec<String> = fields
+            .iter()
+            .map(|f| match &row[*f] {
+                Value::Null => String::new(),
+                Value::String(s) => csv_field(s),
+                other => csv_field(&other.to_string()),
+            })
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// import-dump [--input] [--tables] [--on-conflict] [--dry-run]
+// ---------------------------------------------------------------------------
+
+pub fn cmd_import_dump(
+    conn: &mut Connection,
+    input_dir: &str,
+    tables_str: Option<&str>,
+    on_conflict: &str,
+    dry_run: bool,
+) -> Result<Value, AppError> {
+    if !models::CONFLICT_POLICIES.contains(&on_conflict) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid conflict policy: {on_conflict}. Allowed: {}",
+            models::CONFLICT_POLICIES.join(", ")
+        )));
+    }
+
+    // Explicit tables must have a file; otherwise import whatever is present
+    let files: Vec<(&str, std::path::PathBuf)> = match tables_str {
+        Some(s) => {
+            let requested: Vec<&str> = s
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect();
+            if requested.is_empty() {
+                return Err(AppError::InvalidParameter("tables cannot be empty".into()));
+            }
+            for t in &requested {
+                models::validate_table(t, models::EXPORT_TABLES)?;
+            }
+            let mut files = Vec::new();
+            for table in models::EXPORT_TABLES {
+                if !requested.contains(table) {
+                    continue;
+                }
+                let file = Path::new(input_dir).join(format!("{table}.jsonl"));
+                if !file.is_file() {
+                    return Err(AppError::NotFound(format!(
+                        "File not found: {}",
+                        file.to_string_lossy()
+                    )));
+                }
+                files.push((*table, file));
+            }
+            files
+        }
+        None => models::EXPORT_TABLES
+            .iter()
+            .map(|t| (*t, Path::new(input_dir).join(format!("{t}.jsonl"))))
+            .filter(|(_, file)| file.is_file())
+            .collect(),
+    };
+    if files.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "No .jsonl export files found in {input_dir}"
+        )));
+    }
+
+    let tx = conn.transaction()?;
+    let mut importer = DumpImporter {
+        tx: &tx,
+        on_conflict,
+        queries: HashMap::new(),
+        known_ids: HashMap::new(),
+    };
+    let mut summary = Map::new();
+    let (mut inserted, mut updated, mut skipped) = (0, 0, 0);
+    for (table, file) in &files {
+        let counts = importer.import_file(table, file)?;
+        inserted += counts.inserted;
+        updated += counts.updated;
+        skipped += counts.skipped;
+        summary.insert(
+            table.to_string(),
+            json!({
+                "file": file.to_string_lossy(),
+                "rows": counts.inserted + counts.updated + counts.skipped,
+                "inserted": counts.inserted,
+                "updated": counts.updated,
+                "skipped": counts.skipped
+            }),
+        );
+    }
+    drop(importer);
+    // Dropping the transaction without committing rolls everything back
+    if !dry_run {
+        tx.commit()?;
+    }
+
+    Ok(json!({
+        "on_conflict": on_conflict,
+        "dry_run": dry_run,
+        "tables": summary,
+        "inserted": inserted,
+        "updated": updated,
+        "skipped": skipped
+    }))
+}
+
+#[derive(Default)]
+struct ImportCounts {
+    inserted: usize,
+    updated: usize,
+    skipped: usize,
+}
+
+/// Writes dump rows inside one transaction, caching query definitions by SQL
+/// and the referenced IDs already confirmed to exist.
+struct DumpImporter<'a> {
+    tx: &'a Transaction<'a>,
+    on_conflict: &'a str,
+    queries: HashMap<String, QueryDefinitions>,
+    known_ids: HashMap<&'static str, HashSet<String>>,
+}
+
+impl DumpImporter<'_> {
+    fn import_file(&mut self, table: &'static str, file: &Path) -> Result<ImportCounts, AppError> {
+        let content = std::fs::read_to_string(file)
+            .map_err(|e| AppError::Internal(format!("Failed to read {table} dump: {e}")))?;
+        let config = table_config::get(table)
+            .ok_or_else(|| AppError::Internal(format!("No config for table: {table}")))?;
+        let name = file.to_string_lossy();
+
+        let mut counts = ImportCounts::default();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Every error names the file and line so the dump can be fixed
+            let at = |e: AppError| AppError::InvalidParameter(format!("{name}:{}: {e}", index + 1));
+            let row: Map<String, Value> = serde_json::from_str(line).map_err(|e| at(e.into()))?;
+            for key in row.keys() {
+                if !config.selectable.contains(&key.as_str()) {
+                    return Err(at(AppError::InvalidParameter(format!(
+                        "Invalid field for {table}: {key}. Allowed: {}",
+                        config.selectable.join(", ")
+                    ))));
+                }
+            }
+            for key in row_key(table) {
+                if !row.get(*key).is_some_and(Value::is_string) {
+                    return Err(at(AppError::InvalidParameter(format!("{key} is required"))));
+                }
+            }
+            self.check_references(config, &row).map_err(at)?;
+
+            match self.existing_updated_at(table, &row).map_err(at)? {
+                None => {
+                    self.insert(table, &row).map_err(at)?;
+                    counts.inserted += 1;
+                }
+                Some(existing) if self.should_overwrite(&row, existing) => {
+                    self.update(table, &row).map_err(at)?;
+                    counts.updated += 1;
+                }
+                Some(_) => counts.skipped += 1,
+            }
+            if let Some(id) = row.get("id").and_then(Value::as_str) {
+                self.known_ids
+                    .entry(table)
+                    .or_default()
+                    .insert(id.to_string());
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Whether an existing row is replaced under the conflict policy.
+    /// `newest` needs a strictly newer `updated_at`; rows of tables without
+    /// one are kept.
+    fn should_overwrite(&self, row: &Map<String, Value>, existing: Option<i64>) -> bool {
+        match self.on_conflict {
+            "overwrite" => true,
+            "newest" => match (row.get("updated_at").and_then(Value::as_i64), existing) {
+                (Some(incoming), Some(current)) => incoming > current,
+                (Some(_), None) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Reject a row whose reference points at a record that is neither in the
+    /// database nor earlier in the dump.
+    fn check_references(
+        &mut self,
+        config: &table_config::TableConfig,
+        row: &Map<String, Value>,
+    ) -> Result<(), AppError> {
+        for (col, target) in config.references {
+            let Some(id) = row.get(*col).and_then(Value::as_str) else {
+                continue;
+            };
+            if self
+                .known_ids
+                .get(target)
+                .is_some_and(|ids| ids.contains(id))
+            {
+                continue;
+            }
+            let found = self.run(
+                "SELECT id FROM #[table] WHERE id=@id".into(),
+                &["id"],
+                json!({
+                    "table": {"enum": table_config::build_table_enum(models::EXPORT_TABLES)},
+                    "id": {}
+                }),
+                json!({"table": target, "id": id}),
+            )?;
+            if found.is_empty() {
+                return Err(AppError::NotFound(format!(
+                    "{col} not found: {target}/{id}"
+                )));
+            }
+            self.known_ids
+                .entry(target)
+                .or_default()
+                .insert(id.to_string());
+        }
+        Ok(())
+    }
+
+    /// `None` when the row is new, otherwise `Some` of the stored `updated_at`
+    /// (itself `None` for tables without the column).
+    fn existing_updated_at(
+        &mut self,
+        table: &str,
+        row: &Map<String, Value>,
+    ) -> Result<Option<Option<i64>>, AppError> {
+        let updated_at = if has_updated_at(table) {
+            "updated_at"
+        } else {
+            "NULL AS updated_at"
+        };
+        let (where_sql, mut args, mut params) = key_condition(table, row);
+        args["table"] = json!({"enum": table_config::build_table_enum(models::EXPORT_TABLES)});
+        params["table"] = json!(table);
+        let found = self.run(
+            format!("SELECT {updated_at} FROM #[table] WHERE {where_sql}"),
+            &["updated_at"],
+            args,
+            params,
+        )?;
+        Ok(found
+            .first()
+            .map(|r| r.get("updated_at").and_then(Value::as_i64)))
+    }
+
+    fn insert(&mut self, table: &str, row: &Map<String, Value>) -> Result<(), AppError> {
+        let mut columns = Vec::new();
+        let mut placeholders = Vec::new();
+        let mut args = json!({
+            "table": {"enum": table_config::build_table_enum(models::EXPORT_TABLES)}
+        });
+        let mut params = json!({"table": table});
+        for (col, value) in written_columns(table, row) {
+            columns.push(format!("\"{col}\""));
+            placeholders.push(bind(&col, &value, &mut args, &mut params));
+        }
+        self.run(
+            format!(
+                "INSERT INTO #[table] ({}) VALUES ({})",
+                columns.join(", "),
+                placeholders.join(", ")
+            ),
+            &[],
+            args,
+            params,
+        )?;
+        Ok(())
+    }
+
+    fn update(&mut self, table: &str, row: &Map<String, Value>) -> Result<(), AppError> {
+        let (where_sql, mut args, mut params) = key_condition(table, row);
+        args["table"] = json!({"enum": table_config::build_table_enum(models::EXPORT_TABLES)});
+        params["table"] = json!(table);
+        let key = row_key(table);
+        let sets: Vec<String> = written_columns(table, row)
+            .into_iter()
+            .filter(|(col, _)| !key.contains(&col.as_str()))
+            .map(|(col, value)| format!("\"{col}\"={}", bind(&col, &value, &mut args, &mut params)))
+            .collect();
+        if sets.is_empty() {
+            return Ok(());
+        }
+        self.run(
+            format!("UPDATE #[table] SET {} WHERE {where_sql}", sets.join(", ")),
+            &[],
+            args,
+            params,
+        )?;
+        Ok(())
+    }
+
+    /// Run one statement, building its query definition on first use.
+    fn run(
+        &mut self,
+        sql: String,
+        returns: &[&str],
+        args: Value,
+        params: Value,
+    ) -> Result<Vec<Value>, AppError> {
+        let cache_key = format!("{sql}\n{args}");
+        if !self.queries.contains_key(&cache_key) {
+            let definitions = QueryDefinitions::from_json(json!({
+                "q": {"query": sql, "returns": returns, "args": args}
+            }))
+            .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+            self.queries.insert(cache_key.clone(), definitions);
+        }
+        let result = jankensqlhub::query_run_sqlite_with_transaction(
+            self.tx,
+            &self.queries[&cache_key],
+            "q",
+            &params,
+        )
+        .map_err(AppError::from)?;
+        Ok(result.data)
+    }
+}
+
+/// Whether a table tracks `updated_at`, which the `newest` policy compares.
+fn has_updated_at(table: &str) -> bool {
+    table_config::get(table).is_some_and(|c| c.selectable.contains(&"updated_at"))
+}
+
+/// `WHERE` body matching a row by its key, with args and params for it.
+fn key_condition(table: &str, row: &Map<String, Value>) -> (String, Value, Value) {
+    let mut args = json!({});
+    let mut params = json!({});
+    let mut conditions = Vec::new();
+    for key in row_key(table) {
+        conditions.push(format!("\"{key}\"=@k_{key}"));
+        args[format!("k_{key}")] = json!({});
+        params[format!("k_{key}")] = row[*key].clone();
+    }
+    (conditions.join(" AND "), args, params)
+}
+
+/// The row's columns plus the normalized name keys derived from them.
+fn written_columns(table: &str, row: &Map<String, Value>) -> Vec<(String, Value)> {
+    let mut columns: Vec<(String, Value)> =
+        row.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    for (col, value) in row {
+        if let Some(key_col) = table_config::normalized_key(table, col) {
+            let key = value.as_str().map(normalize::name_key);
+            columns.push((key_col.to_string(), json!(key)));
+        }
+    }
+    columns
+}
+
+/// Bind a value and return its placeholder (`NULL` for null, which
+/// JankenSQLHub parameters cannot carry).
+fn bind(col: &str, value: &Value, args: &mut Value, params: &mut Value) -> String {
+    let (arg_def, param) = match value {
+        Value::Null => return "NULL".into(),
+        Value::Number(n) if n.is_i64() => (json!({"type": "integer"}), value.clone()),
+        Value::Number(_) => (json!({"type": "float"}), value.clone()),
+        Value::Bool(b) => (json!({"type": "integer"}), json!(i64::from(*b))),
+        Value::String(_) => (json!({}), value.clone()),
+        other => (json!({}), json!(other.to_string())),
+    };
+    let param_key = format!("p_{col}");
+    args[&param_key] = arg_def;
+    params[&param_key] = param;
+    format!("@{param_key}")
+}
+
+// ---------------------------------------------------------------------------
+// sync --other [--dry-run]
+// ---------------------------------------------------------------------------
+
+/// The two sides of a sync: the `JANKENOBOE_DB` database and the attached one.
+const SIDES: [&str; 2] = ["main", "other"];
+
+pub fn cmd_sync(conn: &mut Connection, other_path: &str, dry_run: bool) -> Result<Value, AppError> {
+    // ATTACH would silently create a missing file
+    let other = Path::new(other_path);
+    if !other.is_file() {
+        return Err(AppError::NotFound(format!(
+            "Database file not found: {other_path}"
+        )));
+    }
+    let main_path: String = conn.query_row("PRAGMA database_list", [], |r| r.get(2))?;
+    if !main_path.is_empty()
+        && std::fs::canonicalize(&main_path).ok() == std::fs::canonicalize(other).ok()
+    {
+        return Err(AppError::InvalidParameter(
+            "other database is the same file as JANKENOBOE_DB".into(),
+        ));
+    }
+
+    let queries = QueryDefinitions::from_json(json!({
+        "attach": {"query": "ATTACH DATABASE @path AS other", "args": {"path": {}}},
+        "detach": {"query": "DETACH DATABASE other", "args": {}}
+    }))
+    .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+    jankensqlhub::query_run_sqlite(conn, &queries, "attach", &json!({"path": other_path}))
+        .map_err(AppError::from)?;
+    let result = sync_attached(conn, dry_run);
+    jankensqlhub::query_run_sqlite(conn, &queries, "detach", &json!({})).map_err(AppError::from)?;
+
+    let (summary, conflicts) = result?;
+    Ok(json!({
+        "other": other_path,
+        "dry_run": dry_run,
+        "tables": summary,
+        "conflict_count": conflicts.len(),
+        "conflicts": conflicts
+    }))
+}
+
+/// Reconcile every table between `main` and `other` in one transaction, which
+/// spans both files.
+fn sync_attached(
+    conn: &mut Connection,
+    dry_run: bool,
+) -> Result<(Map<String, Value>, Vec<Value>), AppError> {
+    let tx = conn.transaction()?;
+    let mut syncer = Syncer {
+        tx: &tx,
+        queries: HashMap::new(),
+        present: HashMap::new(),
+        conflicts: Vec::new(),
+    };
+    for table in models::EXPORT_TABLES {
+        syncer.check_schema(table)?;
+    }
+    let mut summary = Map::new();
+    for table in models::EXPORT_TABLES {
+        summary.insert(table.to_string(), syncer.sync_table(table)?);
+    }
+    let conflicts = std::mem::take(&mut syncer.conflicts);
+    drop(syncer);
+    // Dropping the transaction without committing rolls everything back
+    if !dry_run {
+        tx.commit()?;
+    }
+    Ok((summary, conflicts))
+}
+
+/// How a row present on both sides is reconciled.
+enum Resolution {
+    /// Copy the `main` row over `other`
+    KeepMain,
+    /// Copy the `other` row over `main`
+    KeepOther,
+    /// Leave both rows as they are and report the reason
+    Conflict(&'static str),
+}
+
+struct Syncer<'a> {
+    tx: &'a Transaction<'a>,
+    queries: HashMap<String, QueryDefinitions>,
+    /// Row keys on each side once their table is synced, for reference checks
+    present: HashMap<(&'static str, &'static str), HashSet<String>>,
+    conflicts: Vec<Value>,
+}
+
+impl Syncer<'_> {
+    /// Fail early when `other` lacks a table or column, rather than partway
+    /// through the sync.
+    fn check_schema(&mut self, table: &str) -> Result<(), AppError> {
+        let found = self.run(
+            "SELECT name FROM pragma_table_info(@table, 'other')".into(),
+            &["name"],
+            json!({"table": {}}),
+            json!({"table": table}),
+        )?;
+        let names: Vec<&str> = found.iter().filter_map(|r| r["name"].as_str()).collect();
+        for col in sync_columns(table) {
+            if !names.contains(&col) {
+                return Err(AppError::InvalidParameter(format!(
+                    "other database is missing {table}.{col}; run doctor --fix on it first"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn sync_table(&mut self, table: &'static str) -> Result<Value, AppError> {
+        let main = self.load(table, "main")?;
+        let other = self.load(table, "other")?;
+        let mut counts = BTreeMap::from([
+            ("copied_to_main", 0),
+            ("copied_to_other", 0),
+            ("updated_main", 0),
+            ("updated_other", 0),
+            ("conflicts", 0),
+        ]);
+
+        let keys: std::collections::BTreeSet<&String> = main.keys().chain(other.keys()).collect();
+        for key in keys {
+            let (action, row, target) = match (main.get(key), other.get(key)) {
+                (Some(m), Some(o)) if m == o => continue,
+                (Some(m), Some(o)) => match resolve(table, m, o) {
+                    Resolution::KeepMain => ("updated_other", m, "other"),
+                    Resolution::KeepOther => ("updated_main", o, "main"),
+                    Resolution::Conflict(reason) => {
+                        self.conflict(table, key, reason, Some(m), Some(o));
+                        *counts.get_mut("conflicts").unwrap() += 1;
+                        continue;
+                    }
+                },
+                (Some(m), None) => ("copied_to_other", m, "other"),
+                (None, Some(o)) => ("copied_to_main", o, "main"),
+                (None, None) => continue,
+            };
+
+            let inserting = action.starts_with("copied");
+            let written = match self.write_blocker(table, row, target, inserting)? {
+                Some(reason) => Err(reason),
+                None if inserting => self.insert(table, row, target),
+                None => self.update(table, row, target),
+            };
+            match written {
+                Ok(()) => *counts.get_mut(action).unwrap() += 1,
+                Err(reason) => {
+                    let (m, o) = (main.get(key), other.get(key));
+                    self.conflict(table, key, &reason, m, o);
+                    *counts.get_mut("conflicts").unwrap() += 1;
+                }
+            }
+        }
+
+        // Record which keys each side now holds, for later tables' references
+        for side in SIDES {
+            let rows = self.load(table, side)?;
+            self.present
+                .insert((table, side), rows.into_keys().collect());
+        }
+        Ok(json!(counts))
+    }
+
+    /// Why a row cannot be written to `target`, if anything: a reference the
+    /// target lacks, or, when inserting, a second active learning record for
+    /// the same song.
+    fn write_blocker(
+        &mut self,
+        table: &str,
+        row: &Map<String, Value>,
+        target: &'static str,
+        inserting: bool,
+    ) -> Result<Option<String>, AppError> {
+        let config = table_config::get(table)
+            .ok_or_else(|| AppError::Internal(format!("No config for table: {table}")))?;
+        for (col, referenced) in config.references {
+            let Some(id) = row.get(*col).and_then(Value::as_str) else {
+                continue;
+            };
+            if !self
+                .present
+                .get(&(*referenced, target))
+                .is_some_and(|ids| ids.contains(id))
+            {
+                return Ok(Some(format!(
+                    "{col} not found on {target}: {referenced}/{id}"
+                )));
+            }
+        }
+        if inserting && table == "learning" && row["graduated"] == json!(0) {
+            let active = self.run(
+                format!("SELECT id FROM {target}.learning WHERE song_id=@song_id AND graduated=0"),
+                &["id"],
+                json!({"song_id": {}}),
+                json!({"song_id": row["song_id"]}),
+            )?;
+            if let Some(existing) = active.first() {
+                return Ok(Some(format!(
+                    "song already has an active learning record on {target}: {}",
+                    existing["id"].as_str().unwrap_or_default()
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    fn conflict(
+        &mut self,
+        table: &str,
+        key: &str,
+        reason: &str,
+        main: Option<&Map<String, Value>>,
+        other: Option<&Map<String, Value>>,
+    ) {
+        self.conflicts.push(json!({
+            "table": table,
+            "id": key,
+            "reason": reason,
+            "main": main,
+            "other": other
+        }));
+    }
+
+    /// All rows of a table on one side, by row key (`show_id:song_id` for
+    /// `rel_show_song`).
+    fn load(
+        &mut self,
+        table: &str,
+        side: &str,
+    ) -> Result<BTreeMap<String, Map<String, Value>>, AppError> {
+        let columns = sync_columns(table);
+        let select: Vec<String> = columns.iter().map(|c| format!("\"{c}\"")).collect();
+        let rows = self.run(
+            format!("SELECT {} FROM {side}.#[table]", select.join(", ")),
+            &columns,
+            json!({"table": {"enum": table_config::build_table_enum(models::EXPORT_TABLES)}}),
+            json!({"table": table}),
+        )?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match row {
+                Value::Object(map) => Some((key_string(table, &map), map)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Insert a row into `target`; a failed statement (e.g. a unique
+    /// constraint) becomes the conflict reason and leaves the transaction open.
+    fn insert(
+        &mut self,
+        table: &str,
+        row: &Map<String, Value>,
+        target: &str,
+    ) -> Result<(), String> {
+        let mut columns = Vec::new();
+        let mut placeholders = Vec::new();
+        let mut args = json!({
+            "table": {"enum": table_config::build_table_enum(models::EXPORT_TABLES)}
+        });
+        let mut params = json!({"table": table});
+        for (col, value) in row {
+            columns.push(format!("\"{col}\""));
+            placeholders.push(bind(col, value, &mut args, &mut params));
+        }
+        self.run(
+            format!(
+                "INSERT INTO {target}.#[table] ({}) VALUES ({})",
+                columns.join(", "),
+                placeholders.join(", ")
+            ),
+            &[],
+            args,
+            params,
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    fn update(
+        &mut self,
+        table: &str,
+        row: &Map<String, Value>,
+        target: &str,
+    ) -> Result<(), String> {
+        let (where_sql, mut args, mut params) = key_condition(table, row);
+        args["table"] = json!({"enum": table_config::build_table_enum(models::EXPORT_TABLES)});
+        params["table"] = json!(table);
+        let key = row_key(table);
+        let sets: Vec<String> = row
+            .iter()
+            .filter(|(col, _)| !key.contains(&col.as_str()))
+            .map(|(col, value)| format!("\"{col}\"={}", bind(col, value, &mut args, &mut params)))
+            .collect();
+        self.run(
+            format!(
+                "UPDATE {target}.#[table] SET {} WHERE {where_sql}",
+                sets.join(", ")
+            ),
+            &[],
+            args,
+            params,
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// Run one statement, building its query definition on first use.
+    fn run(
+        &mut self,
+        sql: String,
+        returns: &[&str],
+        args: Value,
+        params: Value,
+    ) -> Result<Vec<Value>, AppError> {
+        let cache_key = format!("{sql}\n{args}");
+        if !self.queries.contains_key(&cache_key) {
+            let definitions = QueryDefinitions::from_json(json!({
+                "q": {"query": sql, "returns": returns, "args": args}
+            }))
+            .map_err(|e| AppError::Internal(format!("Query definition error: {e}")))?;
+            self.queries.insert(cache_key.clone(), definitions);
+        }
+        let result = jankensqlhub::query_run_sqlite_with_transaction(
+            self.tx,
+            &self.queries[&cache_key],
+            "q",
+            &params,
+        )
+        .map_err(AppError::from)?;
+        Ok(result.data)
+    }
+}
+
+/// Decide which side of a differing row wins.
+///
+/// Learning keeps the more advanced record (graduated, then level), then the
+/// more recent review (`last_level_up_at`), then the later `updated_at`. Other
+/// tables with `updated_at` keep the later one. Tables without it, and exact
+/// ties, cannot be decided.
+fn resolve(table: &str, main: &Map<String, Value>, other: &Map<String, Value>) -> Resolution {
+    let rank = |row: &Map<String, Value>, cols: &[&str]| -> Vec<i64> {
+        cols.iter()
+            .map(|c| row.get(*c).and_then(Value::as_i64).unwrap_or(0))
+            .collect()
+    };
+    let cols: &[&str] = if table == "learning" {
+        &["graduated", "level", "last_level_up_at", "updated_at"]
+    } else if has_updated_at(table) {
+        &["updated_at"]
+    } else {
+        return Resolution::Conflict("rows differ and the table has no updated_at");
+    };
+    match rank(main, cols).cmp(&rank(other, cols)) {
+        std::cmp::Ordering::Greater => Resolution::KeepMain,
+        std::cmp::Ordering::Less => Resolution::KeepOther,
+        std::cmp::Ordering::Equal => Resolution::Conflict("rows differ with the same updated_at"),
+    }
+}
+
+/// Columns compared and copied by `sync`: the selectable fields plus the
+/// normalized name keys, so copied rows need no recomputation.
+fn sync_columns(table: &str) -> Vec<&'static str> {
+    let Some(config) = table_config::get(table) else {
+        return Vec::new();
+    };
+    let mut columns = config.selectable.to_vec();
+    columns.extend(config.normalized.iter().map(|(_, key)| *key));
+    columns
+}
+
+/// A row's key as one string, `show_id:song_id` for `rel_show_song`.
+fn key_string(table: &str, row: &Map<String, Value>) -> String {
+    row_key(table)
+        .iter()
+        .map(|k| row.get(*k).and_then(Value::as_str).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_to_csv_nulls_and_numbers() {
+        let rows = vec![json!({"id": "a", "level": 3, "note": null})];
+        assert_eq!(
+            to_csv(&["id", "level", "note"], &rows),
+            "id,level,note\r\na,3,\r\n"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Fuzzy name matching for duplicate detection.
+//!
+//! Names are folded into a comparison key (the [`normalize`](crate::normalize)
+//! folds, tokens sorted) so that "Yui Horie" / "Horie Yui", "ClariS" /
+//! "ClariS ", full-width / half-width, kana / romaji and long-vowel variants
+//! compare equal. Remaining differences are scored with Levenshtein edit
+//! distance.
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::normalize;
+
+/// Default maximum edit distance for `duplicates --fuzzy`.
+pub const DEFAULT_MAX_DISTANCE: u32 = 2;
+
+/// Split a name into normalized tokens.
+///
+/// NFKC folds full-width/half-width forms (e.g., `ＣｌａｒｉＳ` → `ClariS`),
+/// then everything is lowercased and any non-alphanumeric character acts as a
+/// separator, so punctuation and whitespace never reach the comparison.
+pub fn tokens(name: &str) -> Vec<String> {
+    let folded: String = name
+        .nfkc()
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    folded.split_whitespace().map(str::to_string).collect()
+}
+
+/// Build the token-order-insensitive comparison key for a name.
+///
+/// Normalized tokens are sorted and concatenated without separators, so
+/// "Yui Horie", "Horie Yui" and "ほりえ ゆい" share the key `horieyui`.
+pub fn comparison_key(name: &str) -> String {
+    let mut toks = normalize::fold_tokens(name);
+    toks.sort();
+    toks.concat()
+}
+
+/// Levenshtein edit distance between two strings, counted in chars.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Similarity score in `[0, 1]` derived from the edit distance of two keys.
+/// Identical keys score `1.0`; the score is rounded to 3 decimal places.
+pub fn similarity(a: &str, b: &str, distance: usize) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    let score = 1.0 - distance as f64 / longest as f64;
+    (score * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_strip_punctuation_and_case() {
+        assert_eq!(tokens("K-On!"), vec!["k", "on"]);
+        assert_eq!(tokens("  ClariS "), vec!["claris"]);
+    }
+
+    #[test]
+    fn test_tokens_fold_full_width() {
+        assert_eq!(tokens("ＣｌａｒｉＳ"), vec!["claris"]);
+        assert_eq!(tokens("ｶﾗｵｹ"), vec!["カラオケ"]);
+    }
+
+    #[test]
+    fn test_comparison_key_is_token_order_insensitive() {
+        assert_eq!(comparison_key("Yui Horie"), "horieyui");
+        assert_eq!(comparison_key("Horie Yui"), "horieyui");
+    }
+
+    #[test]
+    fn test_comparison_key_folds_kana_and_long_vowels() {
+        assert_eq!(comparison_key("ほりえ ゆい"), "horieyui");
+        assert_eq!(comparison_key("Yūki Aoi"), comparison_key("Aoi Yuuki"));
+    }
+
+    #[test]
+    fn test_comparison_key_empty() {
+        assert_eq!(comparison_key(" !? "), "");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+        assert_eq!(edit_distance("けいおん", "けいおー"), 1);
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("abcd", "abce", 1), 0.75);
+        assert_eq!(similarity("abc", "abc", 0), 1.0);
+        assert_eq!(similarity("", "", 0), 1.0);
+        assert_eq!(similarity("abc", "abd", 1), 0.667);
+    }
+}
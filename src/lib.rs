@@ -3,5 +3,11 @@ pub mod db;
 pub mod easing;
 pub mod encoding;
 pub mod error;
+pub mod fuzzy;
 pub mod models;
+pub mod name_index;
+pub mod normalize;
+pub mod search_term;
+pub mod song_type;
 pub mod table_config;
+pub mod vintage;
@@ -21,6 +21,9 @@ enum Commands {
         /// Comma-separated list of field names to return
         #[arg(long)]
         fields: String,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
     },
     /// Get multiple records by IDs
     BatchGet {
@@ -32,22 +35,60 @@ enum Commands {
         /// Comma-separated list of field names to return
         #[arg(long)]
         fields: String,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
     },
     /// Search records with table-specific filters
     Search {
         /// Table name
         table: String,
-        /// JSON object mapping column names to {value, match} pairs. String values are URL percent-decoded (e.g., %27 → ', %20 → space).
+        /// JSON object mapping column names (or `table.column` for joined search) to {value, match} pairs, optionally grouped with any/all/not. String values are URL percent-decoded (e.g., %27 → ', %20 → space).
         #[arg(long)]
         term: String,
-        /// Comma-separated list of field names to return
+        /// Comma-separated list of field names to return (`table.column` selects from a joined table)
         #[arg(long)]
         fields: String,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+        /// Maximum number of results
+        #[arg(long)]
+        limit: Option<u32>,
+        /// Number of matching rows to skip (use `next_offset` from the previous page)
+        #[arg(long, default_value = "0")]
+        offset: u32,
+        /// Comma-separated sort fields, each optionally suffixed with :asc or :desc (e.g., created_at:desc,name)
+        #[arg(long)]
+        order_by: Option<String>,
+    },
+    /// Ranked full-text search over artist, song and show names
+    Find {
+        /// Free-text query; each word matches as a prefix
+        query: String,
+        /// Comma-separated entity types to search (artist, show, song)
+        #[arg(long)]
+        tables: Option<String>,
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
     },
     /// Find duplicate records by name
     Duplicates {
         /// Table name
         table: String,
+        /// Fuzzy mode: normalized, token-order-insensitive names scored by edit distance
+        #[arg(long)]
+        fuzzy: bool,
+        /// Maximum edit distance between normalized names (fuzzy mode only)
+        #[arg(long, default_value_t = jankenoboe::fuzzy::DEFAULT_MAX_DISTANCE)]
+        max_distance: u32,
+        /// Minimum similarity score from 0 to 1 (fuzzy mode only)
+        #[arg(long, default_value = "0.75")]
+        min_score: f64,
     },
     /// Create a new record
     Create {
@@ -61,7 +102,7 @@ enum Commands {
     Update {
         /// Table name
         table: String,
-        /// Record UUID
+        /// Record UUID (`show_id:song_id` for rel_show_song)
         id: String,
         /// JSON object with fields to update. String values are URL percent-decoded (e.g., %27 → ', %20 → space).
         #[arg(long)]
@@ -69,6 +110,26 @@ enum Commands {
     },
     /// Delete a record
     Delete {
+        /// Table name
+        table: String,
+        /// Record UUID (`show_id:song_id` for rel_show_song)
+        id: String,
+        /// Also delete dependent rows (songs, rel_show_song, play_history, learning)
+        #[arg(long)]
+        cascade: bool,
+        /// List dependent rows without deleting anything
+        #[arg(long)]
+        preview: bool,
+    },
+    /// Mark a record as deleted (status=1) without removing it
+    SoftDelete {
+        /// Table name
+        table: String,
+        /// Record UUID
+        id: String,
+    },
+    /// Restore a soft-deleted record (status=0)
+    Restore {
         /// Table name
         table: String,
         /// Record UUID
@@ -82,6 +143,9 @@ enum Commands {
         /// Look-ahead offset in seconds (e.g., 7200 for 2 hours into the future). Default 0 = now only.
         #[arg(long, default_value = "0")]
         offset: u32,
+        /// Only songs that are this type in some show: OP, ED or IN
+        #[arg(long)]
+        song_type: Option<String>,
     },
     /// Add songs to learning
     LearningBatch {
@@ -131,17 +195,145 @@ enum Commands {
         #[arg(long)]
         song_ids: String,
     },
+    /// Suggest songs to add to learning, ranked by play history
+    LearningSuggest {
+        /// Maximum number of suggestions
+        #[arg(long, default_value = "20")]
+        limit: u32,
+    },
+    /// Scan for records referencing nonexistent artists, shows or songs
+    Fsck,
+    /// Run database integrity checks, optionally repairing what can be fixed
+    Doctor {
+        /// Apply fixes and report every change made
+        #[arg(long)]
+        fix: bool,
+    },
     /// Get all shows where given artists have song performances
     ShowsByArtistIds {
         /// Comma-separated artist UUIDs
         #[arg(long)]
         artist_ids: String,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
     },
     /// Get all songs by given artists
     SongsByArtistIds {
         /// Comma-separated artist UUIDs
         #[arg(long)]
         artist_ids: String,
+        /// Also include songs of groups the artists are members of
+        #[arg(long)]
+        include_groups: bool,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// Get all songs linked to given shows, with media URLs and learning status
+    SongsByShowIds {
+        /// Comma-separated show UUIDs
+        #[arg(long)]
+        show_ids: String,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// Get songs credited to given artists as composer (or arranger)
+    SongsByComposerIds {
+        /// Comma-separated artist UUIDs of the composers
+        #[arg(long)]
+        composer_ids: String,
+        /// Also include songs the artists arranged
+        #[arg(long)]
+        include_arrangers: bool,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// Get shows of a season or a range of years, in chronological order
+    ShowsByVintage {
+        /// Season and year (e.g., "Winter 2024")
+        #[arg(long)]
+        season: Option<String>,
+        /// Inclusive year range (e.g., 2020-2024) or a single year
+        #[arg(long)]
+        year_range: Option<String>,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// List game sessions, most recent first, with their results
+    GameSessions {
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: u32,
+        /// Count soft-deleted plays (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// Get the plays of given game sessions with their songs and outcomes
+    SongsBySessionIds {
+        /// Comma-separated game session UUIDs
+        #[arg(long)]
+        session_ids: String,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// Guess accuracy from play history, per song, per show or over time
+    PlayStats {
+        /// Grouping: song, show, day, week or month
+        #[arg(long, default_value = "song")]
+        by: String,
+        /// Only report groups with at least this many graded plays
+        #[arg(long, default_value = "1")]
+        min_plays: u32,
+        /// Maximum number of results (the most recent periods when grouping by time)
+        #[arg(long)]
+        limit: Option<u32>,
+        /// Include soft-deleted records (status=1)
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    /// Export tables to JSON Lines or CSV files, one file per table
+    Export {
+        /// Comma-separated table names (default: all tables)
+        #[arg(long)]
+        tables: Option<String>,
+        /// File format: jsonl or csv
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+        /// Output directory
+        #[arg(long, default_value = "jankenoboe-export")]
+        output: String,
+        /// Search term JSON limiting the rows of a single table (same syntax as search --term)
+        #[arg(long)]
+        term: Option<String>,
+    },
+    /// Import a JSON Lines export into this database in one transaction
+    ImportDump {
+        /// Directory holding <table>.jsonl files written by export
+        #[arg(long)]
+        input: String,
+        /// Comma-separated table names (default: every table with a file)
+        #[arg(long)]
+        tables: Option<String>,
+        /// What to do when a row's key already exists: skip, overwrite or newest
+        #[arg(long, default_value = "skip")]
+        on_conflict: String,
+        /// Validate and count without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Two-way merge with another database file, reporting unresolved conflicts
+    Sync {
+        /// Path of the other SQLite database
+        #[arg(long)]
+        other: String,
+        /// Report what would change without writing either file
+        #[arg(long)]
+        dry_run: bool,
     },
     /// Reassign multiple songs to a different artist
     BulkReassign {
@@ -158,6 +350,42 @@ enum Commands {
         #[arg(long)]
         to_artist_id: Option<String>,
     },
+    /// Merge duplicate records into one, repointing every reference
+    Merge {
+        /// Table name
+        table: String,
+        /// UUID of the record to keep
+        #[arg(long)]
+        keep: String,
+        /// Comma-separated UUIDs of the records to merge into --keep and soft-delete
+        #[arg(long)]
+        remove: String,
+    },
+    /// Make an alias the primary name of its artist or show
+    PromoteAlias {
+        /// Alias table (artist_alias or show_alias)
+        table: String,
+        /// Alias UUID
+        id: String,
+    },
+    /// Add artists as members of a group artist
+    AddMember {
+        /// UUID of the group artist
+        #[arg(long)]
+        group_id: String,
+        /// Comma-separated artist UUIDs of the members
+        #[arg(long)]
+        member_ids: String,
+    },
+    /// Remove artists from a group artist
+    RemoveMember {
+        /// UUID of the group artist
+        #[arg(long)]
+        group_id: String,
+        /// Comma-separated artist UUIDs of the members
+        #[arg(long)]
+        member_ids: String,
+    },
 }
 
 fn main() {
@@ -169,22 +397,71 @@ fn main() {
     };
 
     let result = match cli.command {
-        Commands::Get { table, id, fields } => commands::cmd_get(&mut conn, &table, &id, &fields),
-        Commands::BatchGet { table, ids, fields } => {
-            commands::cmd_batch_get(&mut conn, &table, &ids, &fields)
-        }
+        Commands::Get {
+            table,
+            id,
+            fields,
+            include_deleted,
+        } => commands::cmd_get(&mut conn, &table, &id, &fields, include_deleted),
+        Commands::BatchGet {
+            table,
+            ids,
+            fields,
+            include_deleted,
+        } => commands::cmd_batch_get(&mut conn, &table, &ids, &fields, include_deleted),
         Commands::Search {
             table,
             term,
             fields,
-        } => commands::cmd_search(&mut conn, &table, &term, &fields),
-        Commands::Duplicates { table } => commands::cmd_duplicates(&mut conn, &table),
+            include_deleted,
+            limit,
+            offset,
+            order_by,
+        } => commands::cmd_search(
+            &mut conn,
+            &table,
+            &term,
+            &fields,
+            &jankenoboe::models::SearchOptions {
+                include_deleted,
+                limit,
+                offset,
+                order_by,
+            },
+        ),
+        Commands::Find {
+            query,
+            tables,
+            limit,
+            include_deleted,
+        } => commands::cmd_find(&mut conn, &query, tables.as_deref(), limit, include_deleted),
+        Commands::Duplicates {
+            table,
+            fuzzy,
+            max_distance,
+            min_score,
+        } => {
+            if fuzzy {
+                commands::cmd_duplicates_fuzzy(&mut conn, &table, max_distance, min_score)
+            } else {
+                commands::cmd_duplicates(&mut conn, &table)
+            }
+        }
         Commands::Create { table, data } => commands::cmd_create(&mut conn, &table, &data),
         Commands::Update { table, id, data } => commands::cmd_update(&mut conn, &table, &id, &data),
-        Commands::Delete { table, id } => commands::cmd_delete(&mut conn, &table, &id),
-        Commands::LearningDue { limit, offset } => {
-            commands::cmd_learning_due(&mut conn, limit, offset)
-        }
+        Commands::Delete {
+            table,
+            id,
+            cascade,
+            preview,
+        } => commands::cmd_delete(&mut conn, &table, &id, cascade, preview),
+        Commands::SoftDelete { table, id } => commands::cmd_soft_delete(&mut conn, &table, &id),
+        Commands::Restore { table, id } => commands::cmd_restore(&mut conn, &table, &id),
+        Commands::LearningDue {
+            limit,
+            offset,
+            song_type,
+        } => commands::cmd_learning_due(&mut conn, limit, offset, song_type.as_deref()),
         Commands::LearningBatch {
             song_ids,
             relearn_song_ids,
@@ -212,12 +489,80 @@ fn main() {
         Commands::LearningSongStats { song_ids } => {
             commands::cmd_learning_song_stats(&mut conn, &song_ids)
         }
-        Commands::ShowsByArtistIds { artist_ids } => {
-            commands::cmd_shows_by_artist_ids(&mut conn, &artist_ids)
-        }
-        Commands::SongsByArtistIds { artist_ids } => {
-            commands::cmd_songs_by_artist_ids(&mut conn, &artist_ids)
-        }
+        Commands::LearningSuggest { limit } => commands::cmd_learning_suggest(&mut conn, limit),
+        Commands::Fsck => commands::cmd_fsck(&mut conn),
+        Commands::Doctor { fix } => commands::cmd_doctor(&mut conn, fix),
+        Commands::ShowsByArtistIds {
+            artist_ids,
+            include_deleted,
+        } => commands::cmd_shows_by_artist_ids(&mut conn, &artist_ids, include_deleted),
+        Commands::SongsByArtistIds {
+            artist_ids,
+            include_groups,
+            include_deleted,
+        } => commands::cmd_songs_by_artist_ids(
+            &mut conn,
+            &artist_ids,
+            include_groups,
+            include_deleted,
+        ),
+        Commands::SongsByShowIds {
+            show_ids,
+            include_deleted,
+        } => commands::cmd_songs_by_show_ids(&mut conn, &show_ids, include_deleted),
+        Commands::SongsByComposerIds {
+            composer_ids,
+            include_arrangers,
+            include_deleted,
+        } => commands::cmd_songs_by_composer_ids(
+            &mut conn,
+            &composer_ids,
+            include_arrangers,
+            include_deleted,
+        ),
+        Commands::ShowsByVintage {
+            season,
+            year_range,
+            include_deleted,
+        } => commands::cmd_shows_by_vintage(
+            &mut conn,
+            season.as_deref(),
+            year_range.as_deref(),
+            include_deleted,
+        ),
+        Commands::GameSessions {
+            limit,
+            include_deleted,
+        } => commands::cmd_game_sessions(&mut conn, limit, include_deleted),
+        Commands::SongsBySessionIds {
+            session_ids,
+            include_deleted,
+        } => commands::cmd_songs_by_session_ids(&mut conn, &session_ids, include_deleted),
+        Commands::PlayStats {
+            by,
+            min_plays,
+            limit,
+            include_deleted,
+        } => commands::cmd_play_stats(&mut conn, &by, min_plays, limit, include_deleted),
+        Commands::Export {
+            tables,
+            format,
+            output,
+            term,
+        } => commands::cmd_export(
+            &mut conn,
+            tables.as_deref(),
+            &format,
+            &output,
+            term.as_deref(),
+        ),
+        Commands::ImportDump {
+            input,
+            tables,
+            on_conflict,
+            dry_run,
+        } => commands::cmd_import_dump(&mut conn, &input, tables.as_deref(), &on_conflict, dry_run),
+        Commands::Sync { other, dry_run } => commands::cmd_sync(&mut conn, &other, dry_run),
         Commands::BulkReassign {
             song_ids,
             new_artist_id,
@@ -230,6 +575,20 @@ fn main() {
             from_artist_id.as_deref(),
             to_artist_id.as_deref(),
         ),
+        Commands::Merge {
+            table,
+            keep,
+            remove,
+        } => commands::cmd_merge(&mut conn, &table, &keep, &remove),
+        Commands::PromoteAlias { table, id } => commands::cmd_promote_alias(&mut conn, &table, &id),
+        Commands::AddMember {
+            group_id,
+            member_ids,
+        } => commands::cmd_add_member(&mut conn, &group_id, &member_ids),
+        Commands::RemoveMember {
+            group_id,
+            member_ids,
+        } => commands::cmd_remove_member(&mut conn, &group_id, &member_ids),
     };
 
     match result {
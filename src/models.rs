@@ -2,7 +2,18 @@ use crate::error::AppError;
 use crate::table_config;
 
 /// Valid table names for the `get` command.
-pub const GET_TABLES: &[&str] = &["artist", "show", "song", "play_history", "learning"];
+pub const GET_TABLES: &[&str] = &[
+    "artist",
+    "show",
+    "song",
+    "play_history",
+    "learning",
+    "song_credit",
+    "artist_member",
+    "artist_alias",
+    "show_alias",
+    "game_session",
+];
 
 /// Valid table names for the `search` command.
 pub const SEARCH_TABLES: &[&str] = &[
@@ -12,8 +23,16 @@ pub const SEARCH_TABLES: &[&str] = &[
     "play_history",
     "rel_show_song",
     "learning",
+    "song_credit",
+    "artist_member",
+    "artist_alias",
+    "show_alias",
+    "game_session",
 ];
 
+/// Valid table names for the `find` command (`--tables`).
+pub const FIND_TABLES: &[&str] = &["artist", "show", "song"];
+
 /// Valid table names for the `duplicates` command.
 pub const DUPLICATES_TABLES: &[&str] = &["artist", "show", "song"];
 
@@ -25,13 +44,69 @@ pub const CREATE_TABLES: &[&str] = &[
     "play_history",
     "learning",
     "rel_show_song",
+    "song_credit",
+    "artist_alias",
+    "show_alias",
+    "game_session",
 ];
 
 /// Valid table names for the `update` command.
-pub const UPDATE_TABLES: &[&str] = &["artist", "show", "song", "play_history", "learning"];
+pub const UPDATE_TABLES: &[&str] = &[
+    "artist",
+    "show",
+    "song",
+    "play_history",
+    "learning",
+    "rel_show_song",
+    "artist_alias",
+    "show_alias",
+    "game_session",
+];
 
 /// Valid table names for the `delete` command.
-pub const DELETE_TABLES: &[&str] = &["artist", "song"];
+pub const DELETE_TABLES: &[&str] = &[
+    "artist",
+    "show",
+    "song",
+    "play_history",
+    "rel_show_song",
+    "song_credit",
+    "artist_alias",
+    "show_alias",
+    "game_session",
+];
+
+/// Valid table names for the `export` command, parents before the tables
+/// that reference them so a dump can be loaded back in file order.
+pub const EXPORT_TABLES: &[&str] = &[
+    "artist",
+    "show",
+    "game_session",
+    "song",
+    "rel_show_song",
+    "song_credit",
+    "artist_member",
+    "artist_alias",
+    "show_alias",
+    "play_history",
+    "learning",
+];
+
+/// Valid export file formats.
+pub const EXPORT_FORMATS: &[&str] = &["jsonl", "csv"];
+
+/// Valid `import-dump --on-conflict` policies for rows whose key already exists.
+pub const CONFLICT_POLICIES: &[&str] = &["skip", "overwrite", "newest"];
+
+/// Valid table names for the `promote-alias` command.
+pub const ALIAS_TABLES: &[&str] = &["artist_alias", "show_alias"];
+
+/// Valid table names for the `merge` command.
+pub const MERGE_TABLES: &[&str] = &["artist", "show", "song"];
+
+/// Tables with a `status` column (0 = normal, 1 = deleted).
+/// Valid for `soft-delete` / `restore`, and filtered by read commands.
+pub const SOFT_DELETE_TABLES: &[&str] = &["artist", "show", "song", "play_history"];
 
 /// Allowed fields per table for the `get` command (--fields).
 pub fn get_fields(table: &str) -> Result<&'static [&'static str], AppError> {
@@ -66,8 +141,75 @@ pub fn update_data_fields(table: &str) -> Result<&'static [&'static str], AppErr
         .ok_or_else(|| AppError::InvalidParameter(format!("Invalid table for update: {table}")))
 }
 
+/// Paging, ordering and visibility options for the `search` command.
+#[derive(Debug, Default, Clone)]
+pub struct SearchOptions {
+    /// Include soft-deleted records (status=1)
+    pub include_deleted: bool,
+    /// Maximum number of rows to return (no limit when `None`)
+    pub limit: Option<u32>,
+    /// Number of matching rows to skip
+    pub offset: u32,
+    /// Comma-separated `field[:asc|desc]` list
+    pub order_by: Option<String>,
+}
+
+/// Build an `ORDER BY` body from a comma-separated `field[:asc|desc]` list.
+///
+/// Fields are validated against `allowed`, so they are safe to embed. `key`
+/// columns not already listed are appended ascending as a tiebreaker, which
+/// keeps `--limit`/`--offset` pages stable.
+pub fn parse_order_by(order_by: &str, allowed: &[&str], key: &[&str]) -> Result<String, AppError> {
+    let mut parts: Vec<String> = Vec::new();
+    let mut used: Vec<&str> = Vec::new();
+    for item in order_by.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (field, dir) = item.split_once(':').unwrap_or((item, "asc"));
+        let dir = match dir.to_ascii_lowercase().as_str() {
+            "asc" => "ASC",
+            "desc" => "DESC",
+            other => {
+                return Err(AppError::InvalidParameter(format!(
+                    "Invalid sort direction for {field}: {other}. Allowed: asc, desc"
+                )));
+            }
+        };
+        let field = allowed.iter().find(|f| **f == field).ok_or_else(|| {
+            AppError::InvalidParameter(format!(
+                "Invalid order-by field: {field}. Allowed: {}",
+                allowed.join(", ")
+            ))
+        })?;
+        parts.push(format!("\"{field}\" {dir}"));
+        used.push(field);
+    }
+    for col in key {
+        if !used.contains(col) {
+            parts.push(format!("\"{col}\" ASC"));
+        }
+    }
+    Ok(parts.join(", "))
+}
+
 /// Valid match modes for search term conditions.
-pub const MATCH_MODES: &[&str] = &["exact", "exact-i", "starts-with", "ends-with", "contains"];
+pub const MATCH_MODES: &[&str] = &[
+    "exact",
+    "exact-i",
+    "starts-with",
+    "ends-with",
+    "contains",
+    "gt",
+    "gte",
+    "lt",
+    "lte",
+    "between",
+    "in",
+    "is-null",
+    "is-empty",
+    "normalized",
+];
+
+/// Valid `song_credit.role` values.
+pub const CREDIT_ROLES: &[&str] = &["composer", "arranger"];
 
 /// Validate a table name against an allowed list.
 pub fn validate_table(table: &str, allowed: &[&str]) -> Result<(), AppError> {
@@ -103,6 +245,16 @@ pub fn parse_fields(fields_str: &str) -> Vec<String> {
         .collect()
 }
 
+/// SQL fragment appended to a read query's WHERE clause to hide soft-deleted
+/// rows. Empty for tables without a `status` column or when `include_deleted`.
+pub fn status_filter(table: &str, include_deleted: bool) -> &'static str {
+    if include_deleted || !SOFT_DELETE_TABLES.contains(&table) {
+        ""
+    } else {
+        " AND status = 0"
+    }
+}
+
 /// Get the current Unix timestamp in seconds.
 pub fn now_unix() -> i64 {
     std::time::SystemTime::now()
@@ -115,6 +267,44 @@ pub fn now_unix() -> i64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_order_by() {
+        let allowed = &["id", "name", "created_at"];
+        assert_eq!(
+            parse_order_by("created_at:desc, name", allowed, &["id"]).unwrap(),
+            "\"created_at\" DESC, \"name\" ASC, \"id\" ASC"
+        );
+        assert_eq!(
+            parse_order_by("id:DESC", allowed, &["id"]).unwrap(),
+            "\"id\" DESC"
+        );
+        assert_eq!(parse_order_by("", allowed, &["id"]).unwrap(), "\"id\" ASC");
+    }
+
+    #[test]
+    fn test_parse_order_by_invalid() {
+        let allowed = &["id", "name"];
+        assert_eq!(
+            parse_order_by("password", allowed, &["id"])
+                .unwrap_err()
+                .to_string(),
+            "Invalid order-by field: password. Allowed: id, name"
+        );
+        assert_eq!(
+            parse_order_by("name:up", allowed, &["id"])
+                .unwrap_err()
+                .to_string(),
+            "Invalid sort direction for name: up. Allowed: asc, desc"
+        );
+    }
+
+    #[test]
+    fn test_status_filter() {
+        assert_eq!(status_filter("artist", false), " AND status = 0");
+        assert_eq!(status_filter("artist", true), "");
+        assert_eq!(status_filter("learning", false), "");
+    }
+
     #[test]
     fn test_get_fields_invalid_table() {
         assert_eq!(
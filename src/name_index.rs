@@ -0,0 +1,98 @@
+//! Full-text name index used by `find`.
+//!
+//! `docs/init-db.sql` declares the FTS5 table `name_fts`, the rowid map
+//! `name_fts_row` and the triggers that keep them in sync with artist, song
+//! and show names. This module builds safe `MATCH` expressions and holds the
+//! SQL that `doctor --fix` uses to recreate and repopulate the index.
+
+use crate::error::AppError;
+use crate::fuzzy;
+
+/// Comment line that starts the full-text section of `docs/init-db.sql`.
+/// Everything from it to the end of the script belongs to the name index.
+pub const SCHEMA_MARKER: &str = "-- Full-text name index";
+
+/// Clear and repopulate the index from the entity tables.
+pub const REBUILD_SQL: &str = "
+DELETE FROM name_fts;
+DELETE FROM name_fts_row;
+INSERT INTO name_fts_row (entity, entity_id, field) SELECT 'artist', id, 'name' FROM artist;
+INSERT INTO name_fts_row (entity, entity_id, field) SELECT 'song', id, 'name' FROM song;
+INSERT INTO name_fts_row (entity, entity_id, field) SELECT 'song', id, 'name_context' FROM song;
+INSERT INTO name_fts_row (entity, entity_id, field) SELECT 'show', id, 'name' FROM show;
+INSERT INTO name_fts_row (entity, entity_id, field) SELECT 'show', id, 'name_romaji' FROM show;
+INSERT INTO name_fts (rowid, text)
+    SELECT r.id, a.name FROM name_fts_row r JOIN artist a ON r.entity = 'artist' AND a.id = r.entity_id;
+INSERT INTO name_fts (rowid, text)
+    SELECT r.id, CASE r.field WHEN 'name' THEN s.name ELSE s.name_context END
+    FROM name_fts_row r JOIN song s ON r.entity = 'song' AND s.id = r.entity_id;
+INSERT INTO name_fts (rowid, text)
+    SELECT r.id, CASE r.field WHEN 'name' THEN sh.name ELSE sh.name_romaji END
+    FROM name_fts_row r JOIN show sh ON r.entity = 'show' AND sh.id = r.entity_id;
+";
+
+/// The full-text section of a schema script (empty if the marker is absent).
+pub fn schema_sql(init_sql: &str) -> &str {
+    init_sql
+        .find(SCHEMA_MARKER)
+        .map_or("", |start| &init_sql[start..])
+}
+
+/// Names of the tables and triggers a schema section creates, in order.
+pub fn schema_objects(schema: &str) -> Vec<String> {
+    schema
+        .lines()
+        .filter(|line| line.trim_start().to_uppercase().starts_with("CREATE "))
+        .filter_map(|line| line.split('"').nth(1).map(str::to_string))
+        .collect()
+}
+
+/// Build an FTS5 `MATCH` expression from free text.
+///
+/// The text is split into tokens the same way fuzzy duplicate detection does
+/// (NFKC, lowercase, punctuation as separators); each token becomes a quoted
+/// prefix query, and tokens are ANDed. Quoting keeps FTS5 operators and
+/// punctuation in user input from being parsed as query syntax.
+pub fn match_expression(text: &str) -> Result<String, AppError> {
+    let tokens = fuzzy::tokens(text);
+    if tokens.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "query must contain at least one letter or digit".into(),
+        ));
+    }
+    Ok(tokens
+        .iter()
+        .map(|t| format!("\"{t}\"*"))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INIT_DB_SQL: &str = include_str!("../docs/init-db.sql");
+
+    #[test]
+    fn test_match_expression() {
+        assert_eq!(match_expression("Kataomoi").unwrap(), "\"kataomoi\"*");
+        assert_eq!(
+            match_expression("yubisaki AND \"renren\" -x*").unwrap(),
+            "\"yubisaki\"* \"and\"* \"renren\"* \"x\"*"
+        );
+        assert_eq!(match_expression("ＡＩＭＥＲ").unwrap(), "\"aimer\"*");
+        assert_eq!(
+            match_expression(" ?! ").unwrap_err().to_string(),
+            "query must contain at least one letter or digit"
+        );
+    }
+
+    #[test]
+    fn test_schema_objects_from_init_db() {
+        let objects = schema_objects(schema_sql(INIT_DB_SQL));
+        assert_eq!(objects.len(), 11);
+        assert_eq!(objects[0], "name_fts_row");
+        assert_eq!(objects[1], "name_fts");
+        assert!(objects.contains(&"show_name_fts_update".to_string()));
+    }
+}
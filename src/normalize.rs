@@ -0,0 +1,250 @@
+//! Japanese-aware name normalization.
+//!
+//! Folds the spelling variants common in AMQ data into one comparison form:
+//! full-width/half-width forms (NFKC), case, katakana/hiragana, kana/romaji
+//! (kana is transliterated to Hepburn romaji), diacritics and long vowels
+//! (`ō`, `ou`, `oo` and `ー` all become `o`), and punctuation. The result is
+//! stored as `name_key` / `name_romaji_key` and compared by `search` with
+//! `"match": "normalized"` and by `duplicates --fuzzy`.
+//!
+//! Kanji are kept as-is; there is no reading lookup, so `時間` never matches
+//! `jikan`.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Split a name into normalized tokens (see the module docs for the folds).
+pub fn fold_tokens(name: &str) -> Vec<String> {
+    let lowered: String = name.nfkc().flat_map(char::to_lowercase).collect();
+    let hiragana: String = lowered.chars().map(katakana_to_hiragana).collect();
+    let romaji = kana_to_romaji(&hiragana);
+    let stripped: String = romaji
+        .nfd()
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    stripped.split_whitespace().map(fold_long_vowels).collect()
+}
+
+/// The stored normalized key for a name: its folded tokens, in order,
+/// concatenated without separators.
+pub fn name_key(name: &str) -> String {
+    fold_tokens(name).concat()
+}
+
+/// Map a katakana character to its hiragana counterpart.
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        '\u{30a1}'..='\u{30f6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// Hepburn romaji for a single hiragana character.
+fn romaji(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ん' => "n",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+/// Transliterate hiragana to Hepburn romaji, leaving other characters as-is.
+///
+/// Handles yōon (`きゃ` → `kya`, `しゅ` → `shu`), small-vowel extended kana
+/// (`ふぁ` → `fa`, `てぃ` → `ti`), sokuon (`っ` doubles the next consonant,
+/// `っち` → `tchi`) and drops the long-vowel mark `ー`.
+fn kana_to_romaji(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len());
+    let mut sokuon = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == 'っ' {
+            sokuon = true;
+            i += 1;
+            continue;
+        }
+        if c == 'ー' {
+            i += 1;
+            continue;
+        }
+        let Some(base) = romaji(c) else {
+            out.push(c);
+            sokuon = false;
+            i += 1;
+            continue;
+        };
+
+        let mut syllable = base.to_string();
+        if let Some(&next) = chars.get(i + 1) {
+            let combined = match next {
+                // Yōon: consonant + i kana followed by a small ya/yu/yo
+                'ゃ' | 'ゅ' | 'ょ' if base.len() > 1 && base.ends_with('i') => {
+                    let vowel = &romaji(next).unwrap_or_default()[1..];
+                    let stem = &base[..base.len() - 1];
+                    Some(match stem {
+                        "sh" | "ch" | "j" => format!("{stem}{vowel}"),
+                        _ => format!("{stem}y{vowel}"),
+                    })
+                }
+                // Extended kana: consonant (or u) followed by a small vowel
+                'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' if base.len() > 1 || base == "u" => {
+                    let vowel = romaji(next).unwrap_or_default();
+                    let stem = if base == "u" {
+                        "w"
+                    } else {
+                        &base[..base.len() - 1]
+                    };
+                    Some(format!("{stem}{vowel}"))
+                }
+                _ => None,
+            };
+            if let Some(combined) = combined {
+                syllable = combined;
+                i += 1;
+            }
+        }
+
+        if sokuon {
+            if syllable.starts_with("ch") {
+                out.push('t');
+            } else if let Some(first) = syllable.chars().next().filter(|c| !"aiueon".contains(*c)) {
+                out.push(first);
+            }
+            sokuon = false;
+        }
+        out.push_str(&syllable);
+        i += 1;
+    }
+    out
+}
+
+/// Collapse long vowels in a token: `ou` → `o` and doubled vowels
+/// (`aa`, `ii`, `uu`, `ee`, `oo`) → a single vowel.
+fn fold_long_vowels(token: &str) -> String {
+    let mut out = String::with_capacity(token.len());
+    for c in token.chars() {
+        let last = out.chars().last();
+        let is_vowel = "aiueo".contains(c);
+        if is_vowel && (last == Some(c) || (c == 'u' && last == Some('o'))) {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_key_long_vowels() {
+        for name in [
+            "Tōkyō",
+            "Toukyou",
+            "Tookyoo",
+            "Tokyo",
+            "トーキョー",
+            "とうきょう",
+        ] {
+            assert_eq!(name_key(name), "tokyo", "{name}");
+        }
+    }
+
+    #[test]
+    fn test_name_key_kana_and_romaji() {
+        assert_eq!(name_key("ふわふわ"), name_key("Fuwa Fuwa"));
+        assert_eq!(name_key("フワフワ"), "fuwafuwa");
+        assert_eq!(name_key("ｹｲｵﾝ!"), "keion");
+        assert_eq!(name_key("しゅうまつ"), "shumatsu");
+        assert_eq!(name_key("まっちゃ"), "matcha");
+        assert_eq!(name_key("がっこう"), "gakko");
+        assert_eq!(name_key("ファイト"), "faito");
+        assert_eq!(name_key("ティーン"), "tin");
+        assert_eq!(name_key("ウィッチ"), "witchi");
+    }
+
+    #[test]
+    fn test_name_key_width_case_and_punctuation() {
+        assert_eq!(name_key("ＣｌａｒｉＳ"), "claris");
+        assert_eq!(name_key("K-On!!"), "kon");
+        assert_eq!(name_key("Fuwa Fuwa Time"), "fuwafuwatime");
+        assert_eq!(name_key("ふわふわ時間"), "fuwafuwa時間");
+        assert_eq!(name_key(" ?! "), "");
+    }
+
+    #[test]
+    fn test_fold_tokens_keeps_word_boundaries() {
+        assert_eq!(fold_tokens("Yūki Aoi"), vec!["yuki", "aoi"]);
+        assert_eq!(fold_tokens("ゆうき あおい"), vec!["yuki", "aoi"]);
+    }
+}
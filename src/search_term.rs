@@ -0,0 +1,342 @@
+//! Compiler for the `search --term` condition language.
+//!
+//! A term is a JSON object whose keys are column names (ANDed together) or
+//! one of the group keys `any` / `all` (arrays of terms, ORed / ANDed) and
+//! `not` (a single negated term). Each column maps to a `{value, match}`
+//! condition. Column names are resolved by the caller against a whitelist
+//! before they reach SQL; every value is bound as a JankenSQLHub parameter.
+//! The `normalized` match mode compares the stored normalized key column
+//! (see [`crate::normalize`]) instead of the column itself.
+
+use serde_json::{Map, Value, json};
+
+use crate::encoding::url_decode;
+use crate::error::AppError;
+use crate::models::MATCH_MODES;
+use crate::normalize;
+
+/// Maps a term key to a SQL column expression; the flag requests the
+/// normalized key column.
+pub type Resolver<'a> = dyn Fn(&str, bool) -> Result<String, AppError> + 'a;
+
+/// A term compiled to a SQL boolean expression plus its bound parameters.
+#[derive(Debug, Default)]
+pub struct CompiledTerm {
+    /// Boolean SQL expression referencing `@t<n>` parameters
+    pub sql: String,
+    /// JankenSQLHub arg definitions for the parameters
+    pub args: Map<String, Value>,
+    /// Parameter values keyed by name
+    pub params: Map<String, Value>,
+}
+
+impl CompiledTerm {
+    /// Bind a scalar term value and return its `@name` placeholder.
+    ///
+    /// Strings are URL percent-decoded; JSON numbers are bound as integer or
+    /// float parameters so comparisons on numeric columns stay numeric.
+    fn bind(&mut self, col: &str, value: &Value) -> Result<String, AppError> {
+        match value {
+            Value::String(s) => Ok(self.bind_raw(json!({}), json!(decode(col, s)?))),
+            Value::Number(n) if n.is_i64() => {
+                Ok(self.bind_raw(json!({"type": "integer"}), value.clone()))
+            }
+            Value::Number(_) => Ok(self.bind_raw(json!({"type": "float"}), value.clone())),
+            _ => Err(AppError::InvalidParameter(format!(
+                "Term value for '{col}' must be a string or number"
+            ))),
+        }
+    }
+
+    fn bind_raw(&mut self, arg: Value, value: Value) -> String {
+        let name = format!("t{}", self.params.len());
+        self.args.insert(name.clone(), arg);
+        self.params.insert(name.clone(), value);
+        format!("@{name}")
+    }
+}
+
+fn decode(col: &str, value: &str) -> Result<String, AppError> {
+    url_decode(value).map_err(|e| {
+        AppError::InvalidParameter(format!(
+            "URL decoding error for search value of '{col}': {e}"
+        ))
+    })
+}
+
+/// Compile a term object into a SQL boolean expression.
+///
+/// `resolve` maps a term key to a SQL column expression, returning an error
+/// for keys outside the caller's whitelist. Its output is embedded directly.
+/// The flag asks for the column's normalized key column instead; the
+/// resolver errors if the column has none.
+pub fn compile(
+    term: &Map<String, Value>,
+    resolve: &Resolver<'_>,
+) -> Result<CompiledTerm, AppError> {
+    let mut compiled = CompiledTerm::default();
+    compiled.sql = compile_object(term, resolve, &mut compiled)?;
+    Ok(compiled)
+}
+
+/// Collect the column keys of a term, including those nested in groups.
+/// Malformed groups are skipped here and reported by [`compile`].
+pub fn column_keys(term: &Map<String, Value>) -> Vec<&str> {
+    let mut keys = Vec::new();
+    for (key, cond) in term {
+        match key.as_str() {
+            "any" | "all" => {
+                for item in cond.as_array().into_iter().flatten() {
+                    if let Some(sub) = item.as_object() {
+                        keys.extend(column_keys(sub));
+                    }
+                }
+            }
+            "not" => {
+                if let Some(sub) = cond.as_object() {
+                    keys.extend(column_keys(sub));
+                }
+            }
+            col => keys.push(col),
+        }
+    }
+    keys
+}
+
+fn compile_object(
+    term: &Map<String, Value>,
+    resolve: &Resolver<'_>,
+    out: &mut CompiledTerm,
+) -> Result<String, AppError> {
+    if term.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "Term group entries cannot be empty".into(),
+        ));
+    }
+    let mut parts = Vec::new();
+    for (key, cond) in term {
+        let sql = match key.as_str() {
+            "any" | "all" => {
+                let items = cond.as_array().filter(|a| !a.is_empty()).ok_or_else(|| {
+                    AppError::InvalidParameter(format!(
+                        "'{key}' must be a non-empty array of terms"
+                    ))
+                })?;
+                let joiner = if key == "any" { " OR " } else { " AND " };
+                let mut subs = Vec::new();
+                for item in items {
+                    subs.push(compile_object(as_term(key, item)?, resolve, out)?);
+                }
+                format!("({})", subs.join(joiner))
+            }
+            "not" => format!(
+                "NOT ({})",
+                compile_object(as_term(key, cond)?, resolve, out)?
+            ),
+            col => compile_condition(col, cond, resolve, out)?,
+        };
+        parts.push(sql);
+    }
+    Ok(parts.join(" AND "))
+}
+
+fn as_term<'a>(key: &str, value: &'a Value) -> Result<&'a Map<String, Value>, AppError> {
+    value.as_object().ok_or_else(|| {
+        AppError::InvalidParameter(format!("Entries of '{key}' must be term objects"))
+    })
+}
+
+fn compile_condition(
+    col: &str,
+    cond: &Value,
+    resolve: &Resolver<'_>,
+    out: &mut CompiledTerm,
+) -> Result<String, AppError> {
+    let cond_obj = cond.as_object().ok_or_else(|| {
+        AppError::InvalidParameter(format!("Term condition for '{col}' must be an object"))
+    })?;
+    let match_mode = cond_obj
+        .get("match")
+        .and_then(|v| v.as_str())
+        .unwrap_or("exact");
+    if !MATCH_MODES.contains(&match_mode) {
+        return Err(AppError::InvalidParameter(format!(
+            "Invalid match mode: {match_mode}. Allowed: {}",
+            MATCH_MODES.join(", ")
+        )));
+    }
+    let value = cond_obj.get("value");
+    let column = resolve(col, match_mode == "normalized")?;
+
+    let sql = match match_mode {
+        "is-null" => format!("{column} IS NULL"),
+        "is-empty" => format!("IFNULL(LENGTH({column}), 0) = 0"),
+        "gt" | "gte" | "lt" | "lte" => {
+            let op = match match_mode {
+                "gt" => ">",
+                "gte" => ">=",
+                "lt" => "<",
+                _ => "<=",
+            };
+            let value = value.ok_or_else(|| {
+                AppError::InvalidParameter(format!(
+                    "Term condition for '{col}' must have a 'value'"
+                ))
+            })?;
+            format!("{column} {op} {}", out.bind(col, value)?)
+        }
+        "between" => {
+            let bounds = value
+                .and_then(|v| v.as_array())
+                .filter(|a| a.len() == 2)
+                .ok_or_else(|| {
+                    AppError::InvalidParameter(format!(
+                        "Term condition for '{col}' with match 'between' must have a [low, high] 'value'"
+                    ))
+                })?;
+            let low = out.bind(col, &bounds[0])?;
+            let high = out.bind(col, &bounds[1])?;
+            format!("{column} BETWEEN {low} AND {high}")
+        }
+        "in" => {
+            let items = value
+                .and_then(|v| v.as_array())
+                .filter(|a| !a.is_empty())
+                .ok_or_else(|| {
+                    AppError::InvalidParameter(format!(
+                        "Term condition for '{col}' with match 'in' must have a non-empty 'value' array"
+                    ))
+                })?;
+            let mut placeholders = Vec::new();
+            for item in items {
+                placeholders.push(out.bind(col, item)?);
+            }
+            format!("{column} IN ({})", placeholders.join(", "))
+        }
+        _ => {
+            let raw = value.and_then(|v| v.as_str()).ok_or_else(|| {
+                AppError::InvalidParameter(format!(
+                    "Term condition for '{col}' must have a 'value' string"
+                ))
+            })?;
+            let text = decode(col, raw)?;
+            if match_mode == "normalized" {
+                let p = out.bind_raw(json!({}), json!(normalize::name_key(&text)));
+                return Ok(format!("{column}={p}"));
+            }
+            let pattern = match match_mode {
+                "starts-with" => format!("{text}%"),
+                "ends-with" => format!("%{text}"),
+                "contains" => format!("%{text}%"),
+                _ => text,
+            };
+            let p = out.bind_raw(json!({}), json!(pattern));
+            match match_mode {
+                "exact" => format!("{column}={p}"),
+                "exact-i" => format!("LOWER({column})=LOWER({p})"),
+                _ => format!("LOWER({column}) LIKE LOWER({p})"),
+            }
+        }
+    };
+    Ok(sql)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(col: &str, normalized: bool) -> Result<String, AppError> {
+        match normalized {
+            true if col == "name" => Ok("\"name_key\"".into()),
+            true => Err(AppError::InvalidParameter(format!("no key for {col}"))),
+            false => Ok(format!("\"{col}\"")),
+        }
+    }
+
+    fn compile_str(term: &str) -> Result<CompiledTerm, AppError> {
+        let term: Map<String, Value> = serde_json::from_str(term).unwrap();
+        compile(&term, &quote)
+    }
+
+    #[test]
+    fn test_compile_groups() {
+        let c = compile_str(
+            r#"{"any": [{"name": {"value": "a"}}, {"not": {"level": {"value": 3, "match": "gt"}}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(c.sql, "(\"name\"=@t0 OR NOT (\"level\" > @t1))");
+        assert_eq!(c.params["t0"], "a");
+        assert_eq!(c.params["t1"], 3);
+        assert_eq!(c.args["t1"], json!({"type": "integer"}));
+    }
+
+    #[test]
+    fn test_compile_operators() {
+        let c = compile_str(
+            r#"{"level": {"value": [1, 4], "match": "between"},
+                "song_id": {"value": ["a", "b"], "match": "in"},
+                "name_romaji": {"match": "is-empty"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            c.sql,
+            "\"level\" BETWEEN @t0 AND @t1 AND IFNULL(LENGTH(\"name_romaji\"), 0) = 0 AND \"song_id\" IN (@t2, @t3)"
+        );
+    }
+
+    #[test]
+    fn test_compile_pattern_is_decoded_before_wildcards() {
+        let c = compile_str(r#"{"name": {"value": "a%20b", "match": "contains"}}"#).unwrap();
+        assert_eq!(c.params["t0"], "%a b%");
+    }
+
+    #[test]
+    fn test_compile_normalized() {
+        let c = compile_str(r#"{"name": {"value": "T%C5%8Dky%C5%8D", "match": "normalized"}}"#)
+            .unwrap();
+        assert_eq!(c.sql, "\"name_key\"=@t0");
+        assert_eq!(c.params["t0"], "tokyo");
+        assert_eq!(
+            compile_str(r#"{"level": {"value": "1", "match": "normalized"}}"#)
+                .unwrap_err()
+                .to_string(),
+            "no key for level"
+        );
+    }
+
+    #[test]
+    fn test_column_keys() {
+        let term: Map<String, Value> = serde_json::from_str(
+            r#"{"name": {}, "any": [{"artist.name": {}}, {"not": {"show.vintage": {}}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            column_keys(&term),
+            vec!["artist.name", "show.vintage", "name"]
+        );
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        assert_eq!(
+            compile_str(r#"{"any": []}"#).unwrap_err().to_string(),
+            "'any' must be a non-empty array of terms"
+        );
+        assert_eq!(
+            compile_str(r#"{"not": {}}"#).unwrap_err().to_string(),
+            "Term group entries cannot be empty"
+        );
+        assert!(
+            compile_str(r#"{"level": {"value": [1], "match": "between"}}"#)
+                .unwrap_err()
+                .to_string()
+                .contains("[low, high]")
+        );
+        assert_eq!(
+            compile_str(r#"{"level": {"value": true, "match": "gt"}}"#)
+                .unwrap_err()
+                .to_string(),
+            "Term value for 'level' must be a string or number"
+        );
+    }
+}
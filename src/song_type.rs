@@ -0,0 +1,83 @@
+//! Song type of a show–song link (`rel_show_song.song_type`).
+//!
+//! AMQ exports carry `songInfo.type` (1 = Opening, 2 = Ending, 3 = Insert)
+//! and `songInfo.typeNumber` (e.g. 2 for "Opening 2", 0 for inserts).
+//! [`parse`] accepts the AMQ number or a name and yields the stored code;
+//! [`label`] renders the short form players recall, e.g. "OP2".
+
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// Stored song type codes, in AMQ `type` order (1, 2, 3).
+pub const SONG_TYPES: &[&str] = &["OP", "ED", "IN"];
+
+/// Parse a song type given as a code ("OP"), a name ("Opening", "Insert
+/// Song"), or AMQ's numeric `type` (1, 2, 3 or "1"), case-insensitive.
+pub fn parse(value: &Value) -> Option<&'static str> {
+    let text = match value {
+        Value::String(s) => s.trim().to_lowercase(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    let index = match text.as_str() {
+        "op" | "opening" | "1" => 0,
+        "ed" | "ending" | "2" => 1,
+        "in" | "insert" | "insert song" | "3" => 2,
+        _ => return None,
+    };
+    Some(SONG_TYPES[index])
+}
+
+/// Parse a song type that must be well-formed.
+pub fn parse_required(value: &Value) -> Result<&'static str, AppError> {
+    parse(value).ok_or_else(|| {
+        let shown = value
+            .as_str()
+            .map_or_else(|| value.to_string(), str::to_string);
+        AppError::InvalidParameter(format!(
+            "Invalid song_type: {shown}. Expected {} (or AMQ type 1, 2, 3)",
+            SONG_TYPES.join(", ")
+        ))
+    })
+}
+
+/// Short display label: "OP2", "ED1", or the bare code when the number is
+/// unknown or 0 (AMQ numbers inserts 0). Empty when the type is unknown.
+pub fn label(song_type: &str, type_number: Option<i64>) -> String {
+    match type_number {
+        Some(n) if n > 0 && !song_type.is_empty() => format!("{song_type}{n}"),
+        _ => song_type.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_variants() {
+        for v in [json!("OP"), json!("opening"), json!(1), json!("1")] {
+            assert_eq!(parse(&v), Some("OP"), "{v}");
+        }
+        assert_eq!(parse(&json!("Ending")), Some("ED"));
+        assert_eq!(parse(&json!("Insert Song")), Some("IN"));
+        assert_eq!(parse(&json!(3)), Some("IN"));
+        for v in [json!(""), json!("OST"), json!(4), json!(null), json!(true)] {
+            assert_eq!(parse(&v), None, "{v}");
+        }
+        assert_eq!(
+            parse_required(&json!("OST")).unwrap_err().to_string(),
+            "Invalid song_type: OST. Expected OP, ED, IN (or AMQ type 1, 2, 3)"
+        );
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!(label("OP", Some(2)), "OP2");
+        assert_eq!(label("ED", None), "ED");
+        assert_eq!(label("IN", Some(0)), "IN");
+        assert_eq!(label("", Some(1)), "");
+    }
+}
@@ -16,6 +16,10 @@ pub struct TableConfig {
     pub creatable: &'static [&'static str],
     /// Fields writable via --data for update
     pub updatable: &'static [&'static str],
+    /// `(column, referenced table)` pairs; the column holds the referenced table's `id`
+    pub references: &'static [(&'static str, &'static str)],
+    /// `(column, key column)` pairs; the key column stores `normalize::name_key(column)`
+    pub normalized: &'static [(&'static str, &'static str)],
 }
 
 static ARTIST: TableConfig = TableConfig {
@@ -27,9 +31,11 @@ static ARTIST: TableConfig = TableConfig {
         "updated_at",
         "status",
     ],
-    searchable: &["name", "name_context"],
+    searchable: &["name", "name_context", "created_at"],
     creatable: &["name", "name_context"],
     updatable: &["name", "name_context", "status"],
+    references: &[],
+    normalized: &[("name", "name_key")],
 };
 
 static SHOW: TableConfig = TableConfig {
@@ -39,13 +45,26 @@ static SHOW: TableConfig = TableConfig {
         "name_romaji",
         "vintage",
         "s_type",
+        "season",
+        "year",
+        "vintage_key",
         "created_at",
         "updated_at",
         "status",
     ],
-    searchable: &["name", "name_romaji", "vintage"],
+    searchable: &[
+        "name",
+        "name_romaji",
+        "vintage",
+        "season",
+        "year",
+        "vintage_key",
+        "created_at",
+    ],
     creatable: &["name", "name_romaji", "vintage", "s_type"],
     updatable: &["name", "name_romaji", "vintage", "s_type", "status"],
+    references: &[],
+    normalized: &[("name", "name_key"), ("name_romaji", "name_romaji_key")],
 };
 
 static SONG: TableConfig = TableConfig {
@@ -58,9 +77,11 @@ static SONG: TableConfig = TableConfig {
         "updated_at",
         "status",
     ],
-    searchable: &["name", "name_context", "artist_id"],
+    searchable: &["name", "name_context", "artist_id", "created_at"],
     creatable: &["name", "name_context", "artist_id"],
     updatable: &["name", "name_context", "artist_id", "status"],
+    references: &[("artist_id", "artist")],
+    normalized: &[("name", "name_key")],
 };
 
 static PLAY_HISTORY: TableConfig = TableConfig {
@@ -71,10 +92,46 @@ static PLAY_HISTORY: TableConfig = TableConfig {
         "created_at",
         "media_url",
         "status",
+        "correct_guess",
+        "wrong_guess",
+        "answer",
+        "session_id",
+    ],
+    searchable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "created_at",
+        "correct_guess",
+        "wrong_guess",
+        "answer",
+        "session_id",
     ],
-    searchable: &["show_id", "song_id"],
-    creatable: &["show_id", "song_id", "media_url"],
-    updatable: &["show_id", "song_id", "media_url", "status"],
+    creatable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "correct_guess",
+        "wrong_guess",
+        "answer",
+        "session_id",
+    ],
+    updatable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "status",
+        "correct_guess",
+        "wrong_guess",
+        "answer",
+        "session_id",
+    ],
+    references: &[
+        ("show_id", "show"),
+        ("song_id", "song"),
+        ("session_id", "game_session"),
+    ],
+    normalized: &[],
 };
 
 static LEARNING: TableConfig = TableConfig {
@@ -92,18 +149,94 @@ static LEARNING: TableConfig = TableConfig {
         "song_id",
         "level",
         "graduated",
+        "created_at",
         "last_level_up_at",
         "level_up_path",
     ],
     creatable: &["song_id", "level_up_path"],
     updatable: &["level", "graduated"],
+    references: &[("song_id", "song")],
+    normalized: &[],
 };
 
 static REL_SHOW_SONG: TableConfig = TableConfig {
-    selectable: &["show_id", "song_id", "media_url", "created_at"],
-    searchable: &["show_id", "song_id"],
-    creatable: &["show_id", "song_id", "media_url"],
+    selectable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "song_type",
+        "type_number",
+        "created_at",
+    ],
+    searchable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "song_type",
+        "type_number",
+        "created_at",
+    ],
+    creatable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "song_type",
+        "type_number",
+    ],
+    updatable: &[
+        "show_id",
+        "song_id",
+        "media_url",
+        "song_type",
+        "type_number",
+    ],
+    references: &[("show_id", "show"), ("song_id", "song")],
+    normalized: &[],
+};
+
+static SONG_CREDIT: TableConfig = TableConfig {
+    selectable: &["id", "song_id", "artist_id", "role", "created_at"],
+    searchable: &["song_id", "artist_id", "role", "created_at"],
+    creatable: &["song_id", "artist_id", "role"],
     updatable: &[],
+    references: &[("song_id", "song"), ("artist_id", "artist")],
+    normalized: &[],
+};
+
+static ARTIST_MEMBER: TableConfig = TableConfig {
+    selectable: &["id", "group_id", "member_id", "created_at"],
+    searchable: &["group_id", "member_id", "created_at"],
+    creatable: &[],
+    updatable: &[],
+    references: &[("group_id", "artist"), ("member_id", "artist")],
+    normalized: &[],
+};
+
+static ARTIST_ALIAS: TableConfig = TableConfig {
+    selectable: &["id", "artist_id", "name", "created_at"],
+    searchable: &["artist_id", "name", "created_at"],
+    creatable: &["artist_id", "name"],
+    updatable: &["name"],
+    references: &[("artist_id", "artist")],
+    normalized: &[("name", "name_key")],
+};
+
+static SHOW_ALIAS: TableConfig = TableConfig {
+    selectable: &["id", "show_id", "name", "created_at"],
+    searchable: &["show_id", "name", "created_at"],
+    creatable: &["show_id", "name"],
+    updatable: &["name"],
+    references: &[("show_id", "show")],
+    normalized: &[("name", "name_key")],
+};
+
+static GAME_SESSION: TableConfig = TableConfig {
+    selectable: &["id", "room_name", "started_at", "created_at"],
+    searchable: &["room_name", "started_at", "created_at"],
+    creatable: &["room_name", "started_at"],
+    updatable: &["room_name", "started_at"],
+    references: &[],
+    normalized: &[],
 };
 
 /// All known table configurations.
@@ -114,8 +247,120 @@ const ALL_TABLES: &[(&str, &TableConfig)] = &[
     ("play_history", &PLAY_HISTORY),
     ("learning", &LEARNING),
     ("rel_show_song", &REL_SHOW_SONG),
+    ("song_credit", &SONG_CREDIT),
+    ("artist_member", &ARTIST_MEMBER),
+    ("artist_alias", &ARTIST_ALIAS),
+    ("show_alias", &SHOW_ALIAS),
+    ("game_session", &GAME_SESSION),
+];
+
+/// All `(table, column, referenced table)` triples, in table order.
+/// Used by `fsck` to scan every reference for orphans.
+pub fn all_references() -> Vec<(&'static str, &'static str, &'static str)> {
+    ALL_TABLES
+        .iter()
+        .flat_map(|(name, config)| {
+            config
+                .references
+                .iter()
+                .map(move |(col, target)| (*name, *col, *target))
+        })
+        .collect()
+}
+
+/// All `(table, column, key column)` normalized name triples, in table order.
+/// Used by `doctor` to recompute stale keys.
+pub fn all_normalized() -> Vec<(&'static str, &'static str, &'static str)> {
+    ALL_TABLES
+        .iter()
+        .flat_map(|(name, config)| {
+            config
+                .normalized
+                .iter()
+                .map(move |(col, key)| (*name, *col, *key))
+        })
+        .collect()
+}
+
+/// The column storing the normalized key of `table.column`, if it has one.
+pub fn normalized_key(table: &str, column: &str) -> Option<&'static str> {
+    get(table)?
+        .normalized
+        .iter()
+        .find(|(col, _)| *col == column)
+        .map(|(_, key)| *key)
+}
+
+/// Relationship tree for joined search: `(child, column, parent)` where
+/// `child.column` holds `parent.id`. Mirrors concept.md:
+/// artist → song → rel_show_song → show, song → learning, song → play_history,
+/// song → song_credit, artist → artist_alias, show → show_alias,
+/// play_history → game_session. Being a tree, there is exactly one join path
+/// between any two tables, so `song_credit.artist_id` (the credited composer
+/// or arranger) and `play_history.show_id` are not edges: `artist.*` keys
+/// always mean the performer, and `show.*` keys the shows a song belongs to.
+pub const JOIN_EDGES: &[(&str, &str, &str)] = &[
+    ("song", "artist_id", "artist"),
+    ("rel_show_song", "song_id", "song"),
+    ("rel_show_song", "show_id", "show"),
+    ("learning", "song_id", "song"),
+    ("play_history", "song_id", "song"),
+    ("song_credit", "song_id", "song"),
+    ("artist_alias", "artist_id", "artist"),
+    ("show_alias", "show_id", "show"),
+    ("play_history", "session_id", "game_session"),
 ];
 
+/// Plan the joins needed to reach every table in `targets` from `base`.
+///
+/// Returns `(table, ON condition)` pairs in join order, including any
+/// intermediate tables on the path. Tables are aliased by their own name.
+pub fn join_plan(base: &str, targets: &[&str]) -> Vec<(&'static str, String)> {
+    // Breadth-first walk of the tree, recording how each table is reached
+    let mut order: Vec<(&'static str, Option<(&'static str, String)>)> = Vec::new();
+    let mut queue = std::collections::VecDeque::new();
+    if let Some((name, _)) = ALL_TABLES.iter().find(|(name, _)| *name == base) {
+        order.push((name, None));
+        queue.push_back(*name);
+    }
+    while let Some(current) = queue.pop_front() {
+        for (child, col, parent) in JOIN_EDGES {
+            let next = if *child == current {
+                parent
+            } else if *parent == current {
+                child
+            } else {
+                continue;
+            };
+            if order.iter().any(|(t, _)| t == next) {
+                continue;
+            }
+            let on = format!("\"{child}\".\"{col}\" = \"{parent}\".\"id\"");
+            order.push((next, Some((current, on))));
+            queue.push_back(next);
+        }
+    }
+
+    // Mark targets and their ancestors back to the base
+    let mut needed: Vec<&str> = Vec::new();
+    for target in targets {
+        let mut cursor = *target;
+        while let Some((_, Some((from, _)))) = order.iter().find(|(t, _)| *t == cursor) {
+            if needed.contains(&cursor) {
+                break;
+            }
+            needed.push(cursor);
+            cursor = from;
+        }
+    }
+
+    order
+        .into_iter()
+        .filter(|(t, _)| needed.contains(t))
+        .filter_map(|(t, via)| via.map(|(_, on)| (t, on)))
+        .collect()
+}
+
 /// Look up the config for a table by name.
 pub fn get(table: &str) -> Option<&'static TableConfig> {
     ALL_TABLES
@@ -157,6 +402,39 @@ mod tests {
         assert!(config.updatable.contains(&"status"));
     }
 
+    #[test]
+    fn test_join_plan() {
+        let plan = join_plan("artist", &["show", "learning"]);
+        let tables: Vec<&str> = plan.iter().map(|(t, _)| *t).collect();
+        assert_eq!(tables, vec!["song", "rel_show_song", "learning", "show"]);
+        assert_eq!(plan[0].1, "\"song\".\"artist_id\" = \"artist\".\"id\"");
+        assert_eq!(plan[3].1, "\"rel_show_song\".\"show_id\" = \"show\".\"id\"");
+
+        let plan = join_plan("play_history", &["artist"]);
+        let tables: Vec<&str> = plan.iter().map(|(t, _)| *t).collect();
+        assert_eq!(tables, vec!["song", "artist"]);
+
+        let plan = join_plan("game_session", &["song"]);
+        assert_eq!(
+            plan[0].1,
+            "\"play_history\".\"session_id\" = \"game_session\".\"id\""
+        );
+        assert_eq!(plan[1].0, "song");
+
+        assert!(join_plan("song", &["song"]).is_empty());
+    }
+
+    #[test]
+    fn test_normalized_key() {
+        assert_eq!(
+            normalized_key("show", "name_romaji"),
+            Some("name_romaji_key")
+        );
+        assert_eq!(normalized_key("song", "name"), Some("name_key"));
+        assert_eq!(normalized_key("song", "name_context"), None);
+        assert_eq!(normalized_key("learning", "song_id"), None);
+    }
+
     #[test]
     fn test_get_unknown_table() {
         assert!(get("nonexistent").is_none());
@@ -193,9 +471,29 @@ mod tests {
     }
 
     #[test]
-    fn test_rel_show_song_has_no_updatable() {
+    fn test_all_references() {
+        let refs = all_references();
+        assert_eq!(refs.len(), 13);
+        assert_eq!(refs[0], ("song", "artist_id", "artist"));
+        assert_eq!(refs[3], ("play_history", "session_id", "game_session"));
+        assert_eq!(refs[6], ("rel_show_song", "song_id", "song"));
+        assert_eq!(refs[8], ("song_credit", "artist_id", "artist"));
+        assert_eq!(refs[10], ("artist_member", "member_id", "artist"));
+    }
+
+    #[test]
+    fn test_rel_show_song_updatable() {
         let config = get("rel_show_song").unwrap();
-        assert!(config.updatable.is_empty());
+        assert_eq!(
+            config.updatable,
+            &[
+                "show_id",
+                "song_id",
+                "media_url",
+                "song_type",
+                "type_number"
+            ]
+        );
     }
 
     #[test]
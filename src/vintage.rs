@@ -0,0 +1,153 @@
+//! Parsing of free-text `show.vintage` values.
+//!
+//! AMQ vintages look like "Winter 2024"; records imported from other sources
+//! also carry "2024 Winter", "winter 2024" or "Autumn 2019". [`parse`] turns
+//! any of these into a canonical season and year so shows can be filtered
+//! and ordered chronologically. `create`/`update` store the parsed values in
+//! `show.season`, `show.year` and `show.vintage_key`.
+
+use crate::error::AppError;
+
+/// Seasons in chronological order within a year, as stored and displayed.
+pub const SEASONS: &[&str] = &["Winter", "Spring", "Summer", "Fall"];
+
+/// A parsed vintage: canonical season name and year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Vintage {
+    pub year: i64,
+    /// Index into [`SEASONS`]
+    pub season_index: usize,
+}
+
+impl Vintage {
+    /// Canonical season name (e.g. "Winter").
+    pub fn season(&self) -> &'static str {
+        SEASONS[self.season_index]
+    }
+
+    /// Chronological sort key stored as `show.vintage_key`:
+    /// `year * 10 + season number`, e.g. `20241` for Winter 2024.
+    pub fn key(&self) -> i64 {
+        self.year * 10 + self.season_index as i64 + 1
+    }
+}
+
+/// Parse a vintage string into season and year.
+///
+/// Accepts a season word and a four-digit year in either order, in any case,
+/// separated by whitespace or punctuation. "Autumn" is read as "Fall".
+/// Returns `None` for anything else (empty, year only, unknown season).
+pub fn parse(vintage: &str) -> Option<Vintage> {
+    let words: Vec<String> = vintage
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let [a, b] = words.as_slice() else {
+        return None;
+    };
+    let (season, year) = if a.chars().all(|c| c.is_ascii_digit()) {
+        (b, a)
+    } else {
+        (a, b)
+    };
+    let season_index = season_index(season)?;
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(Vintage {
+        year: year.parse().ok()?,
+        season_index,
+    })
+}
+
+/// Parse a vintage that must be well-formed, naming it `label` in the error.
+pub fn parse_required(value: &str, label: &str) -> Result<Vintage, AppError> {
+    parse(value).ok_or_else(|| {
+        AppError::InvalidParameter(format!(
+            "Invalid {label}: {value}. Expected <season> <year>, e.g. \"Winter 2024\" (seasons: {})",
+            SEASONS.join(", ")
+        ))
+    })
+}
+
+/// Index into [`SEASONS`] for a season word, case-insensitive.
+pub fn season_index(word: &str) -> Option<usize> {
+    let word = word.to_lowercase();
+    let word = if word == "autumn" { "fall" } else { &word };
+    SEASONS.iter().position(|s| s.eq_ignore_ascii_case(word))
+}
+
+/// Parse a `--year-range` value: `2020-2024` (inclusive) or a single `2024`.
+pub fn parse_year_range(range: &str) -> Result<(i64, i64), AppError> {
+    let invalid = || {
+        AppError::InvalidParameter(format!(
+            "Invalid year range: {range}. Expected <year> or <from>-<to>, e.g. 2020-2024"
+        ))
+    };
+    let (from, to) = range.split_once('-').unwrap_or((range, range));
+    let from: i64 = from.trim().parse().map_err(|_| invalid())?;
+    let to: i64 = to.trim().parse().map_err(|_| invalid())?;
+    if from > to {
+        return Err(invalid());
+    }
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variants() {
+        let winter_2024 = Some(Vintage {
+            year: 2024,
+            season_index: 0,
+        });
+        for v in ["Winter 2024", "winter 2024", "2024 Winter", "WINTER-2024"] {
+            assert_eq!(parse(v), winter_2024, "{v}");
+        }
+        assert_eq!(parse("Autumn 2019").unwrap().season(), "Fall");
+        assert_eq!(parse("Fall 2019").unwrap().season(), "Fall");
+        for v in [
+            "",
+            "2024",
+            "Winter",
+            "Monsoon 2024",
+            "Winter 24",
+            "Winter 2024 TV",
+        ] {
+            assert_eq!(parse(v), None, "{v}");
+        }
+    }
+
+    #[test]
+    fn test_vintage_order_is_chronological() {
+        let mut v: Vec<Vintage> = ["Fall 2023", "Spring 2024", "Winter 2024", "Summer 2023"]
+            .iter()
+            .filter_map(|s| parse(s))
+            .collect();
+        v.sort();
+        let names: Vec<String> = v
+            .iter()
+            .map(|v| format!("{} {}", v.season(), v.year))
+            .collect();
+        assert_eq!(
+            names,
+            ["Summer 2023", "Fall 2023", "Winter 2024", "Spring 2024"]
+        );
+        let keys: Vec<i64> = v.iter().map(Vintage::key).collect();
+        assert_eq!(keys, [20233, 20234, 20241, 20242]);
+    }
+
+    #[test]
+    fn test_parse_year_range() {
+        assert_eq!(parse_year_range("2020-2024").unwrap(), (2020, 2024));
+        assert_eq!(parse_year_range("2024").unwrap(), (2024, 2024));
+        assert_eq!(
+            parse_year_range("2024-2020").unwrap_err().to_string(),
+            "Invalid year range: 2024-2020. Expected <year> or <from>-<to>, e.g. 2020-2024"
+        );
+        assert!(parse_year_range("recent").is_err());
+    }
+}
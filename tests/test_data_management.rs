@@ -1,4 +1,5 @@
 use jankenoboe::commands;
+use jankenoboe::models::SearchOptions;
 use rusqlite::Connection;
 
 fn test_conn() -> Connection {
@@ -41,6 +42,40 @@ fn insert_show(conn: &mut Connection, name: &str, vintage: &str) -> String {
     id
 }
 
+fn insert_play_history(conn: &mut Connection, show_id: &str, song_id: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO play_history (id, show_id, song_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![id, show_id, song_id, now],
+    )
+    .unwrap();
+    id
+}
+
+fn insert_rel(conn: &mut Connection, show_id: &str, song_id: &str, media_url: &str) {
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO rel_show_song (show_id, song_id, media_url, created_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![show_id, song_id, media_url, now],
+    )
+    .unwrap();
+}
+
+fn insert_credit(conn: &mut Connection, song_id: &str, artist_id: &str, role: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO song_credit (id, song_id, artist_id, role) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![id, song_id, artist_id, role],
+    )
+    .unwrap();
+    id
+}
+
+fn count_rows(conn: &Connection, sql: &str) -> i64 {
+    conn.query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
 fn insert_learning(
     conn: &mut Connection,
     song_id: &str,
@@ -66,7 +101,14 @@ fn test_create_artist() {
     let r = commands::cmd_create(&mut c, "artist", r#"{"name":"ChoQMay"}"#).unwrap();
     let id = r["id"].as_str().unwrap();
     assert!(!id.is_empty());
-    let g = commands::cmd_get(&mut c, "artist", id, "name,status,created_at,updated_at").unwrap();
+    let g = commands::cmd_get(
+        &mut c,
+        "artist",
+        id,
+        "name,status,created_at,updated_at",
+        false,
+    )
+    .unwrap();
     let rec = &g["results"][0];
     assert_eq!(rec["name"], "ChoQMay");
     assert_eq!(rec["status"], 0);
@@ -84,7 +126,7 @@ fn test_create_show_all_fields() {
     )
     .unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "show", id, "name,vintage,s_type").unwrap();
+    let g = commands::cmd_get(&mut c, "show", id, "name,vintage,s_type", false).unwrap();
     assert_eq!(g["results"][0]["name"], "Sign");
     assert_eq!(g["results"][0]["vintage"], "Winter 2024");
     assert_eq!(g["results"][0]["s_type"], "TV");
@@ -101,7 +143,7 @@ fn test_create_song() {
     )
     .unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "song", id, "name,artist_id").unwrap();
+    let g = commands::cmd_get(&mut c, "song", id, "name,artist_id", false).unwrap();
     assert_eq!(g["results"][0]["name"], "snowspring");
     assert_eq!(g["results"][0]["artist_id"], aid);
 }
@@ -119,11 +161,148 @@ fn test_create_play_history() {
     )
     .unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "play_history", id, "show_id,song_id,media_url").unwrap();
+    let g = commands::cmd_get(
+        &mut c,
+        "play_history",
+        id,
+        "show_id,song_id,media_url",
+        false,
+    )
+    .unwrap();
     assert_eq!(g["results"][0]["show_id"], shid);
     assert_eq!(g["results"][0]["media_url"], "https://ex.com");
 }
 
+#[test]
+fn test_create_update_play_history_outcome() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    let r = commands::cmd_create(
+        &mut c,
+        "play_history",
+        &format!(
+            r#"{{"show_id":"{shid}","song_id":"{sid}","correct_guess":false,"wrong_guess":true,"answer":"Sh 2"}}"#
+        ),
+    )
+    .unwrap();
+    let id = r["id"].as_str().unwrap();
+    let fields = "correct_guess,wrong_guess,answer";
+    let g = commands::cmd_get(&mut c, "play_history", id, fields, false).unwrap();
+    assert_eq!(
+        g["results"][0],
+        serde_json::json!({"correct_guess": 0, "wrong_guess": 1, "answer": "Sh 2"})
+    );
+
+    commands::cmd_update(
+        &mut c,
+        "play_history",
+        id,
+        r#"{"correct_guess":1,"wrong_guess":0}"#,
+    )
+    .unwrap();
+    let g = commands::cmd_get(&mut c, "play_history", id, fields, false).unwrap();
+    assert_eq!(g["results"][0]["correct_guess"], 1);
+
+    let err = commands::cmd_update(
+        &mut c,
+        "play_history",
+        id,
+        r#"{"correct_guess":true,"wrong_guess":true}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "correct_guess and wrong_guess cannot both be true"
+    );
+    let err =
+        commands::cmd_update(&mut c, "play_history", id, r#"{"wrong_guess":"yes"}"#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"wrong_guess must be true, false, 0 or 1, got: "yes""#
+    );
+}
+
+#[test]
+fn test_delete_game_session_with_plays() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    let session = commands::cmd_create(
+        &mut c,
+        "game_session",
+        r#"{"room_name":"Ranked","started_at":1700000000}"#,
+    )
+    .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let kept = insert_play_history(&mut c, &shid, &sid);
+    for _ in 0..2 {
+        commands::cmd_create(
+            &mut c,
+            "play_history",
+            &format!(r#"{{"show_id":"{shid}","song_id":"{sid}","session_id":"{session}"}}"#),
+        )
+        .unwrap();
+    }
+
+    let preview = commands::cmd_delete(&mut c, "game_session", &session, false, true).unwrap();
+    assert_eq!(preview["dependent_count"], 2);
+    let err = commands::cmd_delete(&mut c, "game_session", &session, false, false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "game_session/{session} has 2 dependent row(s); use --cascade to delete them too (--preview lists them)"
+        )
+    );
+    let r = commands::cmd_delete(&mut c, "game_session", &session, true, false).unwrap();
+    assert_eq!(r["dependents_deleted"]["play_history"], 2);
+    let left: i64 = c
+        .query_row("SELECT COUNT(*) FROM play_history", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(left, 1);
+    assert!(
+        !commands::cmd_get(&mut c, "play_history", &kept, "id", false).unwrap()["results"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_create_game_session_validation() {
+    let mut c = test_conn();
+    let err = commands::cmd_create(&mut c, "game_session", r#"{"room_name":"Solo"}"#).unwrap_err();
+    assert_eq!(err.to_string(), "started_at is required");
+    let err = commands::cmd_create(
+        &mut c,
+        "game_session",
+        r#"{"room_name":"Solo","started_at":"2024-01-01"}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"started_at must be a unix timestamp, got: "2024-01-01""#
+    );
+    let data = r#"{"room_name":"Solo","started_at":1700000000}"#;
+    commands::cmd_create(&mut c, "game_session", data).unwrap();
+    let err = commands::cmd_create(&mut c, "game_session", data).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "UNIQUE constraint failed: game_session.room_name, game_session.started_at"
+    );
+    let err = commands::cmd_create(
+        &mut c,
+        "play_history",
+        r#"{"show_id":"x","song_id":"y","session_id":"ghost"}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+}
+
 #[test]
 fn test_create_learning_defaults() {
     let mut c = test_conn();
@@ -136,6 +315,7 @@ fn test_create_learning_defaults() {
         "learning",
         id,
         "song_id,level,graduated,last_level_up_at,level_up_path",
+        false,
     )
     .unwrap();
     let rec = &g["results"][0];
@@ -181,6 +361,102 @@ fn test_create_invalid_field() {
     );
 }
 
+// === REFERENTIAL INTEGRITY ===
+
+#[test]
+fn test_create_song_with_missing_artist() {
+    let mut c = test_conn();
+    let err =
+        commands::cmd_create(&mut c, "song", r#"{"name":"S","artist_id":"ghost"}"#).unwrap_err();
+    assert_eq!(err.to_string(), "artist_id not found: artist/ghost");
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM song"), 0);
+}
+
+#[test]
+fn test_create_play_history_with_missing_song() {
+    let mut c = test_conn();
+    let shid = insert_show(&mut c, "Sh", "2024");
+    let err = commands::cmd_create(
+        &mut c,
+        "play_history",
+        &format!(r#"{{"show_id":"{shid}","song_id":"ghost"}}"#),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "song_id not found: song/ghost");
+}
+
+#[test]
+fn test_create_rel_show_song_with_missing_show() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let err = commands::cmd_create(
+        &mut c,
+        "rel_show_song",
+        &format!(r#"{{"show_id":"ghost","song_id":"{sid}"}}"#),
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "show_id not found: show/ghost");
+}
+
+#[test]
+fn test_create_song_credit() {
+    let mut c = test_conn();
+    let singer = insert_artist(&mut c, "ChoQMay");
+    let composer = insert_artist(&mut c, "Yoshihisa Hirano");
+    let sid = insert_song(&mut c, "snowspring", &singer);
+    let r = commands::cmd_create(
+        &mut c,
+        "song_credit",
+        &format!(r#"{{"song_id":"{sid}","artist_id":"{composer}","role":"Composer"}}"#),
+    )
+    .unwrap();
+    let id = r["id"].as_str().unwrap();
+    let g = commands::cmd_get(&mut c, "song_credit", id, "song_id,artist_id,role", false).unwrap();
+    assert_eq!(
+        g["results"][0],
+        serde_json::json!({"song_id": sid, "artist_id": composer, "role": "composer"})
+    );
+
+    let create = |c: &mut Connection, data: String| {
+        commands::cmd_create(c, "song_credit", &data)
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        create(
+            &mut c,
+            format!(r#"{{"song_id":"{sid}","artist_id":"{composer}","role":"lyricist"}}"#)
+        ),
+        "Invalid role: lyricist. Allowed: composer, arranger"
+    );
+    assert_eq!(
+        create(
+            &mut c,
+            format!(r#"{{"song_id":"{sid}","role":"arranger"}}"#)
+        ),
+        "artist_id is required"
+    );
+    assert_eq!(
+        create(
+            &mut c,
+            format!(r#"{{"song_id":"{sid}","artist_id":"ghost","role":"arranger"}}"#)
+        ),
+        "artist_id not found: artist/ghost"
+    );
+}
+
+#[test]
+fn test_update_song_to_missing_artist() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let err = commands::cmd_update(&mut c, "song", &sid, r#"{"artist_id":"ghost"}"#).unwrap_err();
+    assert_eq!(err.to_string(), "artist_id not found: artist/ghost");
+    let g = commands::cmd_get(&mut c, "song", &sid, "artist_id", false).unwrap();
+    assert_eq!(g["results"][0]["artist_id"], aid);
+}
+
 // === UPDATE ===
 
 #[test]
@@ -189,10 +465,90 @@ fn test_update_artist_name() {
     let id = insert_artist(&mut c, "Old");
     let r = commands::cmd_update(&mut c, "artist", &id, r#"{"name":"New"}"#).unwrap();
     assert_eq!(r["updated"], true);
-    let g = commands::cmd_get(&mut c, "artist", &id, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "artist", &id, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "New");
 }
 
+#[test]
+fn test_create_and_update_maintain_name_keys() {
+    let mut c = test_conn();
+    let r = commands::cmd_create(
+        &mut c,
+        "show",
+        r#"{"name":"けいおん!","name_romaji":"K-On!","vintage":"Spring 2009"}"#,
+    )
+    .unwrap();
+    let id = r["id"].as_str().unwrap().to_string();
+    let keys = |c: &Connection| -> (String, String) {
+        c.query_row(
+            "SELECT name_key, name_romaji_key FROM show WHERE id = ?1",
+            [&id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+    };
+    assert_eq!(keys(&c), ("keion".into(), "kon".into()));
+
+    commands::cmd_update(&mut c, "show", &id, r#"{"name":"ケイオン"}"#).unwrap();
+    assert_eq!(keys(&c), ("keion".into(), "kon".into()));
+    commands::cmd_update(&mut c, "show", &id, r#"{"name_romaji":"Keion"}"#).unwrap();
+    assert_eq!(keys(&c), ("keion".into(), "keion".into()));
+}
+
+#[test]
+fn test_create_and_update_derive_vintage_fields() {
+    let mut c = test_conn();
+    let r =
+        commands::cmd_create(&mut c, "show", r#"{"name":"Sign","vintage":"2024 winter"}"#).unwrap();
+    let id = r["id"].as_str().unwrap().to_string();
+    let fields = |c: &mut Connection| {
+        commands::cmd_get(c, "show", &id, "vintage,season,year,vintage_key", false).unwrap()
+            ["results"][0]
+            .clone()
+    };
+    let row = fields(&mut c);
+    assert_eq!(row["vintage"], "2024 winter");
+    assert_eq!(row["season"], "Winter");
+    assert_eq!(row["year"], 2024);
+    assert_eq!(row["vintage_key"], 20241);
+
+    commands::cmd_update(&mut c, "show", &id, r#"{"vintage":"Autumn 2023"}"#).unwrap();
+    let row = fields(&mut c);
+    assert_eq!(row["season"], "Fall");
+    assert_eq!(row["vintage_key"], 20234);
+
+    commands::cmd_update(&mut c, "show", &id, r#"{"vintage":""}"#).unwrap();
+    let row = fields(&mut c);
+    assert_eq!(row["season"], serde_json::Value::Null);
+    assert_eq!(row["year"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_create_and_update_reject_unparseable_vintage() {
+    let mut c = test_conn();
+    let err = commands::cmd_create(&mut c, "show", r#"{"name":"X","vintage":"2024"}"#)
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        err,
+        "Invalid vintage: 2024. Expected <season> <year>, e.g. \"Winter 2024\" (seasons: Winter, Spring, Summer, Fall)"
+    );
+    let id = insert_show(&mut c, "Show", "Winter 2024");
+    assert!(
+        commands::cmd_update(&mut c, "show", &id, r#"{"vintage":"Monsoon 2024"}"#)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid vintage: Monsoon 2024")
+    );
+    let err = commands::cmd_update(&mut c, "show", &id, r#"{"season":"Fall"}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("Invalid field for update show: season"),
+        "{err}"
+    );
+}
+
 // === JSON VALUE TYPE EDGE CASES ===
 
 #[test]
@@ -204,7 +560,7 @@ fn test_update_with_boolean_value() {
     let lid = insert_learning(&mut c, &sid, 19, 1000, 0);
     // Use a JSON boolean `true` instead of number `1`
     commands::cmd_update(&mut c, "learning", &lid, r#"{"graduated":true}"#).unwrap();
-    let g = commands::cmd_get(&mut c, "learning", &lid, "graduated").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "graduated", false).unwrap();
     assert_eq!(g["results"][0]["graduated"], 1);
 }
 
@@ -214,7 +570,7 @@ fn test_create_with_null_value() {
     let mut c = test_conn();
     let r = commands::cmd_create(&mut c, "artist", r#"{"name":"X","name_context":null}"#).unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "artist", id, "name_context").unwrap();
+    let g = commands::cmd_get(&mut c, "artist", id, "name_context", false).unwrap();
     // null value in SQLite — returned as empty or null
     let val = &g["results"][0]["name_context"];
     assert!(val.is_null() || val.as_str() == Some(""));
@@ -233,7 +589,7 @@ fn test_search_with_float_column() {
         "INSERT INTO learning (id, song_id, level, created_at, updated_at, last_level_up_at, level_up_path, graduated) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         rusqlite::params![id, sid, 2.5f64, now, now, 0, "[1,2,3]", 0],
     ).unwrap();
-    let r = commands::cmd_get(&mut c, "learning", &id, "level").unwrap();
+    let r = commands::cmd_get(&mut c, "learning", &id, "level", false).unwrap();
     // Float 2.5 should be returned
     let level = &r["results"][0]["level"];
     assert_eq!(level.as_f64().unwrap(), 2.5);
@@ -250,7 +606,7 @@ fn test_get_with_null_column_value() {
         "INSERT INTO artist (id, name, name_context, created_at, updated_at) VALUES (?1, ?2, NULL, ?3, ?4)",
         rusqlite::params![id, "Test", now, now],
     ).unwrap();
-    let r = commands::cmd_get(&mut c, "artist", &id, "name_context").unwrap();
+    let r = commands::cmd_get(&mut c, "artist", &id, "name_context", false).unwrap();
     assert!(r["results"][0]["name_context"].is_null());
 }
 
@@ -268,7 +624,7 @@ fn test_create_with_json_array_value() {
     )
     .unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "learning", id, "level_up_path").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", id, "level_up_path", false).unwrap();
     assert_eq!(g["results"][0]["level_up_path"], "[1,2,3]");
 }
 
@@ -280,7 +636,7 @@ fn test_create_with_float_number() {
     let sid = insert_song(&mut c, "S", &aid);
     let lid = insert_learning(&mut c, &sid, 0, 0, 0);
     commands::cmd_update(&mut c, "learning", &lid, r#"{"level":3.5}"#).unwrap();
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level", false).unwrap();
     // SQLite will store 3.5 as REAL
     assert_eq!(g["results"][0]["level"].as_f64().unwrap(), 3.5);
 }
@@ -292,7 +648,7 @@ fn test_update_learning_level_sets_last_level_up_at() {
     let sid = insert_song(&mut c, "S", &aid);
     let lid = insert_learning(&mut c, &sid, 3, 0, 0);
     commands::cmd_update(&mut c, "learning", &lid, r#"{"level":8}"#).unwrap();
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level,last_level_up_at").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level,last_level_up_at", false).unwrap();
     assert_eq!(g["results"][0]["level"], 8);
     assert!(g["results"][0]["last_level_up_at"].as_i64().unwrap() > 0);
 }
@@ -304,7 +660,7 @@ fn test_update_learning_graduate() {
     let sid = insert_song(&mut c, "S", &aid);
     let lid = insert_learning(&mut c, &sid, 19, 1000, 0);
     commands::cmd_update(&mut c, "learning", &lid, r#"{"graduated":1}"#).unwrap();
-    let g = commands::cmd_get(&mut c, "learning", &lid, "graduated").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "graduated", false).unwrap();
     assert_eq!(g["results"][0]["graduated"], 1);
 }
 
@@ -315,7 +671,7 @@ fn test_update_graduated_does_not_set_last_level_up_at() {
     let sid = insert_song(&mut c, "S", &aid);
     let lid = insert_learning(&mut c, &sid, 19, 0, 0);
     commands::cmd_update(&mut c, "learning", &lid, r#"{"graduated":1}"#).unwrap();
-    let g = commands::cmd_get(&mut c, "learning", &lid, "last_level_up_at").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "last_level_up_at", false).unwrap();
     assert_eq!(g["results"][0]["last_level_up_at"], 0);
 }
 
@@ -333,7 +689,12 @@ fn test_update_not_found() {
 #[test]
 fn test_update_invalid_table() {
     let mut c = test_conn();
-    assert!(commands::cmd_update(&mut c, "rel_show_song", "x", r#"{"show_id":"y"}"#).is_err());
+    assert!(
+        commands::cmd_update(&mut c, "rel", "x", r#"{"show_id":"y"}"#)
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid table")
+    );
 }
 
 #[test]
@@ -365,9 +726,9 @@ fn test_update_empty_data() {
 fn test_delete_artist() {
     let mut c = test_conn();
     let id = insert_artist(&mut c, "A");
-    let r = commands::cmd_delete(&mut c, "artist", &id).unwrap();
+    let r = commands::cmd_delete(&mut c, "artist", &id, false, false).unwrap();
     assert_eq!(r["deleted"], true);
-    let g = commands::cmd_get(&mut c, "artist", &id, "id").unwrap();
+    let g = commands::cmd_get(&mut c, "artist", &id, "id", false).unwrap();
     assert_eq!(g["results"].as_array().unwrap().len(), 0);
 }
 
@@ -377,7 +738,7 @@ fn test_delete_song() {
     let aid = insert_artist(&mut c, "A");
     let sid = insert_song(&mut c, "S", &aid);
     assert_eq!(
-        commands::cmd_delete(&mut c, "song", &sid).unwrap()["deleted"],
+        commands::cmd_delete(&mut c, "song", &sid, false, false).unwrap()["deleted"],
         true
     );
 }
@@ -386,7 +747,7 @@ fn test_delete_song() {
 fn test_delete_not_found() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_delete(&mut c, "artist", "no")
+        commands::cmd_delete(&mut c, "artist", "no", false, false)
             .unwrap_err()
             .to_string()
             .contains("Record not found")
@@ -396,9 +757,157 @@ fn test_delete_not_found() {
 #[test]
 fn test_delete_invalid_table() {
     let mut c = test_conn();
-    assert!(commands::cmd_delete(&mut c, "show", "x").is_err());
-    assert!(commands::cmd_delete(&mut c, "learning", "x").is_err());
-    assert!(commands::cmd_delete(&mut c, "play_history", "x").is_err());
+    assert!(
+        commands::cmd_delete(&mut c, "learning", "x", false, false)
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid table")
+    );
+}
+
+#[test]
+fn test_delete_artist_with_songs_requires_cascade() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    insert_song(&mut c, "S", &aid);
+    assert_eq!(
+        commands::cmd_delete(&mut c, "artist", &aid, false, false)
+            .unwrap_err()
+            .to_string(),
+        format!(
+            "artist/{aid} has 1 dependent row(s); use --cascade to delete them too (--preview lists them)"
+        )
+    );
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist"), 1);
+}
+
+#[test]
+fn test_delete_artist_preview_lists_dependents() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &sid, "");
+    let ph = insert_play_history(&mut c, &shid, &sid);
+    let lid = insert_learning(&mut c, &sid, 0, 0, 0);
+
+    let r = commands::cmd_delete(&mut c, "artist", &aid, true, true).unwrap();
+    assert_eq!(r["preview"], true);
+    assert_eq!(r["dependent_count"], 4);
+    assert_eq!(
+        r["dependents"],
+        serde_json::json!({
+            "song": [sid],
+            "rel_show_song": [format!("{shid}:{sid}")],
+            "play_history": [ph],
+            "learning": [lid],
+            "song_credit": [],
+            "artist_member": [],
+            "artist_alias": [],
+            "show_alias": []
+        })
+    );
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM song"), 1);
+}
+
+#[test]
+fn test_delete_artist_cascade() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &sid, "");
+    insert_play_history(&mut c, &shid, &sid);
+    insert_learning(&mut c, &sid, 0, 0, 0);
+    // A composes another artist's song: the credit goes, the song stays
+    let other = insert_artist(&mut c, "Other");
+    let covered = insert_song(&mut c, "Covered", &other);
+    insert_credit(&mut c, &covered, &aid, "composer");
+    // ...and is a member of it: the membership goes too
+    commands::cmd_add_member(&mut c, &other, &aid).unwrap();
+    commands::cmd_create(
+        &mut c,
+        "artist_alias",
+        &format!(r#"{{"artist_id":"{aid}","name":"Ei"}}"#),
+    )
+    .unwrap();
+
+    let r = commands::cmd_delete(&mut c, "artist", &aid, true, false).unwrap();
+    assert_eq!(r["deleted"], true);
+    assert_eq!(
+        r["dependents_deleted"],
+        serde_json::json!({
+            "song": 1,
+            "rel_show_song": 1,
+            "play_history": 1,
+            "learning": 1,
+            "song_credit": 1,
+            "artist_member": 1,
+            "artist_alias": 1,
+            "show_alias": 0
+        })
+    );
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM song_credit"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist_member"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist_alias"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist"), 1);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM song"), 1);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM rel_show_song"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM play_history"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM learning"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM show"), 1);
+}
+
+#[test]
+fn test_delete_show_cascade() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &sid, "");
+    insert_play_history(&mut c, &shid, &sid);
+
+    let r = commands::cmd_delete(&mut c, "show", &shid, true, false).unwrap();
+    assert_eq!(r["dependents_deleted"]["rel_show_song"], 1);
+    assert_eq!(r["dependents_deleted"]["play_history"], 1);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM show"), 0);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM song"), 1);
+}
+
+#[test]
+fn test_delete_play_history() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    let ph = insert_play_history(&mut c, &shid, &sid);
+    let r = commands::cmd_delete(&mut c, "play_history", &ph, false, false).unwrap();
+    assert_eq!(r["deleted"], true);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM play_history"), 0);
+}
+
+#[test]
+fn test_delete_rel_show_song_by_composite_key() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &sid, "");
+    let key = format!("{shid}:{sid}");
+    commands::cmd_delete(&mut c, "rel_show_song", &key, false, false).unwrap();
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM rel_show_song"), 0);
+    assert_eq!(
+        commands::cmd_delete(&mut c, "rel_show_song", &key, false, false)
+            .unwrap_err()
+            .to_string(),
+        format!("Record not found: rel_show_song/{key}")
+    );
+    assert_eq!(
+        commands::cmd_delete(&mut c, "rel_show_song", "no-colon", false, false)
+            .unwrap_err()
+            .to_string(),
+        "rel_show_song id must be show_id:song_id, got: no-colon"
+    );
 }
 
 // === BULK-REASSIGN ===
@@ -413,9 +922,9 @@ fn test_bulk_reassign_by_song_ids() {
     let r = commands::cmd_bulk_reassign(&mut c, Some(&format!("{s1},{s2}")), Some(&a2), None, None)
         .unwrap();
     assert_eq!(r["reassigned_count"], 2);
-    let g1 = commands::cmd_get(&mut c, "song", &s1, "artist_id").unwrap();
+    let g1 = commands::cmd_get(&mut c, "song", &s1, "artist_id", false).unwrap();
     assert_eq!(g1["results"][0]["artist_id"], a2);
-    let g2 = commands::cmd_get(&mut c, "song", &s2, "artist_id").unwrap();
+    let g2 = commands::cmd_get(&mut c, "song", &s2, "artist_id", false).unwrap();
     assert_eq!(g2["results"][0]["artist_id"], a2);
 }
 
@@ -451,6 +960,414 @@ fn test_bulk_reassign_empty_song_ids() {
     );
 }
 
+// === MERGE ===
+
+#[test]
+fn test_merge_artist_moves_songs_and_soft_deletes() {
+    let mut c = test_conn();
+    let keep = insert_artist(&mut c, "Yui Horie");
+    let dup = insert_artist(&mut c, "yui horie");
+    insert_song(&mut c, "Kimi ni Todoke", &keep);
+    let s2 = insert_song(&mut c, "Love Destiny", &dup);
+    let r = commands::cmd_merge(&mut c, "artist", &keep, &dup).unwrap();
+    assert_eq!(r["kept_id"], keep);
+    assert_eq!(r["removed_ids"], serde_json::json!([dup]));
+    assert_eq!(r["moved"]["song"], 1);
+    assert_eq!(r["soft_deleted_count"], 1);
+    let g = commands::cmd_get(&mut c, "song", &s2, "artist_id", false).unwrap();
+    assert_eq!(g["results"][0]["artist_id"], keep);
+    let a = commands::cmd_get(&mut c, "artist", &dup, "status", true).unwrap();
+    assert_eq!(a["results"][0]["status"], 1);
+}
+
+#[test]
+fn test_merge_artist_repoints_song_credits() {
+    let mut c = test_conn();
+    let keep = insert_artist(&mut c, "ryo (supercell)");
+    let dup = insert_artist(&mut c, "ryo");
+    let singer = insert_artist(&mut c, "Tia");
+    let s1 = insert_song(&mut c, "Shared", &singer);
+    let s2 = insert_song(&mut c, "OnlyOnDup", &singer);
+    insert_credit(&mut c, &s1, &keep, "composer");
+    let dropped = insert_credit(&mut c, &s1, &dup, "composer");
+    insert_credit(&mut c, &s1, &dup, "arranger");
+    insert_credit(&mut c, &s2, &dup, "composer");
+
+    let r = commands::cmd_merge(&mut c, "artist", &keep, &dup).unwrap();
+    assert_eq!(r["moved"]["song"], 0);
+    assert_eq!(r["moved"]["song_credit"], 2);
+    assert_eq!(
+        r["song_credit_collisions"],
+        serde_json::json!([{"id": dropped, "song_id": s1, "artist_id": dup, "role": "composer"}])
+    );
+    assert_eq!(
+        count_rows(
+            &c,
+            &format!("SELECT COUNT(*) FROM song_credit WHERE artist_id = '{keep}'")
+        ),
+        3
+    );
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM song_credit"), 3);
+}
+
+#[test]
+fn test_merge_artist_repoints_memberships() {
+    let mut c = test_conn();
+    let keep = insert_artist(&mut c, "Saint Snow");
+    let dup = insert_artist(&mut c, "SaintSnow");
+    let sarah = insert_artist(&mut c, "Asami Tano");
+    let leah = insert_artist(&mut c, "Hinata Sato");
+    let unit = insert_artist(&mut c, "Aqours x Saint Snow");
+    commands::cmd_add_member(&mut c, &keep, &sarah).unwrap();
+    commands::cmd_add_member(&mut c, &dup, &format!("{sarah},{leah}")).unwrap();
+    commands::cmd_add_member(&mut c, &unit, &dup).unwrap();
+    // The kept artist listed as a member of its duplicate
+    commands::cmd_add_member(&mut c, &dup, &keep).unwrap();
+
+    let r = commands::cmd_merge(&mut c, "artist", &keep, &dup).unwrap();
+    assert_eq!(r["moved"]["artist_member"], 2);
+    let dropped: Vec<(&str, &str)> = r["artist_member_collisions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| {
+            (
+                m["group_id"].as_str().unwrap(),
+                m["member_id"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(dropped.len(), 2);
+    assert!(dropped.contains(&(dup.as_str(), sarah.as_str())));
+    assert!(dropped.contains(&(dup.as_str(), keep.as_str())));
+
+    let members = |c: &Connection, group: &str| {
+        count_rows(
+            c,
+            &format!("SELECT COUNT(*) FROM artist_member WHERE group_id = '{group}'"),
+        )
+    };
+    assert_eq!(members(&c, &keep), 2);
+    assert_eq!(members(&c, &unit), 1);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist_member"), 3);
+}
+
+#[test]
+fn test_merge_show_resolves_rel_show_song_collisions() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let s1 = insert_song(&mut c, "Shared", &aid);
+    let s2 = insert_song(&mut c, "OnlyOnDup", &aid);
+    let keep = insert_show(&mut c, "K-On!", "Spring 2009");
+    let dup = insert_show(&mut c, "K-ON!", "Spring 2009");
+    insert_rel(&mut c, &keep, &s1, "");
+    insert_rel(&mut c, &dup, &s1, "https://ex.com/a.webm");
+    insert_rel(&mut c, &dup, &s2, "");
+    insert_play_history(&mut c, &dup, &s1);
+    insert_play_history(&mut c, &dup, &s2);
+
+    let r = commands::cmd_merge(&mut c, "show", &keep, &dup).unwrap();
+    assert_eq!(r["moved"]["play_history"], 2);
+    assert_eq!(r["moved"]["rel_show_song"], 1);
+    assert_eq!(
+        r["rel_show_song_collisions"],
+        serde_json::json!([{"show_id": dup, "song_id": s1}])
+    );
+    assert_eq!(
+        count_rows(
+            &c,
+            &format!("SELECT COUNT(*) FROM rel_show_song WHERE show_id='{keep}'")
+        ),
+        2
+    );
+    assert_eq!(
+        count_rows(
+            &c,
+            &format!("SELECT COUNT(*) FROM rel_show_song WHERE show_id='{dup}'")
+        ),
+        0
+    );
+    let url: String = c
+        .query_row(
+            "SELECT media_url FROM rel_show_song WHERE show_id=?1 AND song_id=?2",
+            rusqlite::params![keep, s1],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(url, "https://ex.com/a.webm");
+}
+
+#[test]
+fn test_merge_song_keeps_most_advanced_learning() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let keep = insert_song(&mut c, "Song", &aid);
+    let dup = insert_song(&mut c, "song", &aid);
+    let shid = insert_show(&mut c, "Show", "2024");
+    insert_rel(&mut c, &shid, &dup, "");
+    insert_play_history(&mut c, &shid, &dup);
+    let low = insert_learning(&mut c, &keep, 2, 0, 0);
+    let high = insert_learning(&mut c, &dup, 9, 0, 0);
+
+    let r = commands::cmd_merge(&mut c, "song", &keep, &dup).unwrap();
+    assert_eq!(r["moved"]["play_history"], 1);
+    assert_eq!(r["moved"]["rel_show_song"], 1);
+    assert_eq!(r["learning"]["kept_id"], high);
+    assert_eq!(r["learning"]["deleted_ids"], serde_json::json!([low]));
+    let l = commands::cmd_get(&mut c, "learning", &high, "song_id,level", false).unwrap();
+    assert_eq!(l["results"][0]["song_id"], keep);
+    assert_eq!(l["results"][0]["level"], 9);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM learning"), 1);
+}
+
+#[test]
+fn test_merge_multiple_removed_songs_sharing_a_show() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let keep = insert_song(&mut c, "Song", &aid);
+    let d1 = insert_song(&mut c, "Song ", &aid);
+    let d2 = insert_song(&mut c, "SONG", &aid);
+    let shid = insert_show(&mut c, "Show", "2024");
+    insert_rel(&mut c, &shid, &d1, "");
+    insert_rel(&mut c, &shid, &d2, "");
+
+    let r = commands::cmd_merge(&mut c, "song", &keep, &format!("{d1},{d2}")).unwrap();
+    assert_eq!(r["moved"]["rel_show_song"], 1);
+    assert_eq!(r["rel_show_song_collisions"].as_array().unwrap().len(), 1);
+    assert_eq!(r["learning"]["kept_id"], serde_json::Value::Null);
+    assert_eq!(r["soft_deleted_count"], 2);
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM rel_show_song"), 1);
+}
+
+#[test]
+fn test_merge_errors() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "A");
+    assert!(
+        commands::cmd_merge(&mut c, "learning", &a1, "x")
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid table")
+    );
+    assert_eq!(
+        commands::cmd_merge(&mut c, "artist", &a1, " , ")
+            .unwrap_err()
+            .to_string(),
+        "remove ids cannot be empty"
+    );
+    assert_eq!(
+        commands::cmd_merge(&mut c, "artist", &a1, &a1)
+            .unwrap_err()
+            .to_string(),
+        format!("keep id cannot also be removed: {a1}")
+    );
+    assert_eq!(
+        commands::cmd_merge(&mut c, "artist", &a1, "ghost")
+            .unwrap_err()
+            .to_string(),
+        "Record(s) not found: artist/ghost"
+    );
+}
+
+// === SOFT-DELETE / RESTORE ===
+
+#[test]
+fn test_soft_delete_and_restore_artist() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    c.execute("UPDATE artist SET updated_at = 0 WHERE id = ?1", [&aid])
+        .unwrap();
+    let r = commands::cmd_soft_delete(&mut c, "artist", &aid).unwrap();
+    assert_eq!(r["soft_deleted"], true);
+    let g = commands::cmd_get(&mut c, "artist", &aid, "status,updated_at", true).unwrap();
+    assert_eq!(g["results"][0]["status"], 1);
+    assert!(g["results"][0]["updated_at"].as_i64().unwrap() > 0);
+
+    let r = commands::cmd_restore(&mut c, "artist", &aid).unwrap();
+    assert_eq!(r["restored"], true);
+    let g = commands::cmd_get(&mut c, "artist", &aid, "status", false).unwrap();
+    assert_eq!(g["results"][0]["status"], 0);
+}
+
+#[test]
+fn test_soft_delete_play_history() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Show", "Spring 2020");
+    let pid = insert_play_history(&mut c, &shid, &sid);
+    commands::cmd_soft_delete(&mut c, "play_history", &pid).unwrap();
+    assert_eq!(
+        count_rows(&c, "SELECT COUNT(*) FROM play_history WHERE status = 1"),
+        1
+    );
+}
+
+#[test]
+fn test_soft_delete_errors() {
+    let mut c = test_conn();
+    assert_eq!(
+        commands::cmd_soft_delete(&mut c, "learning", "x")
+            .unwrap_err()
+            .to_string(),
+        "Invalid table: learning. Allowed: artist, show, song, play_history"
+    );
+    assert_eq!(
+        commands::cmd_restore(&mut c, "artist", "ghost")
+            .unwrap_err()
+            .to_string(),
+        "Record not found: artist/ghost"
+    );
+}
+
+// === UPDATE rel_show_song ===
+
+#[test]
+fn test_update_rel_show_song_media_url() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &sid, "https://old");
+    let key = format!("{shid}:{sid}");
+    let r = commands::cmd_update(
+        &mut c,
+        "rel_show_song",
+        &key,
+        r#"{"media_url":"https://new"}"#,
+    )
+    .unwrap();
+    assert_eq!(r, serde_json::json!({"updated": true, "id": key}));
+    assert_eq!(
+        count_rows(
+            &c,
+            "SELECT COUNT(*) FROM rel_show_song WHERE media_url = 'https://new'"
+        ),
+        1
+    );
+}
+
+#[test]
+fn test_update_rel_show_song_rekeys_link() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let wrong = insert_song(&mut c, "Wrong", &aid);
+    let right = insert_song(&mut c, "Right", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &wrong, "https://x");
+    let r = commands::cmd_update(
+        &mut c,
+        "rel_show_song",
+        &format!("{shid}:{wrong}"),
+        &format!(r#"{{"song_id":"{right}"}}"#),
+    )
+    .unwrap();
+    assert_eq!(r["id"], format!("{shid}:{right}"));
+    let song_id: String = c
+        .query_row("SELECT song_id FROM rel_show_song", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(song_id, right);
+}
+
+#[test]
+fn test_update_rel_show_song_errors() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let s1 = insert_song(&mut c, "S1", &aid);
+    let s2 = insert_song(&mut c, "S2", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    insert_rel(&mut c, &shid, &s1, "");
+    insert_rel(&mut c, &shid, &s2, "");
+    let key = format!("{shid}:{s1}");
+
+    assert_eq!(
+        commands::cmd_update(
+            &mut c,
+            "rel_show_song",
+            &key,
+            &format!(r#"{{"song_id":"{s2}"}}"#)
+        )
+        .unwrap_err()
+        .to_string(),
+        format!("rel_show_song link already exists: {shid}:{s2}")
+    );
+    assert_eq!(
+        commands::cmd_update(&mut c, "rel_show_song", &key, r#"{"song_id":"ghost"}"#)
+            .unwrap_err()
+            .to_string(),
+        "song_id not found: song/ghost"
+    );
+    assert_eq!(
+        commands::cmd_update(
+            &mut c,
+            "rel_show_song",
+            &format!("{shid}:ghost"),
+            r#"{"media_url":"x"}"#
+        )
+        .unwrap_err()
+        .to_string(),
+        format!("Record not found: rel_show_song/{shid}:ghost")
+    );
+}
+
+#[test]
+fn test_create_and_update_rel_show_song_song_type() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let op = insert_song(&mut c, "Opening", &aid);
+    let ins = insert_song(&mut c, "Insert", &aid);
+    let shid = insert_show(&mut c, "Sh", "2024");
+    let type_of = |c: &Connection, song_id: &str| -> (Option<String>, Option<i64>) {
+        c.query_row(
+            "SELECT song_type, type_number FROM rel_show_song WHERE song_id = ?1",
+            [song_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+    };
+
+    // AMQ's numeric type is stored as its code
+    commands::cmd_create(
+        &mut c,
+        "rel_show_song",
+        &format!(r#"{{"show_id":"{shid}","song_id":"{op}","song_type":1,"type_number":2}}"#),
+    )
+    .unwrap();
+    assert_eq!(type_of(&c, &op), (Some("OP".into()), Some(2)));
+    commands::cmd_create(
+        &mut c,
+        "rel_show_song",
+        &format!(r#"{{"show_id":"{shid}","song_id":"{ins}","song_type":"Insert"}}"#),
+    )
+    .unwrap();
+    assert_eq!(type_of(&c, &ins), (Some("IN".into()), None));
+
+    let key = format!("{shid}:{op}");
+    commands::cmd_update(
+        &mut c,
+        "rel_show_song",
+        &key,
+        r#"{"song_type":"ed","type_number":1}"#,
+    )
+    .unwrap();
+    assert_eq!(type_of(&c, &op), (Some("ED".into()), Some(1)));
+    commands::cmd_update(&mut c, "rel_show_song", &key, r#"{"song_type":""}"#).unwrap();
+    assert_eq!(type_of(&c, &op), (None, Some(1)));
+
+    assert_eq!(
+        commands::cmd_update(&mut c, "rel_show_song", &key, r#"{"song_type":"OST"}"#)
+            .unwrap_err()
+            .to_string(),
+        "Invalid song_type: OST. Expected OP, ED, IN (or AMQ type 1, 2, 3)"
+    );
+    assert_eq!(
+        commands::cmd_update(&mut c, "rel_show_song", &key, r#"{"type_number":"2"}"#)
+            .unwrap_err()
+            .to_string(),
+        "type_number must be a non-negative integer, got: \"2\""
+    );
+}
+
 // === UPDATE ADDITIONAL TABLE BRANCHES ===
 
 #[test]
@@ -459,7 +1376,7 @@ fn test_update_show_name() {
     let id = insert_show(&mut c, "Old Show", "2024");
     let r = commands::cmd_update(&mut c, "show", &id, r#"{"name":"New Show"}"#).unwrap();
     assert_eq!(r["updated"], true);
-    let g = commands::cmd_get(&mut c, "show", &id, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "show", &id, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "New Show");
 }
 
@@ -470,7 +1387,7 @@ fn test_update_song_name() {
     let sid = insert_song(&mut c, "Old Song", &aid);
     let r = commands::cmd_update(&mut c, "song", &sid, r#"{"name":"New Song"}"#).unwrap();
     assert_eq!(r["updated"], true);
-    let g = commands::cmd_get(&mut c, "song", &sid, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "song", &sid, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "New Song");
 }
 
@@ -495,7 +1412,7 @@ fn test_update_play_history_media_url() {
     )
     .unwrap();
     assert_eq!(r2["updated"], true);
-    let g = commands::cmd_get(&mut c, "play_history", ph_id, "media_url").unwrap();
+    let g = commands::cmd_get(&mut c, "play_history", ph_id, "media_url", false).unwrap();
     assert_eq!(g["results"][0]["media_url"], "https://new.com");
 }
 
@@ -514,7 +1431,7 @@ fn test_create_with_integer_value() {
     )
     .unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "learning", id, "level_up_path").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", id, "level_up_path", false).unwrap();
     assert_eq!(g["results"][0]["level_up_path"], "[1,2,3]");
 }
 
@@ -580,7 +1497,7 @@ fn test_create_artist_url_encoded_single_quote() {
     // Name with single quote: "Ado's Music" encoded as "Ado%27s%20Music"
     let r = commands::cmd_create(&mut c, "artist", r#"{"name":"Ado%27s%20Music"}"#).unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "artist", id, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "artist", id, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "Ado's Music");
 }
 
@@ -590,7 +1507,7 @@ fn test_create_artist_url_encoded_double_quote() {
     // Name with double quote: The "Best" encoded as The%20%22Best%22
     let r = commands::cmd_create(&mut c, "artist", r#"{"name":"The%20%22Best%22"}"#).unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "artist", id, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "artist", id, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "The \"Best\"");
 }
 
@@ -606,7 +1523,7 @@ fn test_create_song_url_encoded_parentheses() {
     )
     .unwrap();
     let id = r["id"].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "song", id, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "song", id, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "Fuwa Fuwa Time (5-nin Ver.)");
 }
 
@@ -617,7 +1534,7 @@ fn test_update_artist_url_encoded_single_quote() {
     // Update with single quote: "it%27s%20new"
     let r = commands::cmd_update(&mut c, "artist", &id, r#"{"name":"it%27s%20new"}"#).unwrap();
     assert_eq!(r["updated"], true);
-    let g = commands::cmd_get(&mut c, "artist", &id, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "artist", &id, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "it's new");
 }
 
@@ -630,7 +1547,7 @@ fn test_update_song_url_encoded_special_chars() {
     let r =
         commands::cmd_update(&mut c, "song", &sid, r#"{"name":"Rock%20%26%20Roll%21"}"#).unwrap();
     assert_eq!(r["updated"], true);
-    let g = commands::cmd_get(&mut c, "song", &sid, "name").unwrap();
+    let g = commands::cmd_get(&mut c, "song", &sid, "name", false).unwrap();
     assert_eq!(g["results"][0]["name"], "Rock & Roll!");
 }
 
@@ -642,7 +1559,7 @@ fn test_create_data_integer_not_decoded() {
     let sid = insert_song(&mut c, "S", &aid);
     let lid = insert_learning(&mut c, &sid, 0, 0, 0);
     commands::cmd_update(&mut c, "learning", &lid, r#"{"level":5}"#).unwrap();
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level", false).unwrap();
     assert_eq!(g["results"][0]["level"], 5);
 }
 
@@ -678,9 +1595,210 @@ fn test_create_and_search_roundtrip_with_quotes() {
         "artist",
         r#"{"name":{"value":"Can%27t%20Stop"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(s["results"].as_array().unwrap().len(), 1);
     assert_eq!(s["results"][0]["id"], id);
     assert_eq!(s["results"][0]["name"], "Can't Stop");
 }
+
+// === GROUP MEMBERS ===
+
+#[test]
+fn test_add_and_remove_member() {
+    let mut c = test_conn();
+    let group = insert_artist(&mut c, "Saint Spica Jogakuin Seika-tai");
+    let m1 = insert_artist(&mut c, "Hitomi Sasaki");
+    let m2 = insert_artist(&mut c, "Sora Amamiya");
+
+    let r = commands::cmd_add_member(&mut c, &group, &format!("{m1}, {m2},{m1}")).unwrap();
+    assert_eq!(
+        r,
+        serde_json::json!({"group_id": group, "added": [m1, m2], "already_members": []})
+    );
+    let r = commands::cmd_add_member(&mut c, &group, &m2).unwrap();
+    assert_eq!(r["added"], serde_json::json!([]));
+    assert_eq!(r["already_members"], serde_json::json!([m2]));
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist_member"), 2);
+
+    let r = commands::cmd_remove_member(&mut c, &group, &format!("{m1},nobody")).unwrap();
+    assert_eq!(
+        r,
+        serde_json::json!({"group_id": group, "removed": [m1], "not_members": ["nobody"]})
+    );
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist_member"), 1);
+}
+
+#[test]
+fn test_add_member_errors() {
+    let mut c = test_conn();
+    let group = insert_artist(&mut c, "G");
+    let m = insert_artist(&mut c, "M");
+    assert_eq!(
+        commands::cmd_add_member(&mut c, &group, " , ")
+            .unwrap_err()
+            .to_string(),
+        "member_ids cannot be empty"
+    );
+    assert_eq!(
+        commands::cmd_add_member(&mut c, &group, &format!("{m},{group}"))
+            .unwrap_err()
+            .to_string(),
+        format!("artist cannot be a member of itself: {group}")
+    );
+    assert_eq!(
+        commands::cmd_add_member(&mut c, "ghost", &format!("{m},ghost2"))
+            .unwrap_err()
+            .to_string(),
+        "Record(s) not found: artist/ghost, ghost2"
+    );
+    assert_eq!(
+        commands::cmd_remove_member(&mut c, "ghost", &m)
+            .unwrap_err()
+            .to_string(),
+        "Record not found: artist/ghost"
+    );
+    assert_eq!(count_rows(&c, "SELECT COUNT(*) FROM artist_member"), 0);
+}
+
+// === ALIASES ===
+
+fn create_alias(conn: &mut Connection, table: &str, data: &str) -> String {
+    let r = commands::cmd_create(conn, table, data).unwrap();
+    r["id"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_create_update_artist_alias() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Yui Horie");
+    let alias = create_alias(
+        &mut c,
+        "artist_alias",
+        &format!(r#"{{"artist_id":"{aid}","name":"Horie Yui"}}"#),
+    );
+    let key: String = c
+        .query_row(
+            "SELECT name_key FROM artist_alias WHERE id = ?1",
+            [&alias],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(key, "horieyui");
+
+    commands::cmd_update(&mut c, "artist_alias", &alias, r#"{"name":"堀江由衣"}"#).unwrap();
+    let r = commands::cmd_get(&mut c, "artist_alias", &alias, "artist_id,name", false).unwrap();
+    assert_eq!(
+        r["results"][0],
+        serde_json::json!({"artist_id": aid, "name": "堀江由衣"})
+    );
+
+    assert_eq!(
+        commands::cmd_create(&mut c, "artist_alias", r#"{"name":"x"}"#)
+            .unwrap_err()
+            .to_string(),
+        "artist_id is required"
+    );
+    assert_eq!(
+        commands::cmd_update(&mut c, "artist_alias", &alias, r#"{"name":"  "}"#)
+            .unwrap_err()
+            .to_string(),
+        "alias name cannot be empty"
+    );
+    assert_eq!(
+        commands::cmd_create(
+            &mut c,
+            "show_alias",
+            r#"{"show_id":"ghost","name":"Strawberry Panic!"}"#
+        )
+        .unwrap_err()
+        .to_string(),
+        "show_id not found: show/ghost"
+    );
+}
+
+#[test]
+fn test_promote_alias_swaps_names() {
+    let mut c = test_conn();
+    let sid = insert_show(&mut c, "Strawberry Panic", "Spring 2006");
+    let alias = create_alias(
+        &mut c,
+        "show_alias",
+        &format!(r#"{{"show_id":"{sid}","name":"Strawberry Panic!"}}"#),
+    );
+
+    let r = commands::cmd_promote_alias(&mut c, "show_alias", &alias).unwrap();
+    assert_eq!(
+        r,
+        serde_json::json!({
+            "promoted": true,
+            "table": "show",
+            "id": sid,
+            "name": "Strawberry Panic!",
+            "previous_name": "Strawberry Panic",
+            "alias": {"id": alias, "name": "Strawberry Panic"}
+        })
+    );
+    let (name, key): (String, String) = c
+        .query_row(
+            "SELECT name, name_key FROM show WHERE id = ?1",
+            [&sid],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        (name.as_str(), key.as_str()),
+        ("Strawberry Panic!", "strawberrypanic")
+    );
+
+    assert_eq!(
+        commands::cmd_promote_alias(&mut c, "show", &alias)
+            .unwrap_err()
+            .to_string(),
+        "Invalid table: show. Allowed: artist_alias, show_alias"
+    );
+    assert_eq!(
+        commands::cmd_promote_alias(&mut c, "artist_alias", &alias)
+            .unwrap_err()
+            .to_string(),
+        format!("Record not found: artist_alias/{alias}")
+    );
+}
+
+#[test]
+fn test_merge_artist_keeps_removed_name_as_alias() {
+    let mut c = test_conn();
+    let keep = insert_artist(&mut c, "Yui Horie");
+    let dup = insert_artist(&mut c, "Horie Yui");
+    let same = insert_artist(&mut c, "Yui Horie");
+    create_alias(
+        &mut c,
+        "artist_alias",
+        &format!(r#"{{"artist_id":"{dup}","name":"堀江由衣"}}"#),
+    );
+    create_alias(
+        &mut c,
+        "artist_alias",
+        &format!(r#"{{"artist_id":"{dup}","name":"Yui Horie"}}"#),
+    );
+
+    let r = commands::cmd_merge(&mut c, "artist", &keep, &format!("{dup},{same}")).unwrap();
+    assert_eq!(r["moved"]["artist_alias"], 1);
+    assert_eq!(r["aliases_added"], serde_json::json!(["Horie Yui"]));
+    let names = commands::cmd_search(
+        &mut c,
+        "artist_alias",
+        &format!(r#"{{"artist_id":{{"value":"{keep}"}}}}"#),
+        "name",
+        &SearchOptions {
+            order_by: Some("name".into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        names["results"],
+        serde_json::json!([{"name": "Horie Yui"}, {"name": "堀江由衣"}])
+    );
+}
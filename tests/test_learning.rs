@@ -1,4 +1,5 @@
 use jankenoboe::commands;
+use jankenoboe::models::SearchOptions;
 use rusqlite::Connection;
 
 fn test_conn() -> Connection {
@@ -58,7 +59,7 @@ fn test_learning_due_level0_newly_created() {
     // Level 0, last_level_up_at=0, updated_at in the past (>300s ago)
     let past = jankenoboe::models::now_unix() - 400;
     let lid = insert_learning_raw(&mut c, &sid, 0, past, past, 0, 0);
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 1);
     assert_eq!(r["results"][0]["id"], lid);
     assert_eq!(r["results"][0]["song_name"], "S");
@@ -74,7 +75,7 @@ fn test_learning_due_level0_with_last_level_up_at() {
     // Level 0, last_level_up_at set to past (>300s ago)
     let past = jankenoboe::models::now_unix() - 400;
     insert_learning_raw(&mut c, &sid, 0, past, past, past, 0);
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 1);
 }
 
@@ -86,7 +87,7 @@ fn test_learning_due_level0_not_yet_due() {
     // Level 0, updated_at = now (< 300s ago)
     let now = jankenoboe::models::now_unix();
     insert_learning_raw(&mut c, &sid, 0, now, now, 0, 0);
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 0);
 }
 
@@ -98,7 +99,7 @@ fn test_learning_due_higher_level() {
     // Level 1 with wait_days=1. last_level_up_at far in the past (>1 day ago)
     let past = jankenoboe::models::now_unix() - 90000; // >1 day
     insert_learning_raw(&mut c, &sid, 1, past, past, past, 0);
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 1);
     assert_eq!(r["results"][0]["wait_days"], 1);
 }
@@ -111,7 +112,7 @@ fn test_learning_due_higher_level_not_yet() {
     // Level 7 with wait_days=2. last_level_up_at = now (not 2 days ago yet)
     let now = jankenoboe::models::now_unix();
     insert_learning_raw(&mut c, &sid, 7, now, now, now, 0);
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 0);
 }
 
@@ -122,10 +123,25 @@ fn test_learning_due_graduated_excluded() {
     let sid = insert_song(&mut c, "S", &aid);
     let past = jankenoboe::models::now_unix() - 400;
     insert_learning_raw(&mut c, &sid, 0, past, past, 0, 1); // graduated
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 0);
 }
 
+#[test]
+fn test_learning_due_soft_deleted_song_excluded() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let past = jankenoboe::models::now_unix() - 400;
+    insert_learning_raw(&mut c, &sid, 0, past, past, 0, 0);
+    commands::cmd_soft_delete(&mut c, "song", &sid).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
+    assert_eq!(r["count"], 0);
+    commands::cmd_restore(&mut c, "song", &sid).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
+    assert_eq!(r["count"], 1);
+}
+
 #[test]
 fn test_learning_due_ordered_by_level_desc() {
     let mut c = test_conn();
@@ -136,7 +152,7 @@ fn test_learning_due_ordered_by_level_desc() {
     let past = jankenoboe::models::now_unix() - 1200000;
     insert_learning_raw(&mut c, &s1, 3, past, past, past, 0);
     insert_learning_raw(&mut c, &s2, 10, past, past, past, 0);
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 2);
     // Higher level first
     assert_eq!(r["results"][0]["level"], 10);
@@ -156,7 +172,7 @@ fn test_learning_due_limit() {
     insert_learning_raw(&mut c, &s1, 0, past, past, 0, 0);
     insert_learning_raw(&mut c, &s2, 0, past, past, 0, 0);
     insert_learning_raw(&mut c, &s3, 0, past, past, 0, 0);
-    let r = commands::cmd_learning_due(&mut c, 2, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 2, 0, None).unwrap();
     assert_eq!(r["count"], 2);
 }
 
@@ -171,10 +187,10 @@ fn test_learning_due_offset_makes_not_yet_due_visible() {
     let now = jankenoboe::models::now_unix();
     insert_learning_raw(&mut c, &sid, 0, now, now, 0, 0);
     // Without offset: not due
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 0);
     // With 400s offset: now due (300s warm-up satisfied)
-    let r = commands::cmd_learning_due(&mut c, 100, 400).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 400, None).unwrap();
     assert_eq!(r["count"], 1);
 }
 
@@ -187,10 +203,10 @@ fn test_learning_due_offset_higher_level() {
     let now = jankenoboe::models::now_unix();
     insert_learning_raw(&mut c, &sid, 1, now, now, now, 0);
     // Without offset: not due
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 0);
     // With offset of 2 days (172800s): due
-    let r = commands::cmd_learning_due(&mut c, 100, 172800).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 172800, None).unwrap();
     assert_eq!(r["count"], 1);
 }
 
@@ -202,10 +218,47 @@ fn test_learning_due_offset_zero_same_as_default() {
     let past = jankenoboe::models::now_unix() - 400;
     insert_learning_raw(&mut c, &sid, 0, past, past, 0, 0);
     // offset=0 should behave identically to default
-    let r = commands::cmd_learning_due(&mut c, 100, 0).unwrap();
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
     assert_eq!(r["count"], 1);
 }
 
+#[test]
+fn test_learning_due_filters_by_song_type() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let op = insert_song(&mut c, "Op", &aid);
+    let ed = insert_song(&mut c, "Ed", &aid);
+    let untyped = insert_song(&mut c, "Untyped", &aid);
+    let past = jankenoboe::models::now_unix() - 400;
+    for sid in [&op, &ed, &untyped] {
+        insert_learning_raw(&mut c, sid, 0, past, past, 0, 0);
+    }
+    let show_id = uuid::Uuid::new_v4().to_string();
+    c.execute("INSERT INTO show (id, name) VALUES (?1, 'Sh')", [&show_id])
+        .unwrap();
+    for (sid, song_type) in [(&op, Some("OP")), (&ed, Some("ED")), (&untyped, None)] {
+        c.execute(
+            "INSERT INTO rel_show_song (show_id, song_id, song_type, type_number) VALUES (?1, ?2, ?3, 1)",
+            rusqlite::params![show_id, sid, song_type],
+        )
+        .unwrap();
+    }
+
+    let r = commands::cmd_learning_due(&mut c, 100, 0, None).unwrap();
+    assert_eq!(r["count"], 3);
+    let r = commands::cmd_learning_due(&mut c, 100, 0, Some("opening")).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["results"][0]["song_id"], op.as_str());
+    let r = commands::cmd_learning_due(&mut c, 100, 0, Some("ED")).unwrap();
+    assert_eq!(r["results"][0]["song_id"], ed.as_str());
+    assert_eq!(
+        commands::cmd_learning_due(&mut c, 100, 0, Some("OST"))
+            .unwrap_err()
+            .to_string(),
+        "Invalid song_type: OST. Expected OP, ED, IN (or AMQ type 1, 2, 3)"
+    );
+}
+
 // === LEARNING-BATCH ===
 
 #[test]
@@ -219,7 +272,14 @@ fn test_learning_batch_new_song() {
     assert_eq!(r["already_graduated_song_ids"].as_array().unwrap().len(), 0);
     // Verify record created
     let lid = r["created_ids"][0].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "learning", lid, "level,graduated,last_level_up_at").unwrap();
+    let g = commands::cmd_get(
+        &mut c,
+        "learning",
+        lid,
+        "level,graduated,last_level_up_at",
+        false,
+    )
+    .unwrap();
     assert_eq!(g["results"][0]["level"], 0);
     assert_eq!(g["results"][0]["graduated"], 0);
     assert_eq!(g["results"][0]["last_level_up_at"], 0);
@@ -273,7 +333,14 @@ fn test_learning_batch_relearn_graduated() {
     assert_eq!(r["already_graduated_song_ids"].as_array().unwrap().len(), 0);
     // Verify new record starts at level 7
     let lid = r["created_ids"][0].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "learning", lid, "level,graduated,last_level_up_at").unwrap();
+    let g = commands::cmd_get(
+        &mut c,
+        "learning",
+        lid,
+        "level,graduated,last_level_up_at",
+        false,
+    )
+    .unwrap();
     assert_eq!(g["results"][0]["level"], 7);
     assert_eq!(g["results"][0]["graduated"], 0);
     assert!(g["results"][0]["last_level_up_at"].as_i64().unwrap() > 0);
@@ -288,7 +355,7 @@ fn test_learning_batch_relearn_custom_start_level() {
     insert_learning_raw(&mut c, &sid, 19, now, now, now, 1);
     let r = commands::cmd_learning_batch(&mut c, &sid, Some(&sid), 5).unwrap();
     let lid = r["created_ids"][0].as_str().unwrap();
-    let g = commands::cmd_get(&mut c, "learning", lid, "level").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", lid, "level", false).unwrap();
     assert_eq!(g["results"][0]["level"], 5);
 }
 
@@ -355,6 +422,11 @@ fn test_learning_song_review_generates_html() {
         rusqlite::params![ph_id, show_id, sid, "https://example.com/media1", now],
     )
     .unwrap();
+    c.execute(
+        "INSERT INTO rel_show_song (show_id, song_id, song_type, type_number) VALUES (?1, ?2, 'OP', 2)",
+        rusqlite::params![show_id, sid],
+    )
+    .unwrap();
 
     let output_path = std::env::temp_dir().join("test_review.html");
     let output_str = output_path.to_string_lossy().to_string();
@@ -369,6 +441,7 @@ fn test_learning_song_review_generates_html() {
     assert!(html.contains("TestArtist"));
     assert!(html.contains("TestShow"));
     assert!(html.contains("https://example.com/media1"));
+    assert!(html.contains("\"songType\":\"OP2\""));
     assert!(html.contains("Total due: 1 songs"));
 
     std::fs::remove_file(&output_path).ok();
@@ -446,7 +519,7 @@ fn test_learning_song_levelup_ids_basic() {
     assert_eq!(r["total_processed"], 1);
 
     // Verify level was incremented
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level,last_level_up_at").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level,last_level_up_at", false).unwrap();
     assert_eq!(g["results"][0]["level"], 1);
     assert!(g["results"][0]["last_level_up_at"].as_i64().unwrap() > 0);
 }
@@ -467,10 +540,10 @@ fn test_learning_song_levelup_ids_multiple() {
     assert_eq!(r["graduated_count"], 0);
     assert_eq!(r["total_processed"], 2);
 
-    let g1 = commands::cmd_get(&mut c, "learning", &lid1, "level").unwrap();
+    let g1 = commands::cmd_get(&mut c, "learning", &lid1, "level", false).unwrap();
     assert_eq!(g1["results"][0]["level"], 4);
 
-    let g2 = commands::cmd_get(&mut c, "learning", &lid2, "level").unwrap();
+    let g2 = commands::cmd_get(&mut c, "learning", &lid2, "level", false).unwrap();
     assert_eq!(g2["results"][0]["level"], 6);
 }
 
@@ -487,7 +560,7 @@ fn test_learning_song_levelup_ids_graduates_max_level() {
     assert_eq!(r["graduated_count"], 1);
     assert_eq!(r["total_processed"], 1);
 
-    let g = commands::cmd_get(&mut c, "learning", &lid, "graduated").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "graduated", false).unwrap();
     assert_eq!(g["results"][0]["graduated"], 1);
 }
 
@@ -507,10 +580,10 @@ fn test_learning_song_levelup_ids_mixed_levelup_and_graduate() {
     assert_eq!(r["graduated_count"], 1);
     assert_eq!(r["total_processed"], 2);
 
-    let g1 = commands::cmd_get(&mut c, "learning", &lid1, "level").unwrap();
+    let g1 = commands::cmd_get(&mut c, "learning", &lid1, "level", false).unwrap();
     assert_eq!(g1["results"][0]["level"], 1);
 
-    let g2 = commands::cmd_get(&mut c, "learning", &lid2, "graduated").unwrap();
+    let g2 = commands::cmd_get(&mut c, "learning", &lid2, "graduated", false).unwrap();
     assert_eq!(g2["results"][0]["graduated"], 1);
 }
 
@@ -557,7 +630,7 @@ fn test_learning_song_levelup_ids_does_not_require_due() {
     assert_eq!(r["leveled_up_count"], 1);
     assert_eq!(r["total_processed"], 1);
 
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level", false).unwrap();
     assert_eq!(g["results"][0]["level"], 6);
 }
 
@@ -838,6 +911,112 @@ fn test_learning_song_stats_with_play_history() {
     assert_eq!(r["results"][0]["play_count"], 3);
 }
 
+// === LEARNING-SUGGEST ===
+
+fn insert_play(conn: &mut Connection, show_id: &str, song_id: &str, correct: Option<bool>) {
+    conn.execute(
+        "INSERT INTO play_history (id, show_id, song_id, created_at, correct_guess) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            show_id,
+            song_id,
+            jankenoboe::models::now_unix(),
+            correct
+        ],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_learning_suggest_ranks_candidates() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let missed = insert_song(&mut c, "Missed", &aid);
+    let frequent = insert_song(&mut c, "Frequent", &aid);
+    let learning = insert_song(&mut c, "Learning", &aid);
+    let old_grad = insert_song(&mut c, "OldGrad", &aid);
+    let new_grad = insert_song(&mut c, "NewGrad", &aid);
+    insert_song(&mut c, "NeverPlayed", &aid);
+    let show = "show-1";
+    c.execute(
+        "INSERT INTO show (id, name, created_at, updated_at) VALUES (?1, 'Sh', 0, 0)",
+        [show],
+    )
+    .unwrap();
+    c.execute(
+        "INSERT INTO rel_show_song (show_id, song_id, created_at) VALUES (?1, ?2, 0)",
+        [show, &frequent],
+    )
+    .unwrap();
+
+    // Missed: 2 plays, both recent misses -> 2 + 3 * 2 = 8
+    insert_play(&mut c, show, &missed, Some(false));
+    insert_play(&mut c, show, &missed, Some(false));
+    // Frequent: 4 correct plays -> 4 + ln(1 + 8 show plays)
+    for _ in 0..4 {
+        insert_play(&mut c, show, &frequent, Some(true));
+    }
+    insert_play(&mut c, show, &learning, None);
+    insert_play(&mut c, show, &new_grad, None);
+    let now = jankenoboe::models::now_unix();
+    insert_learning_raw(&mut c, &learning, 0, now, now, 0, 0);
+    // Graduated 360 days ago -> 2; graduated 10 days ago -> not a candidate
+    insert_learning_raw(&mut c, &old_grad, 19, 0, 0, now - 360 * 86400, 1);
+    insert_learning_raw(&mut c, &new_grad, 19, 0, 0, now - 10 * 86400, 1);
+
+    let r = commands::cmd_learning_suggest(&mut c, 20).unwrap();
+    let names: Vec<&str> = r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["song_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Missed", "Frequent", "OldGrad"]);
+    assert_eq!(r["results"][0]["score"], 8.0);
+    assert_eq!(r["results"][0]["recent_misses"], 2);
+    assert_eq!(r["results"][1]["score"], 6.2);
+    assert_eq!(r["results"][1]["show_play_count"], 8);
+    assert_eq!(r["results"][2]["graduated_days_ago"], 360);
+    assert_eq!(r["results"][2]["score"], 2.0);
+    assert_eq!(r["song_ids"], format!("{missed},{frequent},{old_grad}"));
+    assert_eq!(r["relearn_song_ids"], old_grad.as_str());
+
+    let r = commands::cmd_learning_suggest(&mut c, 1).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["relearn_song_ids"], "");
+    assert_eq!(
+        commands::cmd_learning_suggest(&mut c, 0)
+            .unwrap_err()
+            .to_string(),
+        "limit must be at least 1"
+    );
+}
+
+#[test]
+fn test_learning_suggest_feeds_learning_batch() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let fresh = insert_song(&mut c, "Fresh", &aid);
+    let grad = insert_song(&mut c, "Grad", &aid);
+    insert_play(&mut c, "show-1", &fresh, None);
+    let now = jankenoboe::models::now_unix();
+    insert_learning_raw(&mut c, &grad, 19, 0, 0, now - 400 * 86400, 1);
+
+    let r = commands::cmd_learning_suggest(&mut c, 20).unwrap();
+    let batch = commands::cmd_learning_batch(
+        &mut c,
+        r["song_ids"].as_str().unwrap(),
+        r["relearn_song_ids"].as_str(),
+        7,
+    )
+    .unwrap();
+    assert_eq!(batch["created_ids"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        commands::cmd_learning_suggest(&mut c, 20).unwrap()["count"],
+        0
+    );
+}
+
 // === LEARNING-SONG-GRADUATE-IDS ===
 
 #[test]
@@ -852,8 +1031,14 @@ fn test_learning_song_graduate_ids_basic() {
     assert_eq!(r["graduated_count"], 1);
 
     // Verify level is set to max (19) and graduated is 1
-    let g =
-        commands::cmd_get(&mut c, "learning", &lid, "level,graduated,last_level_up_at").unwrap();
+    let g = commands::cmd_get(
+        &mut c,
+        "learning",
+        &lid,
+        "level,graduated,last_level_up_at",
+        false,
+    )
+    .unwrap();
     assert_eq!(g["results"][0]["level"], 19);
     assert_eq!(g["results"][0]["graduated"], 1);
     assert!(g["results"][0]["last_level_up_at"].as_i64().unwrap() > 0);
@@ -870,7 +1055,7 @@ fn test_learning_song_graduate_ids_from_level_zero() {
     let r = commands::cmd_learning_song_graduate_ids(&mut c, &lid).unwrap();
     assert_eq!(r["graduated_count"], 1);
 
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level,graduated").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level,graduated", false).unwrap();
     assert_eq!(g["results"][0]["level"], 19);
     assert_eq!(g["results"][0]["graduated"], 1);
 }
@@ -886,7 +1071,7 @@ fn test_learning_song_graduate_ids_from_max_level() {
     let r = commands::cmd_learning_song_graduate_ids(&mut c, &lid).unwrap();
     assert_eq!(r["graduated_count"], 1);
 
-    let g = commands::cmd_get(&mut c, "learning", &lid, "level,graduated").unwrap();
+    let g = commands::cmd_get(&mut c, "learning", &lid, "level,graduated", false).unwrap();
     assert_eq!(g["results"][0]["level"], 19);
     assert_eq!(g["results"][0]["graduated"], 1);
 }
@@ -905,11 +1090,11 @@ fn test_learning_song_graduate_ids_multiple() {
     let r = commands::cmd_learning_song_graduate_ids(&mut c, &ids).unwrap();
     assert_eq!(r["graduated_count"], 2);
 
-    let g1 = commands::cmd_get(&mut c, "learning", &lid1, "level,graduated").unwrap();
+    let g1 = commands::cmd_get(&mut c, "learning", &lid1, "level,graduated", false).unwrap();
     assert_eq!(g1["results"][0]["level"], 19);
     assert_eq!(g1["results"][0]["graduated"], 1);
 
-    let g2 = commands::cmd_get(&mut c, "learning", &lid2, "level,graduated").unwrap();
+    let g2 = commands::cmd_get(&mut c, "learning", &lid2, "level,graduated", false).unwrap();
     assert_eq!(g2["results"][0]["level"], 19);
     assert_eq!(g2["results"][0]["graduated"], 1);
 }
@@ -948,13 +1133,13 @@ fn test_learning_song_graduate_ids_empty() {
 #[test]
 fn test_sql_injection_table_name() {
     let mut c = test_conn();
-    assert!(commands::cmd_get(&mut c, "artist; DROP TABLE artist;--", "x", "id").is_err());
+    assert!(commands::cmd_get(&mut c, "artist; DROP TABLE artist;--", "x", "id", false).is_err());
 }
 
 #[test]
 fn test_sql_injection_field_name() {
     let mut c = test_conn();
-    assert!(commands::cmd_get(&mut c, "artist", "x", "id; DROP TABLE artist").is_err());
+    assert!(commands::cmd_get(&mut c, "artist", "x", "id; DROP TABLE artist", false).is_err());
 }
 
 #[test]
@@ -965,12 +1150,13 @@ fn test_sql_injection_search_term_key() {
         "artist",
         r#"{"name; DROP TABLE artist":{"value":"x"}}"#,
         "id",
+        &SearchOptions::default(),
     )
     .unwrap_err()
     .to_string();
     assert_eq!(
         err,
-        "Invalid term key for artist: name; DROP TABLE artist. Allowed: name, name_context"
+        "Invalid term key for artist: name; DROP TABLE artist. Allowed: name, name_context, created_at"
     );
 }
 
@@ -984,6 +1170,7 @@ fn test_sql_injection_in_search_value() {
         "artist",
         r#"{"name":{"value":"'; DROP TABLE artist; --"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     // Should return empty results, not crash
@@ -0,0 +1,401 @@
+use jankenoboe::commands;
+use jankenoboe::normalize::name_key;
+use rusqlite::Connection;
+
+fn test_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("open in-memory");
+    conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+    conn.execute_batch(include_str!("../docs/init-db.sql"))
+        .unwrap();
+    conn
+}
+
+fn insert_artist(conn: &mut Connection, name: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO artist (id, name, name_key, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![id, name, name_key(name), now, now],
+    )
+    .unwrap();
+    id
+}
+
+fn insert_song(conn: &mut Connection, name: &str, artist_id: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO song (id, name, name_key, artist_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![id, name, name_key(name), artist_id, now, now],
+    )
+    .unwrap();
+    id
+}
+
+fn insert_learning(
+    conn: &mut Connection,
+    song_id: &str,
+    level: i64,
+    path: &str,
+    grad: i64,
+) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO learning (id, song_id, level, created_at, updated_at, last_level_up_at, level_up_path, graduated) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![id, song_id, level, now, now, now, path, grad],
+    )
+    .unwrap();
+    id
+}
+
+fn check<'a>(report: &'a serde_json::Value, name: &str) -> &'a serde_json::Value {
+    report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == name)
+        .unwrap()
+}
+
+const PATH: &str = "[1,2,3]";
+
+// === DOCTOR ===
+
+#[test]
+fn test_doctor_clean_database() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    insert_learning(&mut c, &sid, 1, PATH, 0);
+    let r = commands::cmd_doctor(&mut c, false).unwrap();
+    assert_eq!(r["ok"], true);
+    assert_eq!(r["issue_count"], 0);
+    assert_eq!(r["checks"].as_array().unwrap().len(), 12);
+    assert_eq!(r["changes"], serde_json::json!([]));
+}
+
+#[test]
+fn test_doctor_reports_without_changing() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    insert_learning(&mut c, &sid, 0, "not json", 0);
+    insert_learning(&mut c, &sid, 5, PATH, 0);
+    let r = commands::cmd_doctor(&mut c, false).unwrap();
+    assert_eq!(r["ok"], false);
+    assert_eq!(r["issue_count"], 3);
+    assert_eq!(check(&r, "duplicate_active_learning")["issue_count"], 1);
+    assert_eq!(check(&r, "invalid_level_up_path")["issue_count"], 1);
+    assert_eq!(check(&r, "level_beyond_path")["issue_count"], 1);
+    assert_eq!(check(&r, "level_beyond_path")["fixed_count"], 0);
+
+    let again = commands::cmd_doctor(&mut c, false).unwrap();
+    assert_eq!(again["issue_count"], 3);
+}
+
+#[test]
+fn test_doctor_fix_learning_issues() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let s1 = insert_song(&mut c, "S1", &aid);
+    let s2 = insert_song(&mut c, "S2", &aid);
+    let s3 = insert_song(&mut c, "S3", &aid);
+    let kept = insert_learning(&mut c, &s1, 2, PATH, 0);
+    let dropped = insert_learning(&mut c, &s1, 0, PATH, 0);
+    let bad_path = insert_learning(&mut c, &s2, 0, "{\"a\":1}", 0);
+    let grad = insert_learning(&mut c, &s3, 0, PATH, 1);
+
+    let r = commands::cmd_doctor(&mut c, true).unwrap();
+    assert_eq!(r["fix"], true);
+    assert_eq!(r["issue_count"], 3);
+    let changes = r["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0]["action"], "delete");
+    assert_eq!(changes[0]["id"], dropped);
+    assert_eq!(changes[0]["kept_id"], kept);
+    assert_eq!(changes[1]["id"], bad_path);
+    assert_eq!(changes[1]["field"], "level_up_path");
+    assert_eq!(changes[2]["id"], grad);
+    assert_eq!(changes[2]["from"], 0);
+    assert_eq!(changes[2]["to"], 2);
+
+    let after = commands::cmd_doctor(&mut c, false).unwrap();
+    assert_eq!(after["ok"], true);
+    let path: String = c
+        .query_row(
+            "SELECT level_up_path FROM learning WHERE id = ?1",
+            [&bad_path],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(
+        path,
+        jankenoboe::easing::generate_level_up_path_json(jankenoboe::easing::MAX_LEVEL)
+    );
+}
+
+#[test]
+fn test_doctor_fix_orphans() {
+    let mut c = test_conn();
+    let orphan_song = insert_song(&mut c, "S", "ghost-artist");
+    c.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+    let orphan_learning = insert_learning(&mut c, "ghost-song", 0, PATH, 0);
+    c.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+
+    let r = commands::cmd_doctor(&mut c, true).unwrap();
+    let orphans = check(&r, "orphaned_references");
+    assert_eq!(orphans["issue_count"], 2);
+    assert_eq!(orphans["fixed_count"], 1);
+    assert_eq!(
+        r["changes"],
+        serde_json::json!([{
+            "check": "orphaned_references",
+            "action": "delete",
+            "table": "learning",
+            "id": orphan_learning
+        }])
+    );
+
+    // Songs with a missing artist need manual reassignment
+    let after = commands::cmd_fsck(&mut c).unwrap();
+    assert_eq!(after["orphan_count"], 1);
+    assert_eq!(after["orphans"][0]["id"], orphan_song);
+}
+
+#[test]
+fn test_doctor_clears_missing_session() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    c.execute(
+        "INSERT INTO show (id, name, name_key, created_at, updated_at) VALUES ('sh', 'Sh', 'sh', 0, 0)",
+        [],
+    )
+    .unwrap();
+    c.execute(
+        "INSERT INTO play_history (id, show_id, song_id, created_at, session_id) VALUES ('p1', 'sh', ?1, 0, 'ghost-session')",
+        [&sid],
+    )
+    .unwrap();
+
+    let r = commands::cmd_doctor(&mut c, true).unwrap();
+    assert_eq!(check(&r, "orphaned_references")["fixed_count"], 1);
+    assert_eq!(
+        r["changes"],
+        serde_json::json!([{
+            "check": "orphaned_references",
+            "action": "clear_reference",
+            "table": "play_history",
+            "id": "p1",
+            "field": "session_id"
+        }])
+    );
+    // The play is kept
+    let session: Option<String> = c
+        .query_row(
+            "SELECT session_id FROM play_history WHERE id = 'p1'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(session, None);
+}
+
+#[test]
+fn test_doctor_reports_references_to_deleted() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    commands::cmd_soft_delete(&mut c, "artist", &aid).unwrap();
+    let r = commands::cmd_doctor(&mut c, true).unwrap();
+    let refs = check(&r, "references_to_deleted");
+    assert_eq!(
+        refs["findings"],
+        serde_json::json!([{"table": "song", "id": sid, "field": "artist_id", "target_id": aid}])
+    );
+    assert_eq!(refs["fixed_count"], 0);
+}
+
+#[test]
+fn test_doctor_creates_missing_tables() {
+    let mut c = test_conn();
+    c.execute_batch("DROP TABLE song_credit;").unwrap();
+    let r = commands::cmd_doctor(&mut c, false).unwrap();
+    assert_eq!(
+        check(&r, "missing_tables")["findings"],
+        serde_json::json!([{"name": "song_credit"}])
+    );
+
+    let r = commands::cmd_doctor(&mut c, true).unwrap();
+    let actions: Vec<(&str, &str)> = r["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| (c["action"].as_str().unwrap(), c["name"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            ("create_table", "song_credit"),
+            ("create_index", "idx_song_credit_song_id"),
+            ("create_index", "idx_song_credit_artist_id"),
+        ]
+    );
+    assert_eq!(commands::cmd_doctor(&mut c, false).unwrap()["ok"], true);
+    assert_eq!(commands::cmd_fsck(&mut c).unwrap()["ok"], true);
+}
+
+#[test]
+fn test_doctor_fix_missing_index() {
+    let mut c = test_conn();
+    c.execute_batch("DROP INDEX idx_learning_song_id;").unwrap();
+    let r = commands::cmd_doctor(&mut c, false).unwrap();
+    assert_eq!(
+        check(&r, "missing_indexes")["findings"][0]["name"],
+        "idx_learning_song_id"
+    );
+
+    let r = commands::cmd_doctor(&mut c, true).unwrap();
+    assert_eq!(r["changes"][0]["action"], "create_index");
+    let count: i64 = c
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='index' AND name='idx_learning_song_id'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_doctor_rebuilds_missing_name_index() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Aimer");
+    insert_song(&mut c, "Kataomoi", &aid);
+    // Simulate a database created before the name index existed
+    c.execute_batch(
+        "DROP TRIGGER artist_name_fts_insert; DROP TABLE name_fts; DROP TABLE name_fts_row;",
+    )
+    .unwrap();
+
+    let report = commands::cmd_doctor(&mut c, false).unwrap();
+    let findings = check(&report, "name_index")["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 3);
+    assert_eq!(findings[0]["name"], "name_fts_row");
+
+    let report = commands::cmd_doctor(&mut c, true).unwrap();
+    assert_eq!(check(&report, "name_index")["fixed_count"], 3);
+    assert_eq!(report["changes"][0]["action"], "rebuild_name_index");
+    assert_eq!(report["changes"][0]["rows"], 3);
+
+    let found = commands::cmd_find(&mut c, "aim", None, 20, false).unwrap();
+    assert_eq!(found["count"], 1);
+    insert_artist(&mut c, "Aimyon");
+    let found = commands::cmd_find(&mut c, "aim", None, 20, false).unwrap();
+    assert_eq!(found["count"], 2);
+    assert_eq!(commands::cmd_doctor(&mut c, false).unwrap()["ok"], true);
+}
+
+#[test]
+fn test_doctor_detects_out_of_sync_name_index() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "Aimer");
+    c.execute_batch("DELETE FROM name_fts; DELETE FROM name_fts_row;")
+        .unwrap();
+    let report = commands::cmd_doctor(&mut c, true).unwrap();
+    let name_index = check(&report, "name_index");
+    assert_eq!(name_index["findings"][0]["issue"], "out_of_sync");
+    assert_eq!(name_index["findings"][0]["expected_rows"], 1);
+    assert_eq!(name_index["findings"][0]["indexed_rows"], 0);
+    assert_eq!(
+        commands::cmd_find(&mut c, "aimer", None, 20, false).unwrap()["count"],
+        1
+    );
+}
+
+#[test]
+fn test_doctor_adds_missing_columns_and_backfills_name_keys() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "とうきょう");
+    // Simulate a database created before normalized keys existed
+    c.execute_batch("DROP INDEX idx_artist_name_key; ALTER TABLE artist DROP COLUMN name_key;")
+        .unwrap();
+    insert_song_without_key(&mut c, "Fuwa Fuwa Time", &aid);
+
+    let report = commands::cmd_doctor(&mut c, false).unwrap();
+    let columns = check(&report, "missing_columns");
+    assert_eq!(
+        columns["findings"],
+        serde_json::json!([{
+            "table": "artist",
+            "column": "name_key",
+            "sql": "ALTER TABLE \"artist\" ADD COLUMN \"name_key\" TEXT;"
+        }])
+    );
+
+    let report = commands::cmd_doctor(&mut c, true).unwrap();
+    assert_eq!(check(&report, "missing_columns")["fixed_count"], 1);
+    assert_eq!(check(&report, "missing_indexes")["fixed_count"], 1);
+    assert_eq!(check(&report, "stale_name_keys")["fixed_count"], 2);
+    let key: String = c
+        .query_row("SELECT name_key FROM artist WHERE id = ?1", [&aid], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(key, "tokyo");
+    let key: String = c
+        .query_row("SELECT name_key FROM song", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(key, "fuwafuwatime");
+    assert_eq!(commands::cmd_doctor(&mut c, false).unwrap()["ok"], true);
+}
+
+#[test]
+fn test_doctor_backfills_vintage_fields() {
+    let mut c = test_conn();
+    let now = jankenoboe::models::now_unix();
+    // Rows written without the derived vintage columns
+    for (id, vintage) in [("s1", "Spring 2009"), ("s2", "2009"), ("s3", "")] {
+        c.execute(
+            "INSERT INTO show (id, name, vintage, name_key, created_at, updated_at) VALUES (?1, 'x', ?2, 'x', ?3, ?3)",
+            rusqlite::params![id, vintage, now],
+        )
+        .unwrap();
+    }
+    c.execute(
+        "UPDATE show SET season = 'Fall', year = 2009 WHERE id = 's2'",
+        [],
+    )
+    .unwrap();
+
+    let report = commands::cmd_doctor(&mut c, true).unwrap();
+    let check = check(&report, "stale_vintage_fields");
+    assert_eq!(check["issue_count"], 2);
+    assert_eq!(check["findings"][0]["id"], "s1");
+    assert_eq!(
+        check["findings"][0]["expected"],
+        serde_json::json!({"season": "Spring", "year": 2009, "vintage_key": 20092})
+    );
+    assert_eq!(check["findings"][1]["id"], "s2");
+    assert_eq!(check["fixed_count"], 2);
+
+    let found = commands::cmd_shows_by_vintage(&mut c, Some("Spring 2009"), None, false).unwrap();
+    assert_eq!(found["results"][0]["show_id"], "s1");
+    let season: Option<String> = c
+        .query_row("SELECT season FROM show WHERE id = 's2'", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(season, None);
+    assert_eq!(commands::cmd_doctor(&mut c, false).unwrap()["ok"], true);
+}
+
+fn insert_song_without_key(conn: &mut Connection, name: &str, artist_id: &str) {
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO song (id, name, artist_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), name, artist_id, now, now],
+    )
+    .unwrap();
+}
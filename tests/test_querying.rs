@@ -1,4 +1,5 @@
 use jankenoboe::commands;
+use jankenoboe::models::SearchOptions;
 use rusqlite::Connection;
 
 fn test_conn() -> Connection {
@@ -57,9 +58,22 @@ fn insert_show_full(
 ) -> String {
     let id = uuid::Uuid::new_v4().to_string();
     let now = jankenoboe::models::now_unix();
+    // Derived vintage columns as `create` would store them
+    let parsed = jankenoboe::vintage::parse(vintage);
     conn.execute(
-        "INSERT INTO show (id, name, name_romaji, vintage, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        rusqlite::params![id, name, name_romaji, vintage, now, now],
+        "INSERT INTO show (id, name, name_romaji, vintage, season, year, vintage_key, created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            id,
+            name,
+            name_romaji,
+            vintage,
+            parsed.map(|v| v.season()),
+            parsed.map(|v| v.year),
+            parsed.map(|v| v.key()),
+            now,
+            now
+        ],
     )
     .unwrap();
     id
@@ -88,7 +102,7 @@ fn insert_learning(
 fn test_get_artist_by_id() {
     let mut c = test_conn();
     let aid = insert_artist(&mut c, "ChoQMay");
-    let r = commands::cmd_get(&mut c, "artist", &aid, "id,name").unwrap();
+    let r = commands::cmd_get(&mut c, "artist", &aid, "id,name", false).unwrap();
     let results = r["results"].as_array().unwrap();
     assert_eq!(results.len(), 1);
     assert_eq!(results[0]["id"], aid);
@@ -100,7 +114,7 @@ fn test_get_song_multiple_fields() {
     let mut c = test_conn();
     let aid = insert_artist(&mut c, "A");
     let sid = insert_song(&mut c, "S", &aid);
-    let r = commands::cmd_get(&mut c, "song", &sid, "id,name,artist_id").unwrap();
+    let r = commands::cmd_get(&mut c, "song", &sid, "id,name,artist_id", false).unwrap();
     assert_eq!(r["results"][0]["name"], "S");
     assert_eq!(r["results"][0]["artist_id"], aid);
 }
@@ -108,7 +122,7 @@ fn test_get_song_multiple_fields() {
 #[test]
 fn test_get_nonexistent_returns_empty() {
     let mut c = test_conn();
-    let r = commands::cmd_get(&mut c, "artist", "no-such-id", "id,name").unwrap();
+    let r = commands::cmd_get(&mut c, "artist", "no-such-id", "id,name", false).unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
 }
 
@@ -116,7 +130,7 @@ fn test_get_nonexistent_returns_empty() {
 fn test_get_invalid_table() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_get(&mut c, "bad", "x", "id")
+        commands::cmd_get(&mut c, "bad", "x", "id", false)
             .unwrap_err()
             .to_string()
             .contains("Invalid table")
@@ -127,7 +141,7 @@ fn test_get_invalid_table() {
 fn test_get_invalid_field() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_get(&mut c, "artist", "x", "id,password")
+        commands::cmd_get(&mut c, "artist", "x", "id,password", false)
             .unwrap_err()
             .to_string()
             .contains("Invalid field")
@@ -138,7 +152,7 @@ fn test_get_invalid_field() {
 fn test_get_empty_fields() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_get(&mut c, "artist", "x", "")
+        commands::cmd_get(&mut c, "artist", "x", "", false)
             .unwrap_err()
             .to_string()
             .contains("fields cannot be empty")
@@ -151,7 +165,7 @@ fn test_get_learning_record() {
     let aid = insert_artist(&mut c, "A");
     let sid = insert_song(&mut c, "S", &aid);
     let lid = insert_learning(&mut c, &sid, 5, 0, 0);
-    let r = commands::cmd_get(&mut c, "learning", &lid, "level,graduated").unwrap();
+    let r = commands::cmd_get(&mut c, "learning", &lid, "level,graduated", false).unwrap();
     assert_eq!(r["results"][0]["level"], 5);
     assert_eq!(r["results"][0]["graduated"], 0);
 }
@@ -164,7 +178,8 @@ fn test_batch_get_multiple_artists() {
     let a1 = insert_artist(&mut c, "Alpha");
     let a2 = insert_artist(&mut c, "Beta");
     insert_artist(&mut c, "Gamma");
-    let r = commands::cmd_batch_get(&mut c, "artist", &format!("{a1},{a2}"), "id,name").unwrap();
+    let r =
+        commands::cmd_batch_get(&mut c, "artist", &format!("{a1},{a2}"), "id,name", false).unwrap();
     assert_eq!(r["count"], 2);
     let results = r["results"].as_array().unwrap();
     assert_eq!(results.len(), 2);
@@ -180,7 +195,7 @@ fn test_batch_get_multiple_artists() {
 fn test_batch_get_single_id() {
     let mut c = test_conn();
     let a1 = insert_artist(&mut c, "Solo");
-    let r = commands::cmd_batch_get(&mut c, "artist", &a1, "id,name").unwrap();
+    let r = commands::cmd_batch_get(&mut c, "artist", &a1, "id,name", false).unwrap();
     assert_eq!(r["count"], 1);
     assert_eq!(r["results"][0]["name"], "Solo");
 }
@@ -191,8 +206,14 @@ fn test_batch_get_songs() {
     let aid = insert_artist(&mut c, "A");
     let s1 = insert_song(&mut c, "Song1", &aid);
     let s2 = insert_song(&mut c, "Song2", &aid);
-    let r = commands::cmd_batch_get(&mut c, "song", &format!("{s1},{s2}"), "id,name,artist_id")
-        .unwrap();
+    let r = commands::cmd_batch_get(
+        &mut c,
+        "song",
+        &format!("{s1},{s2}"),
+        "id,name,artist_id",
+        false,
+    )
+    .unwrap();
     assert_eq!(r["count"], 2);
     let results = r["results"].as_array().unwrap();
     assert_eq!(results[0]["artist_id"], aid);
@@ -203,8 +224,14 @@ fn test_batch_get_songs() {
 fn test_batch_get_nonexistent_ids_ignored() {
     let mut c = test_conn();
     let a1 = insert_artist(&mut c, "Real");
-    let r =
-        commands::cmd_batch_get(&mut c, "artist", &format!("{a1},no-such-id"), "id,name").unwrap();
+    let r = commands::cmd_batch_get(
+        &mut c,
+        "artist",
+        &format!("{a1},no-such-id"),
+        "id,name",
+        false,
+    )
+    .unwrap();
     assert_eq!(r["count"], 1);
     assert_eq!(r["results"][0]["name"], "Real");
 }
@@ -212,7 +239,7 @@ fn test_batch_get_nonexistent_ids_ignored() {
 #[test]
 fn test_batch_get_all_nonexistent() {
     let mut c = test_conn();
-    let r = commands::cmd_batch_get(&mut c, "artist", "no-id-1,no-id-2", "id,name").unwrap();
+    let r = commands::cmd_batch_get(&mut c, "artist", "no-id-1,no-id-2", "id,name", false).unwrap();
     assert_eq!(r["count"], 0);
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
 }
@@ -220,7 +247,7 @@ fn test_batch_get_all_nonexistent() {
 #[test]
 fn test_batch_get_empty_ids() {
     let mut c = test_conn();
-    let err = commands::cmd_batch_get(&mut c, "artist", "", "id,name")
+    let err = commands::cmd_batch_get(&mut c, "artist", "", "id,name", false)
         .unwrap_err()
         .to_string();
     assert_eq!(err, "ids cannot be empty");
@@ -229,7 +256,7 @@ fn test_batch_get_empty_ids() {
 #[test]
 fn test_batch_get_empty_fields() {
     let mut c = test_conn();
-    let err = commands::cmd_batch_get(&mut c, "artist", "some-id", "")
+    let err = commands::cmd_batch_get(&mut c, "artist", "some-id", "", false)
         .unwrap_err()
         .to_string();
     assert_eq!(err, "fields cannot be empty");
@@ -238,7 +265,7 @@ fn test_batch_get_empty_fields() {
 #[test]
 fn test_batch_get_invalid_table() {
     let mut c = test_conn();
-    let err = commands::cmd_batch_get(&mut c, "bad", "some-id", "id")
+    let err = commands::cmd_batch_get(&mut c, "bad", "some-id", "id", false)
         .unwrap_err()
         .to_string();
     assert!(err.contains("Invalid table"));
@@ -247,7 +274,7 @@ fn test_batch_get_invalid_table() {
 #[test]
 fn test_batch_get_invalid_field() {
     let mut c = test_conn();
-    let err = commands::cmd_batch_get(&mut c, "artist", "some-id", "id,password")
+    let err = commands::cmd_batch_get(&mut c, "artist", "some-id", "id,password", false)
         .unwrap_err()
         .to_string();
     assert!(err.contains("Invalid field"));
@@ -266,6 +293,7 @@ fn test_batch_get_learning_records() {
         "learning",
         &format!("{l1},{l2}"),
         "id,level,graduated",
+        false,
     )
     .unwrap();
     assert_eq!(r["count"], 2);
@@ -290,6 +318,7 @@ fn test_search_exact_match() {
         "artist",
         r#"{"name":{"value":"ChoQMay"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -305,6 +334,7 @@ fn test_search_exact_is_case_sensitive() {
         "artist",
         r#"{"name":{"value":"minami","match":"exact"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
@@ -319,6 +349,7 @@ fn test_search_exact_i() {
         "artist",
         r#"{"name":{"value":"minami","match":"exact-i"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -336,6 +367,7 @@ fn test_search_starts_with() {
         "artist",
         r#"{"name":{"value":"min","match":"starts-with"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -352,6 +384,7 @@ fn test_search_ends_with() {
         "artist",
         r#"{"name":{"value":"ami","match":"ends-with"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 2);
@@ -367,6 +400,7 @@ fn test_search_contains() {
         "artist",
         r#"{"name":{"value":"nam","match":"contains"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -382,6 +416,7 @@ fn test_search_multiple_and_conditions() {
         &mut c, "show",
         r#"{"name":{"value":"sign","match":"contains"},"vintage":{"value":"2024","match":"ends-with"}}"#,
         "id,name,vintage",
+        &SearchOptions::default(),
     ).unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 2);
 }
@@ -403,6 +438,7 @@ fn test_search_rel_show_song() {
         "rel_show_song",
         &format!(r#"{{"show_id":{{"value":"{shid}"}},"song_id":{{"value":"{sid}"}}}}"#),
         "show_id,song_id",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -421,6 +457,7 @@ fn test_search_song_by_artist_id() {
         "song",
         &format!(r#"{{"artist_id":{{"value":"{a1}"}}}}"#),
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 2);
@@ -429,12 +466,18 @@ fn test_search_song_by_artist_id() {
 #[test]
 fn test_search_invalid_table() {
     let mut c = test_conn();
-    let err = commands::cmd_search(&mut c, "bad_table", r#"{"name":{"value":"t"}}"#, "id")
-        .unwrap_err()
-        .to_string();
+    let err = commands::cmd_search(
+        &mut c,
+        "bad_table",
+        r#"{"name":{"value":"t"}}"#,
+        "id",
+        &SearchOptions::default(),
+    )
+    .unwrap_err()
+    .to_string();
     assert_eq!(
         err,
-        "Invalid table in term key validation: bad_table. Allowed: artist, show, song, play_history, rel_show_song, learning"
+        "Invalid table in term key validation: bad_table. Allowed: artist, show, song, play_history, rel_show_song, learning, song_credit, artist_member, artist_alias, show_alias, game_session"
     );
 }
 
@@ -442,12 +485,18 @@ fn test_search_invalid_table() {
 fn test_search_invalid_term_key() {
     let mut c = test_conn();
     // "id" is not in artist's searchable fields (only "name" and "name_context")
-    let err = commands::cmd_search(&mut c, "artist", r#"{"id":{"value":"t"}}"#, "id,name")
-        .unwrap_err()
-        .to_string();
+    let err = commands::cmd_search(
+        &mut c,
+        "artist",
+        r#"{"id":{"value":"t"}}"#,
+        "id,name",
+        &SearchOptions::default(),
+    )
+    .unwrap_err()
+    .to_string();
     assert_eq!(
         err,
-        "Invalid term key for artist: id. Allowed: name, name_context"
+        "Invalid term key for artist: id. Allowed: name, name_context, created_at"
     );
 }
 
@@ -459,7 +508,8 @@ fn test_search_invalid_match_mode() {
             &mut c,
             "artist",
             r#"{"name":{"value":"t","match":"regex"}}"#,
-            "id,name"
+            "id,name",
+            &SearchOptions::default()
         )
         .unwrap_err()
         .to_string()
@@ -471,10 +521,16 @@ fn test_search_invalid_match_mode() {
 fn test_search_empty_term() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_search(&mut c, "artist", r#"{}"#, "id,name")
-            .unwrap_err()
-            .to_string()
-            .contains("term cannot be empty")
+        commands::cmd_search(
+            &mut c,
+            "artist",
+            r#"{}"#,
+            "id,name",
+            &SearchOptions::default()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("term cannot be empty")
     );
 }
 
@@ -482,10 +538,16 @@ fn test_search_empty_term() {
 fn test_search_term_condition_not_object() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_search(&mut c, "artist", r#"{"name":"string_val"}"#, "id,name")
-            .unwrap_err()
-            .to_string()
-            .contains("must be an object")
+        commands::cmd_search(
+            &mut c,
+            "artist",
+            r#"{"name":"string_val"}"#,
+            "id,name",
+            &SearchOptions::default()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("must be an object")
     );
 }
 
@@ -493,10 +555,16 @@ fn test_search_term_condition_not_object() {
 fn test_search_term_condition_missing_value() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_search(&mut c, "artist", r#"{"name":{"match":"exact"}}"#, "id,name")
-            .unwrap_err()
-            .to_string()
-            .contains("must have a 'value' string")
+        commands::cmd_search(
+            &mut c,
+            "artist",
+            r#"{"name":{"match":"exact"}}"#,
+            "id,name",
+            &SearchOptions::default()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("must have a 'value' string")
     );
 }
 
@@ -504,19 +572,31 @@ fn test_search_term_condition_missing_value() {
 fn test_search_term_value_not_string() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_search(&mut c, "artist", r#"{"name":{"value":123}}"#, "id,name")
-            .unwrap_err()
-            .to_string()
-            .contains("must have a 'value' string")
+        commands::cmd_search(
+            &mut c,
+            "artist",
+            r#"{"name":{"value":123}}"#,
+            "id,name",
+            &SearchOptions::default()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("must have a 'value' string")
     );
 }
 
 #[test]
 fn test_search_invalid_json() {
     let mut c = test_conn();
-    let err = commands::cmd_search(&mut c, "artist", "not json", "id,name")
-        .unwrap_err()
-        .to_string();
+    let err = commands::cmd_search(
+        &mut c,
+        "artist",
+        "not json",
+        "id,name",
+        &SearchOptions::default(),
+    )
+    .unwrap_err()
+    .to_string();
     assert_eq!(err, "Invalid JSON: expected ident at line 1 column 2");
 }
 
@@ -530,6 +610,7 @@ fn test_search_url_encoded_value() {
         "artist",
         r#"{"name":{"value":"it%27s%20a%20test"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -545,6 +626,7 @@ fn test_search_url_encoded_value_contains() {
         "artist",
         r#"{"name":{"value":"K-On%21","match":"starts-with"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -560,6 +642,7 @@ fn test_search_plain_value_still_works() {
         "artist",
         r#"{"name":{"value":"ChoQMay"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -577,6 +660,7 @@ fn test_search_url_encoded_single_quote() {
         "artist",
         r#"{"name":{"value":"Ado%27s%20Music"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -593,6 +677,7 @@ fn test_search_url_encoded_double_quote() {
         "artist",
         r#"{"name":{"value":"The%20%22Best%22%20Artist"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -609,6 +694,7 @@ fn test_search_url_encoded_quotes_contains() {
         "artist",
         r#"{"name":{"value":"5-nin%20Ver.","match":"contains"}}"#,
         "id,name",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -619,19 +705,31 @@ fn test_search_url_encoded_quotes_contains() {
 fn test_search_empty_fields() {
     let mut c = test_conn();
     assert!(
-        commands::cmd_search(&mut c, "artist", r#"{"name":{"value":"x"}}"#, "")
-            .unwrap_err()
-            .to_string()
-            .contains("fields cannot be empty")
+        commands::cmd_search(
+            &mut c,
+            "artist",
+            r#"{"name":{"value":"x"}}"#,
+            "",
+            &SearchOptions::default()
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("fields cannot be empty")
     );
 }
 
 #[test]
 fn test_search_invalid_url_encoding() {
     let mut c = test_conn();
-    let err = commands::cmd_search(&mut c, "artist", r#"{"name":{"value":"%ZZ"}}"#, "id,name")
-        .unwrap_err()
-        .to_string();
+    let err = commands::cmd_search(
+        &mut c,
+        "artist",
+        r#"{"name":{"value":"%ZZ"}}"#,
+        "id,name",
+        &SearchOptions::default(),
+    )
+    .unwrap_err()
+    .to_string();
     assert!(err.contains("URL decoding error for search value of 'name'"));
 }
 
@@ -652,6 +750,7 @@ fn test_search_play_history_by_song_id() {
         "play_history",
         &format!(r#"{{"song_id":{{"value":"{song1}"}}}}"#),
         "id,song_id,media_url",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 2);
@@ -671,6 +770,7 @@ fn test_search_play_history_by_show_id() {
         "play_history",
         &format!(r#"{{"show_id":{{"value":"{sh1}"}}}}"#),
         "id,show_id,song_id",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -691,6 +791,7 @@ fn test_search_play_history_by_both_ids() {
         "play_history",
         &format!(r#"{{"show_id":{{"value":"{shid}"}},"song_id":{{"value":"{song1}"}}}}"#),
         "id,media_url",
+        &SearchOptions::default(),
     )
     .unwrap();
     assert_eq!(r["results"].as_array().unwrap().len(), 1);
@@ -712,6 +813,7 @@ fn test_search_show_by_name_romaji_exact_i() {
         "show",
         r#"{"name_romaji":{"value":"yubisaki to renren","match":"exact-i"}}"#,
         "id,name,name_romaji",
+        &SearchOptions::default(),
     )
     .unwrap();
     let results = r["results"].as_array().unwrap();
@@ -741,6 +843,7 @@ fn test_search_show_by_name_romaji_contains() {
         "show",
         r#"{"name_romaji":{"value":"yubisaki","match":"contains"}}"#,
         "id,name,name_romaji",
+        &SearchOptions::default(),
     )
     .unwrap();
     let results = r["results"].as_array().unwrap();
@@ -758,6 +861,7 @@ fn test_search_show_by_name_romaji_and_vintage() {
         "show",
         r#"{"name_romaji":{"value":"Romaji A","match":"exact-i"},"vintage":{"value":"Winter 2024"}}"#,
         "id,name,vintage",
+        &SearchOptions::default(),
     )
     .unwrap();
     let results = r["results"].as_array().unwrap();
@@ -791,6 +895,77 @@ fn test_duplicates_artist() {
     assert_eq!(counts, vec![1, 2]);
 }
 
+#[test]
+fn test_duplicates_artist_through_alias() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "Yui Horie");
+    let a2 = insert_artist(&mut c, "Horie Yui");
+    commands::cmd_create(
+        &mut c,
+        "artist_alias",
+        &format!(r#"{{"artist_id":"{a1}","name":"HORIE YUI"}}"#),
+    )
+    .unwrap();
+    let r = commands::cmd_duplicates(&mut c, "artist").unwrap();
+    assert_eq!(
+        r["duplicates"],
+        serde_json::json!([{
+            "name": "horie yui",
+            "records": [
+                {"id": a2, "name": "Horie Yui", "song_count": 0},
+                {"id": a1, "name": "Yui Horie", "alias": "HORIE YUI", "song_count": 0}
+            ]
+        }])
+    );
+}
+
+#[test]
+fn test_duplicates_fuzzy_show_alias() {
+    let mut c = test_conn();
+    let s1 = insert_show(
+        &mut c,
+        "Wooser's Hand-to-Mouth Life: Awakening Arc",
+        "Fall 2014",
+    );
+    let s2 = insert_show(&mut c, "Wooser no Sono Higurashi: Kakusei-hen", "Fall 2014");
+    commands::cmd_create(
+        &mut c,
+        "show_alias",
+        &format!(r#"{{"show_id":"{s1}","name":"Wooser no Sono Higurashi Kakusei hen"}}"#),
+    )
+    .unwrap();
+    let r = commands::cmd_duplicates_fuzzy(&mut c, "show", 2, 0.75).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["pairs"][0]["matched_on"], "alias/name");
+    assert_eq!(r["pairs"][0]["records"][0]["id"], s1);
+    assert_eq!(r["pairs"][0]["records"][1]["id"], s2);
+}
+
+#[test]
+fn test_search_artist_by_alias() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "Yui Horie");
+    insert_artist(&mut c, "Horie");
+    let song = insert_song(&mut c, "Love Destiny", &a1);
+    for alias in ["Horie Yui", "堀江由衣"] {
+        commands::cmd_create(
+            &mut c,
+            "artist_alias",
+            &format!(r#"{{"artist_id":"{a1}","name":"{alias}"}}"#),
+        )
+        .unwrap();
+    }
+
+    let term = r#"{"any": [{"name": {"value": "horieyui", "match": "normalized"}},
+                           {"artist_alias.name": {"value": "horieyui", "match": "normalized"}}]}"#;
+    let r = commands::cmd_search(&mut c, "artist", term, "id", &SearchOptions::default()).unwrap();
+    assert_eq!(r["results"], serde_json::json!([{"id": a1}]));
+
+    let term = r#"{"artist_alias.name": {"value": "堀江由衣"}}"#;
+    let r = commands::cmd_search(&mut c, "song", term, "id", &SearchOptions::default()).unwrap();
+    assert_eq!(r["results"], serde_json::json!([{"id": song}]));
+}
+
 #[test]
 fn test_duplicates_none() {
     let mut c = test_conn();
@@ -823,14 +998,67 @@ fn test_duplicates_song() {
     let mut c = test_conn();
     let a1 = insert_artist(&mut c, "A1");
     let a2 = insert_artist(&mut c, "A2");
-    insert_song(&mut c, "Same Song", &a1);
-    insert_song(&mut c, "same song", &a2);
+    let s1 = insert_song(&mut c, "Same Song", &a1);
+    let s2 = insert_song(&mut c, "same song", &a1);
+    insert_song(&mut c, "Same Song", &a2);
     insert_song(&mut c, "Unique Song", &a1);
+    let shid = insert_show(&mut c, "Show", "Winter 2024");
+    insert_play_history(&mut c, &shid, &s1, "");
+    insert_play_history(&mut c, &shid, &s1, "");
+    insert_learning(&mut c, &s2, 0, 0, 0);
     let r = commands::cmd_duplicates(&mut c, "song").unwrap();
     let dups = r["duplicates"].as_array().unwrap();
     assert_eq!(dups.len(), 1);
     assert_eq!(dups[0]["name"], "same song");
-    assert_eq!(dups[0]["records"].as_array().unwrap().len(), 2);
+    assert_eq!(dups[0]["artist_id"], a1);
+    assert_eq!(dups[0]["artist_name"], "A1");
+    let recs = dups[0]["records"].as_array().unwrap();
+    assert_eq!(recs.len(), 2);
+    assert_eq!(recs[0]["id"], s1);
+    assert_eq!(recs[0]["artist_name"], "A1");
+    assert_eq!(recs[0]["play_count"], 2);
+    assert_eq!(recs[0]["learning_count"], 0);
+    assert_eq!(recs[1]["id"], s2);
+    assert_eq!(recs[1]["play_count"], 0);
+    assert_eq!(recs[1]["learning_count"], 1);
+}
+
+#[test]
+fn test_duplicates_song_different_artists_not_grouped() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "A1");
+    let a2 = insert_artist(&mut c, "A2");
+    insert_song(&mut c, "Same Song", &a1);
+    insert_song(&mut c, "same song", &a2);
+    let r = commands::cmd_duplicates(&mut c, "song").unwrap();
+    assert_eq!(r["duplicates"].as_array().unwrap().len(), 0);
+    let fuzzy = commands::cmd_duplicates_fuzzy(&mut c, "song", 2, 0.75).unwrap();
+    assert_eq!(fuzzy["count"], 0);
+}
+
+#[test]
+fn test_duplicates_show_context() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let song = insert_song(&mut c, "S", &aid);
+    let sh1 = insert_show(&mut c, "K-On!", "Spring 2009");
+    let sh2 = insert_show(&mut c, "k-on!", "Fall 2009");
+    c.execute(
+        "INSERT INTO rel_show_song (show_id, song_id, created_at) VALUES (?1, ?2, 0)",
+        rusqlite::params![sh1, song],
+    )
+    .unwrap();
+    insert_play_history(&mut c, &sh1, &song, "");
+    let r = commands::cmd_duplicates(&mut c, "show").unwrap();
+    let recs = r["duplicates"][0]["records"].as_array().unwrap();
+    assert_eq!(recs[0]["id"], sh1);
+    assert_eq!(recs[0]["vintage"], "Spring 2009");
+    assert_eq!(recs[0]["song_count"], 1);
+    assert_eq!(recs[0]["play_count"], 1);
+    assert_eq!(recs[1]["id"], sh2);
+    assert_eq!(recs[1]["vintage"], "Fall 2009");
+    assert_eq!(recs[1]["song_count"], 0);
+    assert_eq!(recs[1]["play_count"], 0);
 }
 
 #[test]
@@ -845,6 +1073,79 @@ fn test_duplicates_multiple_groups() {
     assert_eq!(dups.len(), 2);
 }
 
+// === DUPLICATES --fuzzy ===
+
+#[test]
+fn test_duplicates_fuzzy_artist_token_order_and_width() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "Yui Horie");
+    let a2 = insert_artist(&mut c, "Horie Yui");
+    let a3 = insert_artist(&mut c, "ClariS ");
+    let a4 = insert_artist(&mut c, "ＣｌａｒｉＳ");
+    insert_artist(&mut c, "Aimer");
+    let r = commands::cmd_duplicates_fuzzy(&mut c, "artist", 2, 0.75).unwrap();
+    assert_eq!(r["count"], 2);
+    let pairs = r["pairs"].as_array().unwrap();
+    assert_eq!(pairs[0]["records"][0]["id"], a3);
+    assert_eq!(pairs[0]["records"][1]["id"], a4);
+    assert_eq!(pairs[1]["records"][0]["id"], a1);
+    assert_eq!(pairs[1]["records"][1]["id"], a2);
+    assert_eq!(pairs[0]["score"], 1.0);
+    assert_eq!(pairs[0]["distance"], 0);
+    assert_eq!(pairs[0]["matched_on"], "name");
+}
+
+#[test]
+fn test_duplicates_fuzzy_edit_distance_threshold() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "Kana Hanazawa");
+    insert_artist(&mut c, "Kana Hanazwa");
+    let r = commands::cmd_duplicates_fuzzy(&mut c, "artist", 1, 0.75).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["pairs"][0]["distance"], 1);
+    assert_eq!(r["pairs"][0]["score"], 0.917);
+    let strict = commands::cmd_duplicates_fuzzy(&mut c, "artist", 0, 0.75).unwrap();
+    assert_eq!(strict["count"], 0);
+}
+
+#[test]
+fn test_duplicates_fuzzy_show_romaji_and_vintage() {
+    let mut c = test_conn();
+    let s1 = insert_show_full(&mut c, "K-On!", Some("Keion!"), "Spring 2009");
+    let s2 = insert_show_full(&mut c, "Keion", None, "Spring 2009");
+    insert_show_full(&mut c, "K-On!", Some("Keion!"), "Spring 2010");
+    let r = commands::cmd_duplicates_fuzzy(&mut c, "show", 2, 0.75).unwrap();
+    assert_eq!(r["count"], 1);
+    let pair = &r["pairs"][0];
+    assert_eq!(pair["matched_on"], "name_romaji/name");
+    assert_eq!(pair["records"][0]["id"], s1);
+    assert_eq!(pair["records"][1]["id"], s2);
+    assert_eq!(pair["records"][0]["vintage"], "Spring 2009");
+}
+
+#[test]
+fn test_duplicates_fuzzy_excludes_deleted_and_validates() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "Minami");
+    let a2 = insert_artist(&mut c, "minami");
+    c.execute("UPDATE artist SET status=1 WHERE id=?1", [&a2])
+        .unwrap();
+    let r = commands::cmd_duplicates_fuzzy(&mut c, "artist", 2, 0.75).unwrap();
+    assert_eq!(r["count"], 0);
+    assert_eq!(
+        commands::cmd_duplicates_fuzzy(&mut c, "learning", 2, 0.75)
+            .unwrap_err()
+            .to_string(),
+        "Invalid table: learning. Allowed: artist, show, song"
+    );
+    assert_eq!(
+        commands::cmd_duplicates_fuzzy(&mut c, "artist", 2, 1.5)
+            .unwrap_err()
+            .to_string(),
+        "min_score must be between 0 and 1, got 1.5"
+    );
+}
+
 // === SHOWS BY ARTIST IDS ===
 
 fn insert_rel_show_song(conn: &mut Connection, show_id: &str, song_id: &str) {
@@ -867,7 +1168,7 @@ fn test_shows_by_artist_ids_single_artist() {
     insert_rel_show_song(&mut c, &sh1, &song1);
     insert_rel_show_song(&mut c, &sh2, &song2);
 
-    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1, false).unwrap();
     assert_eq!(r["count"], 2);
     let results = r["results"].as_array().unwrap();
     assert_eq!(results.len(), 2);
@@ -889,7 +1190,7 @@ fn test_shows_by_artist_ids_multiple_artists() {
     insert_rel_show_song(&mut c, &sh1, &song1);
     insert_rel_show_song(&mut c, &sh2, &song2);
 
-    let r = commands::cmd_shows_by_artist_ids(&mut c, &format!("{a1},{a2}")).unwrap();
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &format!("{a1},{a2}"), false).unwrap();
     assert_eq!(r["count"], 2);
     let results = r["results"].as_array().unwrap();
     // Should include results from both artists
@@ -907,7 +1208,7 @@ fn test_shows_by_artist_ids_artist_with_no_shows() {
     let a1 = insert_artist(&mut c, "No Shows Artist");
     insert_song(&mut c, "Orphan Song", &a1); // song but no rel_show_song
 
-    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1, false).unwrap();
     assert_eq!(r["count"], 0);
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
 }
@@ -922,7 +1223,7 @@ fn test_shows_by_artist_ids_same_show_different_songs() {
     insert_rel_show_song(&mut c, &sh1, &song1);
     insert_rel_show_song(&mut c, &sh1, &song2);
 
-    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1, false).unwrap();
     // Two rows because two different songs link to the same show
     assert_eq!(r["count"], 2);
     let results = r["results"].as_array().unwrap();
@@ -940,7 +1241,7 @@ fn test_shows_by_artist_ids_same_show_different_songs() {
 #[test]
 fn test_shows_by_artist_ids_empty_input() {
     let mut c = test_conn();
-    let err = commands::cmd_shows_by_artist_ids(&mut c, "")
+    let err = commands::cmd_shows_by_artist_ids(&mut c, "", false)
         .unwrap_err()
         .to_string();
     assert_eq!(err, "artist_ids cannot be empty");
@@ -949,7 +1250,7 @@ fn test_shows_by_artist_ids_empty_input() {
 #[test]
 fn test_shows_by_artist_ids_nonexistent_artist() {
     let mut c = test_conn();
-    let r = commands::cmd_shows_by_artist_ids(&mut c, "nonexistent-uuid").unwrap();
+    let r = commands::cmd_shows_by_artist_ids(&mut c, "nonexistent-uuid", false).unwrap();
     assert_eq!(r["count"], 0);
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
 }
@@ -962,7 +1263,7 @@ fn test_shows_by_artist_ids_returns_correct_fields() {
     let song1 = insert_song(&mut c, "TestSong", &a1);
     insert_rel_show_song(&mut c, &sh1, &song1);
 
-    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1, false).unwrap();
     let row = &r["results"][0];
     assert_eq!(row["show_id"], sh1);
     assert_eq!(row["show_name"], "TestShow");
@@ -982,7 +1283,7 @@ fn test_songs_by_artist_ids_single_artist() {
     insert_song(&mut c, "Crying for Rain", &a1);
     insert_song(&mut c, "Viva La Vida", &a1);
 
-    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1, false, false).unwrap();
     assert_eq!(r["count"], 2);
     let results = r["results"].as_array().unwrap();
     assert_eq!(results.len(), 2);
@@ -1000,7 +1301,7 @@ fn test_songs_by_artist_ids_multiple_artists() {
     insert_song(&mut c, "Song A2", &a1);
     insert_song(&mut c, "Song B1", &a2);
 
-    let r = commands::cmd_songs_by_artist_ids(&mut c, &format!("{a1},{a2}")).unwrap();
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &format!("{a1},{a2}"), false, false).unwrap();
     assert_eq!(r["count"], 3);
     let results = r["results"].as_array().unwrap();
     let artist_ids: Vec<&str> = results
@@ -1016,7 +1317,7 @@ fn test_songs_by_artist_ids_artist_with_no_songs() {
     let mut c = test_conn();
     let a1 = insert_artist(&mut c, "No Songs Artist");
 
-    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1, false, false).unwrap();
     assert_eq!(r["count"], 0);
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
 }
@@ -1024,7 +1325,7 @@ fn test_songs_by_artist_ids_artist_with_no_songs() {
 #[test]
 fn test_songs_by_artist_ids_empty_input() {
     let mut c = test_conn();
-    let err = commands::cmd_songs_by_artist_ids(&mut c, "")
+    let err = commands::cmd_songs_by_artist_ids(&mut c, "", false, false)
         .unwrap_err()
         .to_string();
     assert_eq!(err, "artist_ids cannot be empty");
@@ -1033,7 +1334,7 @@ fn test_songs_by_artist_ids_empty_input() {
 #[test]
 fn test_songs_by_artist_ids_nonexistent_artist() {
     let mut c = test_conn();
-    let r = commands::cmd_songs_by_artist_ids(&mut c, "nonexistent-uuid").unwrap();
+    let r = commands::cmd_songs_by_artist_ids(&mut c, "nonexistent-uuid", false, false).unwrap();
     assert_eq!(r["count"], 0);
     assert_eq!(r["results"].as_array().unwrap().len(), 0);
 }
@@ -1044,7 +1345,7 @@ fn test_songs_by_artist_ids_returns_correct_fields() {
     let a1 = insert_artist(&mut c, "FieldTest");
     let song1 = insert_song(&mut c, "TestSong", &a1);
 
-    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1).unwrap();
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1, false, false).unwrap();
     let row = &r["results"][0];
     assert_eq!(row["song_id"], song1);
     assert_eq!(row["song_name"], "TestSong");
@@ -1061,7 +1362,7 @@ fn test_songs_by_artist_ids_ordered_by_artist_then_song() {
     insert_song(&mut c, "Gamma", &a2);
     insert_song(&mut c, "Beta", &a2);
 
-    let r = commands::cmd_songs_by_artist_ids(&mut c, &format!("{a1},{a2}")).unwrap();
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &format!("{a1},{a2}"), false, false).unwrap();
     let results = r["results"].as_array().unwrap();
     // Alpha's songs first (alphabetical by artist name), then Bravo's
     assert_eq!(results[0]["artist_name"], "Alpha");
@@ -1071,3 +1372,1210 @@ fn test_songs_by_artist_ids_ordered_by_artist_then_song() {
     assert_eq!(results[2]["artist_name"], "Bravo");
     assert_eq!(results[2]["song_name"], "Zeta");
 }
+
+// === SONGS BY COMPOSER IDS ===
+
+fn insert_credit(conn: &mut Connection, song_id: &str, artist_id: &str, role: &str) {
+    conn.execute(
+        "INSERT INTO song_credit (id, song_id, artist_id, role) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![uuid::Uuid::new_v4().to_string(), song_id, artist_id, role],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_songs_by_composer_ids_with_arrangers() {
+    let mut c = test_conn();
+    let ryo = insert_artist(&mut c, "ryo (supercell)");
+    let tia = insert_artist(&mut c, "Tia");
+    let kokia = insert_artist(&mut c, "KOKIA");
+    let composed = insert_song(&mut c, "Chotto Dekakete Kimasu", &tia);
+    let arranged = insert_song(&mut c, "tell tell Bouzu", &kokia);
+    let deleted = insert_song(&mut c, "Gone", &tia);
+    insert_credit(&mut c, &composed, &ryo, "composer");
+    insert_credit(&mut c, &composed, &ryo, "arranger");
+    insert_credit(&mut c, &arranged, &ryo, "arranger");
+    insert_credit(&mut c, &deleted, &ryo, "composer");
+    commands::cmd_soft_delete(&mut c, "song", &deleted).unwrap();
+
+    let r = commands::cmd_songs_by_composer_ids(&mut c, &ryo, false, false).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(
+        r["results"][0],
+        serde_json::json!({
+            "credit_artist_id": ryo,
+            "credit_artist_name": "ryo (supercell)",
+            "role": "composer",
+            "song_id": composed,
+            "song_name": "Chotto Dekakete Kimasu",
+            "artist_id": tia,
+            "artist_name": "Tia"
+        })
+    );
+
+    let r = commands::cmd_songs_by_composer_ids(&mut c, &ryo, true, false).unwrap();
+    let rows: Vec<(&str, &str)> = r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["role"].as_str().unwrap(),
+                row["song_name"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("composer", "Chotto Dekakete Kimasu"),
+            ("arranger", "Chotto Dekakete Kimasu"),
+            ("arranger", "tell tell Bouzu"),
+        ]
+    );
+    let r = commands::cmd_songs_by_composer_ids(&mut c, &ryo, false, true).unwrap();
+    assert_eq!(r["count"], 2);
+
+    assert_eq!(
+        commands::cmd_songs_by_composer_ids(&mut c, " , ", false, false)
+            .unwrap_err()
+            .to_string(),
+        "composer_ids cannot be empty"
+    );
+}
+
+#[test]
+fn test_search_songs_joined_on_song_credit() {
+    let mut c = test_conn();
+    let ryo = insert_artist(&mut c, "ryo");
+    let tia = insert_artist(&mut c, "Tia");
+    let credited = insert_song(&mut c, "Credited", &tia);
+    insert_song(&mut c, "Uncredited", &tia);
+    insert_credit(&mut c, &credited, &ryo, "composer");
+
+    let term = format!(r#"{{"song_credit.artist_id": {{"value": "{ryo}"}}}}"#);
+    let r = commands::cmd_search(
+        &mut c,
+        "song",
+        &term,
+        "name,artist.name,song_credit.role",
+        &SearchOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        r["results"],
+        serde_json::json!([{"name": "Credited", "artist.name": "Tia", "song_credit.role": "composer"}])
+    );
+}
+
+// === SONGS BY SHOW IDS ===
+
+#[test]
+fn test_songs_by_show_ids_with_media_and_learning_status() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "Aimer");
+    let sh1 = insert_show(&mut c, "Show One", "Winter 2024");
+    let sh2 = insert_show(&mut c, "Show Two", "Spring 2024");
+    let learning = insert_song(&mut c, "Alpha", &a1);
+    let graduated = insert_song(&mut c, "Beta", &a1);
+    let fresh = insert_song(&mut c, "Gamma", &a1);
+    insert_rel_show_song(&mut c, &sh1, &learning);
+    insert_rel_show_song(&mut c, &sh1, &graduated);
+    insert_rel_show_song(&mut c, &sh2, &fresh);
+    c.execute(
+        "UPDATE rel_show_song SET media_url = 'https://example.com/a.mp4' WHERE song_id = ?1",
+        [&learning],
+    )
+    .unwrap();
+    insert_learning(&mut c, &learning, 4, 0, 0);
+    insert_learning(&mut c, &graduated, 19, 0, 1);
+
+    let r = commands::cmd_songs_by_show_ids(&mut c, &format!("{sh1}, {sh2}"), false).unwrap();
+    assert_eq!(r["count"], 3);
+    let rows = r["results"].as_array().unwrap();
+    assert_eq!(rows[0]["song_name"], "Alpha");
+    assert_eq!(rows[0]["show_name"], "Show One");
+    assert_eq!(rows[0]["artist_name"], "Aimer");
+    assert_eq!(rows[0]["media_url"], "https://example.com/a.mp4");
+    assert_eq!(rows[0]["learning_status"], "learning");
+    assert_eq!(rows[0]["learning_level"], 4);
+    assert_eq!(rows[1]["learning_status"], "graduated");
+    assert_eq!(rows[1]["learning_level"], serde_json::Value::Null);
+    assert_eq!(rows[2]["show_id"], sh2);
+    assert_eq!(rows[2]["media_url"], "");
+    assert_eq!(rows[2]["learning_status"], "none");
+    assert_eq!(rows[2]["song_type"], "");
+    assert_eq!(rows[2]["type_number"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_songs_by_show_ids_hides_soft_deleted_and_validates() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "A");
+    let sh1 = insert_show(&mut c, "Show", "Winter 2024");
+    let song = insert_song(&mut c, "Song", &a1);
+    insert_rel_show_song(&mut c, &sh1, &song);
+    c.execute("UPDATE song SET status = 1 WHERE id = ?1", [&song])
+        .unwrap();
+    assert_eq!(
+        commands::cmd_songs_by_show_ids(&mut c, &sh1, false).unwrap()["count"],
+        0
+    );
+    assert_eq!(
+        commands::cmd_songs_by_show_ids(&mut c, &sh1, true).unwrap()["count"],
+        1
+    );
+    assert_eq!(
+        commands::cmd_songs_by_show_ids(&mut c, " , ", false)
+            .unwrap_err()
+            .to_string(),
+        "show_ids cannot be empty"
+    );
+}
+
+// === SHOWS BY VINTAGE ===
+
+#[test]
+fn test_search_shows_chronologically_by_vintage_key() {
+    let mut c = test_conn();
+    insert_show(&mut c, "Fall", "Fall 2023");
+    insert_show(&mut c, "Winter", "Winter 2024");
+    insert_show(&mut c, "Summer", "Summer 2023");
+    insert_show(&mut c, "Old", "Spring 2010");
+    let opts = SearchOptions {
+        order_by: Some("vintage_key:desc".into()),
+        ..Default::default()
+    };
+    let r = commands::cmd_search(
+        &mut c,
+        "show",
+        r#"{"year": {"value": [2023, 2024], "match": "between"}}"#,
+        "name,season,year",
+        &opts,
+    )
+    .unwrap();
+    let names: Vec<&str> = r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Winter", "Fall", "Summer"]);
+    assert_eq!(r["results"][0]["season"], "Winter");
+    assert_eq!(r["results"][0]["year"], 2024);
+}
+
+#[test]
+fn test_shows_by_vintage_season() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "A");
+    let sh1 = insert_show(&mut c, "Zeta", "Winter 2024");
+    insert_show(&mut c, "Alpha", "winter 2024");
+    insert_show(&mut c, "Other", "Spring 2024");
+    insert_show(&mut c, "Unparsed", "2024");
+    let song = insert_song(&mut c, "Song", &a1);
+    insert_rel_show_song(&mut c, &sh1, &song);
+
+    let r = commands::cmd_shows_by_vintage(&mut c, Some("2024 Winter"), None, false).unwrap();
+    assert_eq!(r["count"], 2);
+    let rows = r["results"].as_array().unwrap();
+    assert_eq!(rows[0]["show_name"], "Alpha");
+    assert_eq!(rows[0]["vintage"], "winter 2024");
+    assert_eq!(rows[0]["season"], "Winter");
+    assert_eq!(rows[0]["year"], 2024);
+    assert_eq!(rows[1]["show_id"], sh1);
+    assert_eq!(rows[1]["song_count"], 1);
+}
+
+#[test]
+fn test_shows_by_vintage_year_range_is_chronological() {
+    let mut c = test_conn();
+    insert_show(&mut c, "Late", "Fall 2021");
+    insert_show(&mut c, "Early", "Spring 2020");
+    insert_show(&mut c, "Middle", "Autumn 2020");
+    insert_show(&mut c, "Before", "Winter 2019");
+    let deleted = insert_show(&mut c, "Gone", "Summer 2020");
+    c.execute("UPDATE show SET status = 1 WHERE id = ?1", [&deleted])
+        .unwrap();
+
+    let r = commands::cmd_shows_by_vintage(&mut c, None, Some("2020-2021"), false).unwrap();
+    let names: Vec<&str> = r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["show_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Early", "Middle", "Late"]);
+
+    let r = commands::cmd_shows_by_vintage(&mut c, None, Some("2020"), true).unwrap();
+    assert_eq!(r["count"], 3);
+    assert_eq!(r["results"][1]["show_name"], "Gone");
+}
+
+#[test]
+fn test_shows_by_vintage_errors() {
+    let mut c = test_conn();
+    assert_eq!(
+        commands::cmd_shows_by_vintage(&mut c, None, None, false)
+            .unwrap_err()
+            .to_string(),
+        "shows-by-vintage requires --season or --year-range"
+    );
+    assert_eq!(
+        commands::cmd_shows_by_vintage(&mut c, Some("Monsoon 2024"), None, false)
+            .unwrap_err()
+            .to_string(),
+        "Invalid season: Monsoon 2024. Expected <season> <year>, e.g. \"Winter 2024\" (seasons: Winter, Spring, Summer, Fall)"
+    );
+    assert!(
+        commands::cmd_shows_by_vintage(&mut c, None, Some("2024-2020"), false)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid year range")
+    );
+}
+
+// === SOFT-DELETE FILTERING ===
+
+#[test]
+fn test_get_excludes_soft_deleted_unless_included() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Gone");
+    commands::cmd_soft_delete(&mut c, "artist", &aid).unwrap();
+    let r = commands::cmd_get(&mut c, "artist", &aid, "id", false).unwrap();
+    assert_eq!(r["results"], serde_json::json!([]));
+    let r = commands::cmd_get(&mut c, "artist", &aid, "id,status", true).unwrap();
+    assert_eq!(r["results"][0]["status"], 1);
+}
+
+#[test]
+fn test_batch_get_and_search_exclude_soft_deleted() {
+    let mut c = test_conn();
+    let keep = insert_artist(&mut c, "Aimer");
+    let gone = insert_artist(&mut c, "Aimer");
+    commands::cmd_soft_delete(&mut c, "artist", &gone).unwrap();
+
+    let ids = format!("{keep},{gone}");
+    let r = commands::cmd_batch_get(&mut c, "artist", &ids, "id", false).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["results"][0]["id"], keep);
+    let r = commands::cmd_batch_get(&mut c, "artist", &ids, "id", true).unwrap();
+    assert_eq!(r["count"], 2);
+
+    let term = r#"{"name": {"value": "Aimer"}}"#;
+    let r = commands::cmd_search(&mut c, "artist", term, "id", &SearchOptions::default()).unwrap();
+    assert_eq!(r["results"], serde_json::json!([{"id": keep}]));
+    let r = commands::cmd_search(
+        &mut c,
+        "artist",
+        term,
+        "id",
+        &SearchOptions {
+            include_deleted: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(r["results"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_search_learning_unaffected_by_include_deleted() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    insert_learning(&mut c, &sid, 0, 0, 0);
+    let term = format!(r#"{{"song_id": {{"value": "{sid}"}}}}"#);
+    let r = commands::cmd_search(
+        &mut c,
+        "learning",
+        &term,
+        "song_id",
+        &SearchOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(r["results"], serde_json::json!([{"song_id": sid}]));
+}
+
+#[test]
+fn test_songs_by_artist_ids_excludes_soft_deleted_song() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "Minami");
+    insert_song(&mut c, "Crying for Rain", &a1);
+    let gone = insert_song(&mut c, "Lost Song", &a1);
+    commands::cmd_soft_delete(&mut c, "song", &gone).unwrap();
+
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1, false, false).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["results"][0]["song_name"], "Crying for Rain");
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &a1, false, true).unwrap();
+    assert_eq!(r["count"], 2);
+}
+
+#[test]
+fn test_songs_by_artist_ids_include_groups() {
+    let mut c = test_conn();
+    let sora = insert_artist(&mut c, "Sora Amamiya");
+    let hitomi = insert_artist(&mut c, "Hitomi Sasaki");
+    let group = insert_artist(&mut c, "Saint Spica Jogakuin Seika-tai");
+    let former = insert_artist(&mut c, "Former Unit");
+    insert_song(&mut c, "Skyreach", &sora);
+    insert_song(&mut c, "Hoshi no Kodou", &group);
+    insert_song(&mut c, "Old Single", &former);
+    commands::cmd_add_member(&mut c, &group, &format!("{sora},{hitomi}")).unwrap();
+    commands::cmd_add_member(&mut c, &former, &sora).unwrap();
+    commands::cmd_soft_delete(&mut c, "artist", &former).unwrap();
+
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &sora, false, false).unwrap();
+    assert_eq!(r["count"], 1);
+    assert!(r["results"][0].get("via_artist_id").is_none());
+
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &sora, true, false).unwrap();
+    let rows: Vec<(&str, &str)> = r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["song_name"].as_str().unwrap(),
+                row["via_artist_id"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("Hoshi no Kodou", sora.as_str()),
+            ("Skyreach", sora.as_str())
+        ]
+    );
+
+    // Asking for a member and the group itself reports the group song once, directly
+    let r =
+        commands::cmd_songs_by_artist_ids(&mut c, &format!("{sora},{hitomi},{group}"), true, false)
+            .unwrap();
+    assert_eq!(r["count"], 2);
+    assert_eq!(r["results"][0]["via_artist_id"], group);
+
+    let r = commands::cmd_songs_by_artist_ids(&mut c, &sora, true, true).unwrap();
+    assert_eq!(r["count"], 3);
+}
+
+#[test]
+fn test_shows_by_artist_ids_excludes_soft_deleted_show() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "Minami");
+    let sh1 = insert_show(&mut c, "Boku no Hero", "Spring 2016");
+    let sh2 = insert_show(&mut c, "Gone Show", "Spring 2019");
+    let song = insert_song(&mut c, "Crying for Rain", &a1);
+    insert_rel_show_song(&mut c, &sh1, &song);
+    insert_rel_show_song(&mut c, &sh2, &song);
+    commands::cmd_soft_delete(&mut c, "show", &sh2).unwrap();
+
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1, false).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["results"][0]["show_id"], sh1);
+    let r = commands::cmd_shows_by_artist_ids(&mut c, &a1, true).unwrap();
+    assert_eq!(r["count"], 2);
+}
+
+// === FSCK ===
+
+#[test]
+fn test_fsck_clean_database() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    insert_song(&mut c, "S", &aid);
+    let r = commands::cmd_fsck(&mut c).unwrap();
+    assert_eq!(r["ok"], true);
+    assert_eq!(r["orphan_count"], 0);
+}
+
+#[test]
+fn test_fsck_reports_orphans() {
+    let mut c = test_conn();
+    let song = insert_song(&mut c, "Orphan", "ghost-artist");
+    let show = insert_show(&mut c, "Sh", "2024");
+    let ph = insert_play_history(&mut c, &show, "ghost-song", "");
+    let r = commands::cmd_fsck(&mut c).unwrap();
+    assert_eq!(r["ok"], false);
+    assert_eq!(r["orphan_count"], 2);
+    assert_eq!(
+        r["orphans"],
+        serde_json::json!([
+            {"table": "song", "id": song, "field": "artist_id", "references": "artist", "missing_id": "ghost-artist"},
+            {"table": "play_history", "id": ph, "field": "song_id", "references": "song", "missing_id": "ghost-song"}
+        ])
+    );
+}
+
+// === SEARCH PAGINATION / ORDERING ===
+
+#[test]
+fn test_search_total_without_paging() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "Aimer");
+    insert_artist(&mut c, "Aimyon");
+    let term = r#"{"name": {"value": "aim", "match": "starts-with"}}"#;
+    let r =
+        commands::cmd_search(&mut c, "artist", term, "name", &SearchOptions::default()).unwrap();
+    assert_eq!(r["total"], 2);
+    assert_eq!(r["count"], 2);
+    assert_eq!(r["next_offset"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_search_order_by_limit_offset() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "Aimer");
+    insert_artist(&mut c, "Aimyon");
+    insert_artist(&mut c, "Aina");
+    let term = r#"{"name": {"value": "ai", "match": "starts-with"}}"#;
+    let mut opts = SearchOptions {
+        limit: Some(2),
+        order_by: Some("name:desc".into()),
+        ..Default::default()
+    };
+    let r = commands::cmd_search(&mut c, "artist", term, "name", &opts).unwrap();
+    assert_eq!(r["total"], 3);
+    assert_eq!(
+        r["results"],
+        serde_json::json!([{"name": "Aina"}, {"name": "Aimyon"}])
+    );
+    assert_eq!(r["next_offset"], 2);
+
+    opts.offset = 2;
+    let r = commands::cmd_search(&mut c, "artist", term, "name", &opts).unwrap();
+    assert_eq!(r["results"], serde_json::json!([{"name": "Aimer"}]));
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["next_offset"], serde_json::Value::Null);
+}
+
+#[test]
+fn test_search_offset_without_limit() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "B");
+    insert_artist(&mut c, "A");
+    let term = r#"{"name": {"value": "", "match": "contains"}}"#;
+    let opts = SearchOptions {
+        offset: 1,
+        order_by: Some("name".into()),
+        ..Default::default()
+    };
+    let r = commands::cmd_search(&mut c, "artist", term, "name", &opts).unwrap();
+    assert_eq!(r["results"], serde_json::json!([{"name": "B"}]));
+}
+
+#[test]
+fn test_search_paging_errors() {
+    let mut c = test_conn();
+    let term = r#"{"name": {"value": "x"}}"#;
+    let opts = SearchOptions {
+        order_by: Some("password".into()),
+        ..Default::default()
+    };
+    assert!(
+        commands::cmd_search(&mut c, "artist", term, "name", &opts)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Invalid order-by field: password")
+    );
+    let opts = SearchOptions {
+        limit: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        commands::cmd_search(&mut c, "artist", term, "name", &opts)
+            .unwrap_err()
+            .to_string(),
+        "limit must be at least 1"
+    );
+}
+
+#[test]
+fn test_search_rel_show_song_paged_by_composite_key() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let s1 = insert_song(&mut c, "S1", &aid);
+    let s2 = insert_song(&mut c, "S2", &aid);
+    let sh = insert_show(&mut c, "Sh", "2024");
+    insert_rel_show_song(&mut c, &sh, &s1);
+    insert_rel_show_song(&mut c, &sh, &s2);
+    let term = format!(r#"{{"show_id": {{"value": "{sh}"}}}}"#);
+    let opts = SearchOptions {
+        limit: Some(1),
+        ..Default::default()
+    };
+    let r = commands::cmd_search(&mut c, "rel_show_song", &term, "song_id", &opts).unwrap();
+    assert_eq!(r["total"], 2);
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["next_offset"], 1);
+}
+
+// === SEARCH TERM OPERATORS ===
+
+fn search_names(c: &mut Connection, table: &str, term: &str) -> Vec<String> {
+    let r = commands::cmd_search(
+        c,
+        table,
+        term,
+        "name",
+        &SearchOptions {
+            order_by: Some("name".into()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_search_any_and_not_groups() {
+    let mut c = test_conn();
+    insert_artist(&mut c, "Aimer");
+    insert_artist(&mut c, "LiSA");
+    insert_artist(&mut c, "Eir Aoi");
+    let term = r#"{"any": [{"name": {"value": "aim", "match": "starts-with"}},
+                          {"name": {"value": "lisa", "match": "exact-i"}}]}"#;
+    assert_eq!(search_names(&mut c, "artist", term), vec!["Aimer", "LiSA"]);
+
+    let term = r#"{"not": {"name": {"value": "ai", "match": "contains"}}}"#;
+    assert_eq!(
+        search_names(&mut c, "artist", term),
+        vec!["Eir Aoi", "LiSA"]
+    );
+
+    let term = r#"{"all": [{"name": {"value": "a", "match": "contains"}},
+                          {"not": {"name": {"value": "LiSA"}}}]}"#;
+    assert_eq!(
+        search_names(&mut c, "artist", term),
+        vec!["Aimer", "Eir Aoi"]
+    );
+}
+
+#[test]
+fn test_search_in_and_empty_checks() {
+    let mut c = test_conn();
+    insert_show_full(&mut c, "A", Some("a romaji"), "Fall 2024");
+    insert_show_full(&mut c, "B", None, "Fall 2024");
+    insert_show_full(&mut c, "C", Some(""), "Winter 2025");
+    let term = r#"{"name_romaji": {"match": "is-empty"}}"#;
+    assert_eq!(search_names(&mut c, "show", term), vec!["B", "C"]);
+    let term = r#"{"name_romaji": {"match": "is-null"}}"#;
+    assert_eq!(search_names(&mut c, "show", term), vec!["B"]);
+    let term = r#"{"name": {"value": ["A", "C", "Z"], "match": "in"}}"#;
+    assert_eq!(search_names(&mut c, "show", term), vec!["A", "C"]);
+}
+
+#[test]
+fn test_search_numeric_comparisons() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let low = insert_song(&mut c, "S1", &aid);
+    let mid = insert_song(&mut c, "S2", &aid);
+    let high = insert_song(&mut c, "S3", &aid);
+    insert_learning(&mut c, &low, 1, 100, 0);
+    insert_learning(&mut c, &mid, 8, 200, 0);
+    insert_learning(&mut c, &high, 15, 300, 0);
+
+    let song_ids = |term: &str, c: &mut Connection| -> Vec<String> {
+        let r = commands::cmd_search(
+            c,
+            "learning",
+            term,
+            "song_id",
+            &SearchOptions {
+                order_by: Some("level".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        r["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row["song_id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    // Numeric values compare numerically (9 > 10 as text would be wrong)
+    assert_eq!(
+        song_ids(r#"{"level": {"value": 5, "match": "gt"}}"#, &mut c),
+        vec![mid.clone(), high.clone()]
+    );
+    assert_eq!(
+        song_ids(
+            r#"{"level": {"value": [1, 8], "match": "between"}}"#,
+            &mut c
+        ),
+        vec![low.clone(), mid.clone()]
+    );
+    assert_eq!(
+        song_ids(
+            r#"{"last_level_up_at": {"value": 200, "match": "lte"}, "level": {"value": 1, "match": "gte"}}"#,
+            &mut c
+        ),
+        vec![low, mid]
+    );
+    assert_eq!(
+        song_ids(
+            r#"{"level": {"value": 15, "match": "lt"}, "song_id": {"value": "none"}}"#,
+            &mut c
+        ),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn test_search_group_errors() {
+    let mut c = test_conn();
+    let err = |term: &str, c: &mut Connection| {
+        commands::cmd_search(c, "artist", term, "name", &SearchOptions::default())
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        err(r#"{"any": {"name": {"value": "x"}}}"#, &mut c),
+        "'any' must be a non-empty array of terms"
+    );
+    assert_eq!(
+        err(r#"{"any": ["name"]}"#, &mut c),
+        "Entries of 'any' must be term objects"
+    );
+    assert_eq!(
+        err(r#"{"not": {"id": {"value": "x"}}}"#, &mut c),
+        "Invalid term key for artist: id. Allowed: name, name_context, created_at"
+    );
+    assert!(
+        err(r#"{"name": {"value": "x", "match": "in"}}"#, &mut c)
+            .contains("must have a non-empty 'value' array")
+    );
+}
+
+// === JOINED SEARCH ===
+
+#[test]
+fn test_search_joined_across_tables() {
+    let mut c = test_conn();
+    let aimer = insert_artist(&mut c, "Aimer");
+    let lisa = insert_artist(&mut c, "LiSA");
+    let s1 = insert_song(&mut c, "S1", &aimer);
+    let s2 = insert_song(&mut c, "S2", &aimer);
+    let s3 = insert_song(&mut c, "S3", &lisa);
+    let winter = insert_show(&mut c, "Winter Show", "Winter 2024");
+    let winter2 = insert_show(&mut c, "Other Winter Show", "Winter 2024");
+    let spring = insert_show(&mut c, "Spring Show", "Spring 2024");
+    insert_rel_show_song(&mut c, &winter, &s1);
+    insert_rel_show_song(&mut c, &winter2, &s1);
+    insert_rel_show_song(&mut c, &winter, &s2);
+    insert_rel_show_song(&mut c, &spring, &s3);
+    insert_learning(&mut c, &s2, 0, 0, 0);
+
+    // Songs by Aimer in Winter 2024 shows that are not yet learning
+    let term = r#"{"artist.name": {"value": "aimer", "match": "exact-i"},
+                   "show.vintage": {"value": "Winter 2024"},
+                   "learning.song_id": {"match": "is-null"}}"#;
+    let r = commands::cmd_search(
+        &mut c,
+        "song",
+        term,
+        "id,name,artist.name",
+        &SearchOptions::default(),
+    )
+    .unwrap();
+    // S1 appears in two matching shows but is returned once
+    assert_eq!(r["total"], 1);
+    assert_eq!(r["results"][0]["id"], s1.as_str());
+    assert_eq!(r["results"][0]["artist.name"], "Aimer");
+}
+
+#[test]
+fn test_search_joined_fields_and_order() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Aimer");
+    let s1 = insert_song(&mut c, "S1", &aid);
+    let a = insert_show(&mut c, "A", "Winter 2024");
+    let b = insert_show(&mut c, "B", "Winter 2024");
+    insert_rel_show_song(&mut c, &a, &s1);
+    insert_rel_show_song(&mut c, &b, &s1);
+
+    let opts = SearchOptions {
+        order_by: Some("show.name:desc".into()),
+        limit: Some(1),
+        ..Default::default()
+    };
+    let r = commands::cmd_search(
+        &mut c,
+        "artist",
+        r#"{"name": {"value": "Aimer"}}"#,
+        "name,song.name,show.name",
+        &opts,
+    )
+    .unwrap();
+    assert_eq!(r["total"], 2);
+    assert_eq!(
+        r["results"],
+        serde_json::json!([{"name": "Aimer", "song.name": "S1", "show.name": "B"}])
+    );
+    assert_eq!(r["next_offset"], 1);
+}
+
+#[test]
+fn test_search_joined_hides_soft_deleted_related_rows() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "A");
+    let sid = insert_song(&mut c, "S", &aid);
+    let show = insert_show(&mut c, "Sh", "2024");
+    insert_rel_show_song(&mut c, &show, &sid);
+    c.execute("UPDATE show SET status = 1 WHERE id = ?1", [&show])
+        .unwrap();
+
+    let term = r#"{"show.name": {"value": "Sh"}}"#;
+    let r = commands::cmd_search(&mut c, "song", term, "name", &SearchOptions::default()).unwrap();
+    assert_eq!(r["total"], 0);
+    let opts = SearchOptions {
+        include_deleted: true,
+        ..Default::default()
+    };
+    let r = commands::cmd_search(&mut c, "song", term, "name", &opts).unwrap();
+    assert_eq!(r["total"], 1);
+}
+
+#[test]
+fn test_search_joined_errors() {
+    let mut c = test_conn();
+    let err = |term: &str, fields: &str, order_by: Option<&str>, c: &mut Connection| {
+        let opts = SearchOptions {
+            order_by: order_by.map(String::from),
+            ..Default::default()
+        };
+        commands::cmd_search(c, "song", term, fields, &opts)
+            .unwrap_err()
+            .to_string()
+    };
+    assert_eq!(
+        err(r#"{"bad.name": {"value": "x"}}"#, "name", None, &mut c),
+        "Invalid table in term key: bad.name. Allowed: artist, show, song, play_history, rel_show_song, learning, song_credit, artist_member, artist_alias, show_alias, game_session"
+    );
+    assert_eq!(
+        err(r#"{"artist.id": {"value": "x"}}"#, "name", None, &mut c),
+        "Invalid term key for artist: id. Allowed: name, name_context, created_at"
+    );
+    assert_eq!(
+        err(
+            r#"{"name": {"value": "x"}}"#,
+            "artist.password",
+            None,
+            &mut c
+        ),
+        "Invalid field for artist: password. Allowed: id, name, name_context, created_at, updated_at, status"
+    );
+    assert_eq!(
+        err(
+            r#"{"name": {"value": "x"}}"#,
+            "name,artist.name",
+            Some("id"),
+            &mut c
+        ),
+        "Invalid order-by field: id. Allowed: name, artist.name"
+    );
+}
+
+#[test]
+fn test_search_normalized_matches_kana_and_romaji() {
+    let mut c = test_conn();
+    let aid = commands::cmd_create(&mut c, "artist", r#"{"name":"とうきょう"}"#).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    commands::cmd_create(
+        &mut c,
+        "song",
+        &format!(r#"{{"name":"ふわふわ時間","artist_id":"{aid}"}}"#),
+    )
+    .unwrap();
+    commands::cmd_create(
+        &mut c,
+        "show",
+        r#"{"name":"K-On!","name_romaji":"Keion!","vintage":"Spring 2009"}"#,
+    )
+    .unwrap();
+    let opts = SearchOptions::default();
+
+    for value in ["Tokyo", "T%C5%8Dky%C5%8D", "TOUKYOU", "トーキョー"] {
+        let term = format!(r#"{{"name": {{"value": "{value}", "match": "normalized"}}}}"#);
+        let r = commands::cmd_search(&mut c, "artist", &term, "name", &opts).unwrap();
+        assert_eq!(r["results"][0]["name"], "とうきょう", "{value}");
+    }
+    let r = commands::cmd_search(
+        &mut c,
+        "song",
+        r#"{"name": {"value": "フワフワ時間", "match": "normalized"}}"#,
+        "name",
+        &opts,
+    )
+    .unwrap();
+    assert_eq!(r["count"], 1);
+    let r = commands::cmd_search(
+        &mut c,
+        "show",
+        r#"{"name_romaji": {"value": "けいおん", "match": "normalized"}}"#,
+        "name",
+        &opts,
+    )
+    .unwrap();
+    assert_eq!(r["results"][0]["name"], "K-On!");
+
+    // Joined keys compare the related table's key column
+    let r = commands::cmd_search(
+        &mut c,
+        "song",
+        r#"{"artist.name": {"value": "Tokyo", "match": "normalized"}}"#,
+        "name,artist.name",
+        &opts,
+    )
+    .unwrap();
+    assert_eq!(r["results"][0]["artist.name"], "とうきょう");
+}
+
+#[test]
+fn test_search_normalized_unsupported_column() {
+    let mut c = test_conn();
+    let err = commands::cmd_search(
+        &mut c,
+        "song",
+        r#"{"name_context": {"value": "x", "match": "normalized"}}"#,
+        "name",
+        &SearchOptions::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Match 'normalized' is not supported for song.name_context. Supported: artist.name, show.name, show.name_romaji, song.name, artist_alias.name, show_alias.name"
+    );
+}
+
+#[test]
+fn test_duplicates_fuzzy_pairs_kana_with_romaji() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "ゆうき あおい");
+    let a2 = insert_artist(&mut c, "Yūki Aoi");
+    insert_artist(&mut c, "Aimer");
+    let r = commands::cmd_duplicates_fuzzy(&mut c, "artist", 0, 0.75).unwrap();
+    assert_eq!(r["count"], 1);
+    let ids: Vec<&str> = r["pairs"][0]["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rec| rec["id"].as_str().unwrap())
+        .collect();
+    assert!(ids.contains(&a1.as_str()) && ids.contains(&a2.as_str()));
+    assert_eq!(r["pairs"][0]["score"], 1.0);
+}
+
+// === FIND ===
+
+#[test]
+fn test_find_ranks_across_entity_types() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Aimer");
+    let sid = insert_song(&mut c, "Kataomoi", &aid);
+    insert_show_full(
+        &mut c,
+        "A Sign of Affection",
+        Some("Yubisaki to Renren"),
+        "Winter 2024",
+    );
+
+    let r = commands::cmd_find(&mut c, "aim", None, 20, false).unwrap();
+    assert_eq!(r["count"], 1);
+    assert_eq!(r["results"][0]["type"], "artist");
+    assert_eq!(r["results"][0]["id"], aid.as_str());
+    assert!(r["results"][0]["score"].as_f64().unwrap() > 0.0);
+
+    // Prefix matching per word, on the romaji name
+    let r = commands::cmd_find(&mut c, "yubi ren", None, 20, false).unwrap();
+    assert_eq!(r["results"][0]["type"], "show");
+    assert_eq!(r["results"][0]["name"], "A Sign of Affection");
+    assert_eq!(r["results"][0]["matched_field"], "name_romaji");
+
+    let r = commands::cmd_find(&mut c, "KATA", Some("song"), 20, false).unwrap();
+    assert_eq!(r["results"][0]["id"], sid.as_str());
+    let r = commands::cmd_find(&mut c, "kata", Some("artist,show"), 20, false).unwrap();
+    assert_eq!(r["count"], 0);
+}
+
+#[test]
+fn test_find_tracks_updates_deletes_and_diacritics() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Yōko Kanno");
+    let other = insert_artist(&mut c, "Yoko Takahashi");
+    assert_eq!(
+        commands::cmd_find(&mut c, "yoko", None, 20, false).unwrap()["count"],
+        2
+    );
+
+    c.execute("UPDATE artist SET name = 'Kanno' WHERE id = ?1", [&aid])
+        .unwrap();
+    c.execute("DELETE FROM artist WHERE id = ?1", [&other])
+        .unwrap();
+    assert_eq!(
+        commands::cmd_find(&mut c, "yoko", None, 20, false).unwrap()["count"],
+        0
+    );
+    assert_eq!(
+        commands::cmd_find(&mut c, "kanno", None, 20, false).unwrap()["count"],
+        1
+    );
+}
+
+#[test]
+fn test_find_hides_soft_deleted_and_limits() {
+    let mut c = test_conn();
+    let a = insert_artist(&mut c, "Aimer");
+    insert_artist(&mut c, "Aimyon");
+    c.execute("UPDATE artist SET status = 1 WHERE id = ?1", [&a])
+        .unwrap();
+    assert_eq!(
+        commands::cmd_find(&mut c, "aim", None, 20, false).unwrap()["count"],
+        1
+    );
+    assert_eq!(
+        commands::cmd_find(&mut c, "aim", None, 20, true).unwrap()["count"],
+        2
+    );
+    assert_eq!(
+        commands::cmd_find(&mut c, "aim", None, 1, true).unwrap()["count"],
+        1
+    );
+}
+
+#[test]
+fn test_find_errors() {
+    let mut c = test_conn();
+    assert_eq!(
+        commands::cmd_find(&mut c, "!!", None, 20, false)
+            .unwrap_err()
+            .to_string(),
+        "query must contain at least one letter or digit"
+    );
+    assert_eq!(
+        commands::cmd_find(&mut c, "x", Some("learning"), 20, false)
+            .unwrap_err()
+            .to_string(),
+        "Invalid table: learning. Allowed: artist, show, song"
+    );
+    assert_eq!(
+        commands::cmd_find(&mut c, "x", None, 0, false)
+            .unwrap_err()
+            .to_string(),
+        "limit must be at least 1"
+    );
+}
+
+/// Insert a graded play (`correct` None leaves the outcome unknown).
+fn insert_graded_play(
+    conn: &mut Connection,
+    show_id: &str,
+    song_id: &str,
+    correct: Option<bool>,
+    created_at: i64,
+) {
+    conn.execute(
+        "INSERT INTO play_history (id, show_id, song_id, created_at, correct_guess, wrong_guess) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(),
+            show_id,
+            song_id,
+            created_at,
+            correct,
+            correct.map(|c| !c)
+        ],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_play_stats_by_song_and_show() {
+    let mut c = test_conn();
+    let a = insert_artist(&mut c, "ClariS");
+    let hit = insert_song(&mut c, "Connect", &a);
+    let miss = insert_song(&mut c, "Colorful", &a);
+    let show = insert_show(&mut c, "Madoka", "Winter 2011");
+    let t = 1_700_000_000;
+    insert_graded_play(&mut c, &show, &hit, Some(true), t);
+    insert_graded_play(&mut c, &show, &hit, Some(true), t);
+    insert_graded_play(&mut c, &show, &hit, Some(false), t);
+    insert_graded_play(&mut c, &show, &miss, Some(false), t);
+    insert_graded_play(&mut c, &show, &miss, Some(false), t + 60);
+    insert_graded_play(&mut c, &show, &miss, None, t);
+
+    let result = commands::cmd_play_stats(&mut c, "song", 1, None, false).unwrap();
+    assert_eq!(result["summary"]["plays"], 5);
+    assert_eq!(result["summary"]["correct"], 2);
+    assert_eq!(result["summary"]["hit_rate"], 0.4);
+    assert_eq!(result["summary"]["ungraded_plays"], 1);
+    assert_eq!(result["count"], 2);
+    // Most-missed first
+    let worst = &result["results"][0];
+    assert_eq!(worst["song_id"], miss.as_str());
+    assert_eq!(worst["artist_name"], "ClariS");
+    assert_eq!(worst["plays"], 2);
+    assert_eq!(worst["missed"], 2);
+    assert_eq!(worst["wrong"], 2);
+    assert_eq!(worst["hit_rate"], 0.0);
+    assert_eq!(worst["last_played_at"], t + 60);
+    assert_eq!(result["results"][1]["hit_rate"], 0.6667);
+
+    let result = commands::cmd_play_stats(&mut c, "song", 3, None, false).unwrap();
+    assert_eq!(result["count"], 1);
+    assert_eq!(result["results"][0]["song_id"], hit.as_str());
+
+    let result = commands::cmd_play_stats(&mut c, "show", 1, Some(1), false).unwrap();
+    assert_eq!(result["results"][0]["show_name"], "Madoka");
+    assert_eq!(result["results"][0]["vintage"], "Winter 2011");
+    assert_eq!(result["results"][0]["missed"], 3);
+
+    c.execute("UPDATE song SET status = 1 WHERE id = ?1", [&miss])
+        .unwrap();
+    let result = commands::cmd_play_stats(&mut c, "song", 1, None, false).unwrap();
+    assert_eq!(result["count"], 1);
+    assert_eq!(result["summary"]["ungraded_plays"], 0);
+    let result = commands::cmd_play_stats(&mut c, "song", 1, None, true).unwrap();
+    assert_eq!(result["count"], 2);
+}
+
+#[test]
+fn test_play_stats_by_period() {
+    let mut c = test_conn();
+    let a = insert_artist(&mut c, "LiSA");
+    let s = insert_song(&mut c, "Gurenge", &a);
+    let show = insert_show(&mut c, "Kimetsu", "Spring 2019");
+    // 2023-11-14, 2023-12-01 and 2024-01-02 (UTC)
+    insert_graded_play(&mut c, &show, &s, Some(false), 1_699_920_000);
+    insert_graded_play(&mut c, &show, &s, Some(true), 1_701_388_800);
+    insert_graded_play(&mut c, &show, &s, Some(true), 1_704_153_600);
+
+    let result = commands::cmd_play_stats(&mut c, "month", 1, None, false).unwrap();
+    let periods: Vec<&str> = result["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["period"].as_str().unwrap())
+        .collect();
+    assert_eq!(periods, ["2023-11", "2023-12", "2024-01"]);
+    assert_eq!(result["results"][0]["hit_rate"], 0.0);
+
+    // The limit keeps the most recent periods, still oldest first
+    let result = commands::cmd_play_stats(&mut c, "day", 1, Some(2), false).unwrap();
+    assert_eq!(result["results"][0]["period"], "2023-12-01");
+    assert_eq!(result["results"][1]["period"], "2024-01-02");
+    let result = commands::cmd_play_stats(&mut c, "week", 1, Some(1), false).unwrap();
+    assert_eq!(result["results"][0]["period"], "2024-W01");
+
+    assert_eq!(
+        commands::cmd_play_stats(&mut c, "year", 1, None, false)
+            .unwrap_err()
+            .to_string(),
+        "Invalid grouping: year. Allowed: song, show, day, week, month"
+    );
+}
+
+fn insert_session(conn: &mut Connection, room_name: &str, started_at: i64) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO game_session (id, room_name, started_at, created_at) VALUES (?1, ?2, ?3, ?3)",
+        rusqlite::params![id, room_name, started_at],
+    )
+    .unwrap();
+    id
+}
+
+fn insert_session_play(
+    conn: &mut Connection,
+    session_id: &str,
+    show_id: &str,
+    song_id: &str,
+    correct: Option<bool>,
+    created_at: i64,
+) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO play_history (id, show_id, song_id, created_at, correct_guess, wrong_guess, answer, session_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'guess', ?7)",
+        rusqlite::params![id, show_id, song_id, created_at, correct, correct.map(|c| !c), session_id],
+    )
+    .unwrap();
+    id
+}
+
+#[test]
+fn test_game_sessions_and_songs_by_session_ids() {
+    let mut c = test_conn();
+    let a = insert_artist(&mut c, "ClariS");
+    let s1 = insert_song(&mut c, "Connect", &a);
+    let s2 = insert_song(&mut c, "Colorful", &a);
+    let show = insert_show(&mut c, "Madoka", "Winter 2011");
+    let older = insert_session(&mut c, "Ranked", 1_700_000_000);
+    let newer = insert_session(&mut c, "Friends", 1_700_100_000);
+    insert_session(&mut c, "Empty", 1_600_000_000);
+    let second = insert_session_play(&mut c, &older, &show, &s2, Some(false), 1_700_000_060);
+    let first = insert_session_play(&mut c, &older, &show, &s1, Some(true), 1_700_000_030);
+    insert_session_play(&mut c, &older, &show, &s1, None, 1_700_000_090);
+    insert_session_play(&mut c, &newer, &show, &s1, Some(true), 1_700_100_030);
+
+    let r = commands::cmd_game_sessions(&mut c, 20, false).unwrap();
+    let rooms: Vec<&str> = r["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["room_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(rooms, ["Friends", "Ranked", "Empty"]);
+    let ranked = &r["results"][1];
+    assert_eq!(ranked["play_count"], 3);
+    assert_eq!(ranked["graded_count"], 2);
+    assert_eq!(ranked["correct"], 1);
+    assert_eq!(ranked["wrong"], 1);
+    assert_eq!(ranked["hit_rate"], 0.5);
+    assert_eq!(r["results"][2]["play_count"], 0);
+    assert!(r["results"][2]["hit_rate"].is_null());
+    assert_eq!(
+        commands::cmd_game_sessions(&mut c, 1, false).unwrap()["count"],
+        1
+    );
+
+    let r = commands::cmd_songs_by_session_ids(&mut c, &format!("{newer},{older}"), false).unwrap();
+    assert_eq!(r["count"], 4);
+    // Sessions in start order, plays in play order
+    assert_eq!(r["results"][0]["play_id"], first.as_str());
+    assert_eq!(r["results"][0]["room_name"], "Ranked");
+    assert_eq!(r["results"][0]["correct_guess"], 1);
+    assert_eq!(r["results"][0]["answer"], "guess");
+    assert_eq!(r["results"][1]["play_id"], second.as_str());
+    assert_eq!(r["results"][1]["song_name"], "Colorful");
+    assert_eq!(r["results"][1]["show_name"], "Madoka");
+    assert!(r["results"][2]["correct_guess"].is_null());
+    assert_eq!(r["results"][3]["session_id"], newer.as_str());
+
+    // Joined search from a play to its session
+    let r = commands::cmd_search(
+        &mut c,
+        "play_history",
+        r#"{"game_session.room_name":{"value":"Friends"}}"#,
+        "id,game_session.started_at",
+        &SearchOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(r["results"][0]["game_session.started_at"], 1_700_100_000);
+
+    assert_eq!(
+        commands::cmd_songs_by_session_ids(&mut c, " , ", false)
+            .unwrap_err()
+            .to_string(),
+        "session_ids cannot be empty"
+    );
+}
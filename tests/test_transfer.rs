@@ -0,0 +1,511 @@
+use jankenoboe::commands;
+use rusqlite::Connection;
+
+fn test_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("open in-memory");
+    conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+    conn.execute_batch(include_str!("../docs/init-db.sql"))
+        .unwrap();
+    conn
+}
+
+fn insert_artist(conn: &mut Connection, name: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO artist (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![id, name, now, now],
+    )
+    .unwrap();
+    id
+}
+
+fn insert_song(conn: &mut Connection, name: &str, artist_id: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = jankenoboe::models::now_unix();
+    conn.execute(
+        "INSERT INTO song (id, name, artist_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![id, name, artist_id, now, now],
+    ).unwrap();
+    id
+}
+
+fn read_jsonl(path: &std::path::Path) -> Vec<serde_json::Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+// === EXPORT ===
+
+#[test]
+fn test_export_jsonl_all_tables() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "ClariS");
+    let sid = insert_song(&mut c, "connect", &aid);
+    let deleted = insert_artist(&mut c, "Old");
+    c.execute(
+        "UPDATE artist SET status = 1 WHERE id = ?1",
+        rusqlite::params![deleted],
+    )
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let r = commands::cmd_export(&mut c, None, "jsonl", out, None).unwrap();
+
+    assert_eq!(r["format"], "jsonl");
+    assert_eq!(r["tables"]["artist"]["rows"], 2);
+    assert_eq!(r["tables"]["song"]["rows"], 1);
+    assert_eq!(r["tables"]["learning"]["rows"], 0);
+    assert_eq!(r["total_rows"], 3);
+    assert_eq!(
+        r["tables"].as_object().unwrap().len(),
+        jankenoboe::models::EXPORT_TABLES.len()
+    );
+
+    // Soft-deleted rows are kept, and every selectable field is written
+    let artists = read_jsonl(&dir.path().join("artist.jsonl"));
+    assert_eq!(artists.len(), 2);
+    let old = artists
+        .iter()
+        .find(|a| a["id"] == deleted.as_str())
+        .unwrap();
+    assert_eq!(old["status"], 1);
+    assert!(old["created_at"].is_i64());
+    assert!(old.get("name_context").is_some());
+
+    let songs = read_jsonl(&dir.path().join("song.jsonl"));
+    assert_eq!(songs[0]["id"], sid.as_str());
+    assert_eq!(songs[0]["artist_id"], aid.as_str());
+    assert!(dir.path().join("learning.jsonl").exists());
+}
+
+#[test]
+fn test_export_csv_quotes_fields() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Kalafina, \"the\" trio");
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let r = commands::cmd_export(&mut c, Some("artist"), "csv", out, None).unwrap();
+    assert_eq!(r["tables"].as_object().unwrap().len(), 1);
+
+    let csv = std::fs::read_to_string(dir.path().join("artist.csv")).unwrap();
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(
+        lines[0],
+        "id,name,name_context,created_at,updated_at,status"
+    );
+    assert!(lines[1].starts_with(&format!("{aid},\"Kalafina, \"\"the\"\" trio\",,")));
+    assert!(lines[1].ends_with(",0"));
+}
+
+#[test]
+fn test_export_with_term() {
+    let mut c = test_conn();
+    let a1 = insert_artist(&mut c, "ClariS");
+    let a2 = insert_artist(&mut c, "LiSA");
+    insert_song(&mut c, "connect", &a1);
+    insert_song(&mut c, "crossing field", &a2);
+    insert_song(&mut c, "Gurenge", &a2);
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let term = format!(r#"{{"artist_id": {{"value": "{a2}"}}}}"#);
+    let r = commands::cmd_export(&mut c, Some("song"), "jsonl", out, Some(&term)).unwrap();
+    assert_eq!(r["total_rows"], 2);
+    let songs = read_jsonl(&dir.path().join("song.jsonl"));
+    assert!(songs.iter().all(|s| s["artist_id"] == a2.as_str()));
+}
+
+#[test]
+fn test_export_invalid_input() {
+    let mut c = test_conn();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+
+    let err = commands::cmd_export(&mut c, None, "xml", out, None).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid format: xml. Allowed: jsonl, csv");
+
+    let err = commands::cmd_export(&mut c, Some("artist,bogus"), "jsonl", out, None).unwrap_err();
+    assert!(err.to_string().starts_with("Invalid table: bogus."));
+
+    let err = commands::cmd_export(&mut c, Some(" , "), "jsonl", out, None).unwrap_err();
+    assert_eq!(err.to_string(), "tables cannot be empty");
+
+    let err = commands::cmd_export(
+        &mut c,
+        Some("artist,song"),
+        "jsonl",
+        out,
+        Some(r#"{"name": {"value": "x"}}"#),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "term requires exactly one table in --tables"
+    );
+}
+
+// === IMPORT-DUMP ===
+
+fn write_dump(dir: &std::path::Path, table: &str, rows: &[serde_json::Value]) {
+    let content: String = rows.iter().map(|r| format!("{r}\n")).collect();
+    std::fs::write(dir.join(format!("{table}.jsonl")), content).unwrap();
+}
+
+fn count(conn: &Connection, table: &str) -> i64 {
+    conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0))
+        .unwrap()
+}
+
+#[test]
+fn test_import_dump_round_trip() {
+    let mut src = test_conn();
+    let aid = insert_artist(&mut src, "ClariS");
+    let sid = insert_song(&mut src, "connect", &aid);
+    commands::cmd_learning_batch(&mut src, &sid, None, 0).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    commands::cmd_export(&mut src, None, "jsonl", out, None).unwrap();
+
+    let mut dst = test_conn();
+    let r = commands::cmd_import_dump(&mut dst, out, None, "skip", false).unwrap();
+    assert_eq!(r["inserted"], 3);
+    assert_eq!(r["tables"]["learning"]["inserted"], 1);
+
+    // IDs, timestamps and derived name keys come through
+    let (name_key, created_at): (String, i64) = dst
+        .query_row(
+            "SELECT name_key, created_at FROM artist WHERE id = ?1",
+            rusqlite::params![aid],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(name_key, jankenoboe::normalize::name_key("ClariS"));
+    let src_created: i64 = src
+        .query_row(
+            "SELECT created_at FROM artist WHERE id = ?1",
+            rusqlite::params![aid],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(created_at, src_created);
+    let found = commands::cmd_find(&mut dst, "connect", None, 10, false).unwrap();
+    assert_eq!(found["results"][0]["id"], sid.as_str());
+
+    // A second run finds every row already present
+    let r = commands::cmd_import_dump(&mut dst, out, None, "skip", false).unwrap();
+    assert_eq!(r["inserted"], 0);
+    assert_eq!(r["skipped"], 3);
+}
+
+#[test]
+fn test_import_dump_conflict_policies() {
+    let mut c = test_conn();
+    let aid = insert_artist(&mut c, "Local");
+    c.execute(
+        "UPDATE artist SET updated_at = 2000 WHERE id = ?1",
+        rusqlite::params![aid],
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    let row = |name: &str, updated_at: i64| serde_json::json!({"id": aid, "name": name, "created_at": 1000, "updated_at": updated_at, "status": 0});
+    let name = |c: &Connection| -> String {
+        c.query_row(
+            "SELECT name FROM artist WHERE id = ?1",
+            rusqlite::params![aid],
+            |r| r.get(0),
+        )
+        .unwrap()
+    };
+
+    write_dump(dir.path(), "artist", &[row("Older", 1500)]);
+    let r = commands::cmd_import_dump(&mut c, out, None, "skip", false).unwrap();
+    assert_eq!(r["skipped"], 1);
+    let r = commands::cmd_import_dump(&mut c, out, None, "newest", false).unwrap();
+    assert_eq!(r["skipped"], 1);
+    assert_eq!(name(&c), "Local");
+
+    write_dump(dir.path(), "artist", &[row("Newer", 3000)]);
+    let r = commands::cmd_import_dump(&mut c, out, None, "newest", false).unwrap();
+    assert_eq!(r["updated"], 1);
+    assert_eq!(name(&c), "Newer");
+
+    write_dump(dir.path(), "artist", &[row("Forced", 1)]);
+    let r = commands::cmd_import_dump(&mut c, out, None, "overwrite", false).unwrap();
+    assert_eq!(r["updated"], 1);
+    assert_eq!(name(&c), "Forced");
+    let key: String = c
+        .query_row(
+            "SELECT name_key FROM artist WHERE id = ?1",
+            rusqlite::params![aid],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(key, jankenoboe::normalize::name_key("Forced"));
+}
+
+#[test]
+fn test_import_dump_missing_reference_rolls_back() {
+    let mut c = test_conn();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+    write_dump(
+        dir.path(),
+        "artist",
+        &[
+            serde_json::json!({"id": "a1", "name": "A", "created_at": 1, "updated_at": 1, "status": 0}),
+        ],
+    );
+    write_dump(
+        dir.path(),
+        "song",
+        &[
+            serde_json::json!({"id": "s1", "name": "S1", "artist_id": "a1", "created_at": 1, "updated_at": 1, "status": 0}),
+            serde_json::json!({"id": "s2", "name": "S2", "artist_id": "nope", "created_at": 1, "updated_at": 1, "status": 0}),
+        ],
+    );
+
+    let err = commands::cmd_import_dump(&mut c, out, None, "skip", false).unwrap_err();
+    let song_file = dir.path().join("song.jsonl");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{}:2: artist_id not found: artist/nope",
+            song_file.to_string_lossy()
+        )
+    );
+    assert_eq!(count(&c, "artist"), 0);
+    assert_eq!(count(&c, "song"), 0);
+
+    // Limiting to the valid table imports it alone
+    let r = commands::cmd_import_dump(&mut c, out, Some("artist"), "skip", false).unwrap();
+    assert_eq!(r["inserted"], 1);
+}
+
+#[test]
+fn test_import_dump_dry_run_and_invalid_input() {
+    let mut c = test_conn();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().to_str().unwrap();
+
+    let err = commands::cmd_import_dump(&mut c, out, None, "skip", false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("No .jsonl export files found in {out}")
+    );
+    let err = commands::cmd_import_dump(&mut c, out, Some("song"), "skip", false).unwrap_err();
+    assert!(err.to_string().starts_with("File not found: "));
+    let err = commands::cmd_import_dump(&mut c, out, None, "merge", false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid conflict policy: merge. Allowed: skip, overwrite, newest"
+    );
+
+    write_dump(
+        dir.path(),
+        "artist",
+        &[
+            serde_json::json!({"id": "a1", "name": "A", "created_at": 1, "updated_at": 1, "status": 0}),
+        ],
+    );
+    let r = commands::cmd_import_dump(&mut c, out, None, "skip", true).unwrap();
+    assert_eq!(r["dry_run"], true);
+    assert_eq!(r["inserted"], 1);
+    assert_eq!(count(&c, "artist"), 0);
+
+    write_dump(
+        dir.path(),
+        "artist",
+        &[serde_json::json!({"id": "a1", "name": "A", "name_key": "a"})],
+    );
+    let err = commands::cmd_import_dump(&mut c, out, None, "skip", false).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(":1: Invalid field for artist: name_key. Allowed: id, name")
+    );
+    write_dump(dir.path(), "artist", &[serde_json::json!({"name": "A"})]);
+    let err = commands::cmd_import_dump(&mut c, out, None, "skip", false).unwrap_err();
+    assert!(err.to_string().ends_with(":1: id is required"));
+}
+
+// === SYNC ===
+
+fn temp_db() -> (tempfile::NamedTempFile, Connection) {
+    let tmp = tempfile::NamedTempFile::new().unwrap();
+    let conn = Connection::open(tmp.path()).unwrap();
+    conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+    conn.execute_batch(include_str!("../docs/init-db.sql"))
+        .unwrap();
+    (tmp, conn)
+}
+
+fn insert_learning(conn: &Connection, id: &str, song_id: &str, level: i64, reviewed_at: i64) {
+    conn.execute(
+        "INSERT INTO learning (id, song_id, level, created_at, updated_at, last_level_up_at, level_up_path, graduated) \
+         VALUES (?1, ?2, ?3, 1, ?4, ?4, '[1,2,3,4,5,6,7,8,9,10]', 0)",
+        rusqlite::params![id, song_id, level, reviewed_at],
+    )
+    .unwrap();
+}
+
+fn copy_row(from: &Connection, to: &Connection, table: &str, id: &str) {
+    let path: String = from
+        .query_row("PRAGMA database_list", [], |r| r.get(2))
+        .unwrap();
+    to.execute("ATTACH DATABASE ?1 AS src", rusqlite::params![path])
+        .unwrap();
+    to.execute(
+        &format!("INSERT INTO main.{table} SELECT * FROM src.{table} WHERE id = ?1"),
+        rusqlite::params![id],
+    )
+    .unwrap();
+    to.execute("DETACH DATABASE src", []).unwrap();
+}
+
+#[test]
+fn test_sync_copies_both_ways_and_merges_learning() {
+    let (_main_file, mut main) = temp_db();
+    let (other_file, mut other) = temp_db();
+    let other_path = other_file.path().to_str().unwrap();
+
+    // Shared song, learned on both machines
+    let aid = insert_artist(&mut main, "ClariS");
+    let sid = insert_song(&mut main, "connect", &aid);
+    copy_row(&main, &other, "artist", &aid);
+    copy_row(&main, &other, "song", &sid);
+    insert_learning(&main, "l1", &sid, 3, 1000);
+    insert_learning(&other, "l1", &sid, 5, 900);
+
+    // One artist only on each side, one renamed later on other
+    let only_main = insert_artist(&mut main, "LiSA");
+    let only_other = insert_artist(&mut other, "Aimer");
+    other
+        .execute(
+            "UPDATE artist SET name = 'ClariS (duo)', updated_at = updated_at + 10 WHERE id = ?1",
+            rusqlite::params![aid],
+        )
+        .unwrap();
+
+    let r = commands::cmd_sync(&mut main, other_path, false).unwrap();
+    assert_eq!(r["tables"]["artist"]["copied_to_main"], 1);
+    assert_eq!(r["tables"]["artist"]["copied_to_other"], 1);
+    assert_eq!(r["tables"]["artist"]["updated_main"], 1);
+    assert_eq!(r["tables"]["learning"]["updated_main"], 1);
+    assert_eq!(r["conflict_count"], 0);
+
+    for conn in [&main, &other] {
+        assert_eq!(count(conn, "artist"), 3);
+        let ids: Vec<String> = conn
+            .prepare("SELECT id FROM artist")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert!(ids.contains(&only_main) && ids.contains(&only_other));
+        let name: String = conn
+            .query_row(
+                "SELECT name FROM artist WHERE id = ?1",
+                rusqlite::params![aid],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(name, "ClariS (duo)");
+        // The more advanced record wins even though it was reviewed earlier
+        let level: i64 = conn
+            .query_row("SELECT level FROM learning WHERE id = 'l1'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(level, 5);
+    }
+
+    // A second sync has nothing left to do
+    let r = commands::cmd_sync(&mut main, other_path, false).unwrap();
+    assert_eq!(r["tables"]["artist"]["copied_to_main"], 0);
+    assert_eq!(r["tables"]["learning"]["updated_other"], 0);
+}
+
+#[test]
+fn test_sync_reports_conflicts() {
+    let (_main_file, mut main) = temp_db();
+    let (other_file, other) = temp_db();
+    let other_path = other_file.path().to_str().unwrap();
+
+    let aid = insert_artist(&mut main, "ClariS");
+    let sid = insert_song(&mut main, "connect", &aid);
+    copy_row(&main, &other, "artist", &aid);
+    copy_row(&main, &other, "song", &sid);
+
+    // Same updated_at, different names
+    other
+        .execute(
+            "UPDATE artist SET name = 'Claris' WHERE id = ?1",
+            rusqlite::params![aid],
+        )
+        .unwrap();
+    // Both machines started learning the song under different records
+    insert_learning(&main, "l-main", &sid, 1, 1000);
+    insert_learning(&other, "l-other", &sid, 2, 1000);
+
+    let r = commands::cmd_sync(&mut main, other_path, false).unwrap();
+    assert_eq!(r["tables"]["artist"]["conflicts"], 1);
+    assert_eq!(r["tables"]["learning"]["conflicts"], 2);
+    assert_eq!(r["conflict_count"], 3);
+    let artist_conflict = &r["conflicts"][0];
+    assert_eq!(artist_conflict["table"], "artist");
+    assert_eq!(artist_conflict["id"], aid.as_str());
+    assert_eq!(
+        artist_conflict["reason"],
+        "rows differ with the same updated_at"
+    );
+    assert_eq!(artist_conflict["main"]["name"], "ClariS");
+    assert_eq!(artist_conflict["other"]["name"], "Claris");
+    assert!(
+        r["conflicts"][1]["reason"]
+            .as_str()
+            .unwrap()
+            .starts_with("song already has an active learning record on other: ")
+    );
+
+    // Conflicting rows are left untouched
+    assert_eq!(count(&main, "learning"), 1);
+    assert_eq!(count(&other, "learning"), 1);
+}
+
+#[test]
+fn test_sync_dry_run_and_invalid_input() {
+    let (main_file, mut main) = temp_db();
+    let (other_file, other) = temp_db();
+    let other_path = other_file.path().to_str().unwrap();
+    insert_artist(&mut main, "ClariS");
+
+    let r = commands::cmd_sync(&mut main, other_path, true).unwrap();
+    assert_eq!(r["dry_run"], true);
+    assert_eq!(r["tables"]["artist"]["copied_to_other"], 1);
+    assert_eq!(count(&other, "artist"), 0);
+
+    let err = commands::cmd_sync(&mut main, "/nonexistent/other.db", false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Database file not found: /nonexistent/other.db"
+    );
+    let err = commands::cmd_sync(&mut main, main_file.path().to_str().unwrap(), false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "other database is the same file as JANKENOBOE_DB"
+    );
+
+    other.execute_batch("DROP TABLE show_alias;").unwrap();
+    let err = commands::cmd_sync(&mut main, other_path, false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "other database is missing show_alias.id; run doctor --fix on it first"
+    );
+}